@@ -0,0 +1,52 @@
+//! Benchmarks of the searches that the `parallel` feature runs on all cores.
+//!
+//! Without the feature, each search runs once on one thread. With
+//! `cargo bench --features parallel`, each search also runs on a one-thread pool,
+//! for comparison with the default pool using all cores.
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::hint::black_box;
+use ternary::ji::solve_step_sig_slow;
+use ternary::ji_ratio::RawJiRatio;
+use ternary::monzo::Monzo;
+use ternary::{SigFilter, sig_to_result};
+
+type Search = (&'static str, fn());
+
+const SEARCHES: [Search; 2] = [
+    ("sig_to_result 6L3m3s", || {
+        sig_to_result(
+            black_box(&[6, 3, 3]),
+            &SigFilter::default(),
+            RawJiRatio::OCTAVE,
+            53,
+            20.0,
+            250.0,
+        );
+    }),
+    ("solve_step_sig_slow 5L2m2s", || {
+        solve_step_sig_slow(black_box(&[5, 2, 2]), Monzo::OCTAVE, 20.0, 250.0);
+    }),
+];
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parallel");
+    group.sample_size(10);
+    for (name, search) in SEARCHES {
+        #[cfg(feature = "parallel")]
+        {
+            let one_thread = rayon::ThreadPoolBuilder::new()
+                .num_threads(1)
+                .build()
+                .unwrap();
+            group.bench_function(format!("{name} (1 thread)"), |b| {
+                b.iter(|| one_thread.install(search))
+            });
+        }
+        group.bench_function(name, |b| b.iter(search));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,26 @@
+//! Generates the C header `ternary.h` in `OUT_DIR` when the `ffi` feature is enabled.
+//!
+//! The committed copy in `include/ternary.h` is checked against it by `tests/ffi.rs`,
+//! which also updates it when run with `TERNARY_UPDATE_HEADER=1`.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "ffi")]
+    generate_c_header();
+}
+
+#[cfg(feature = "ffi")]
+fn generate_c_header() {
+    let crate_dir = std::path::PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml"))
+        .expect("cbindgen.toml should be valid");
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(crate_dir.join("src/ffi.rs"))
+        .generate()
+        .expect("the C API should be expressible in C")
+        .write_to_file(out_dir.join("ternary.h"));
+}
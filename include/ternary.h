@@ -0,0 +1,279 @@
+/* Generated by cbindgen from src/ffi.rs when building with the `ffi` feature. Do not edit. */
+
+#ifndef TERNARY_H
+#define TERNARY_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The result of a call into the C API.
+typedef enum TernaryStatus {
+  // The call succeeded.
+  TERNARY_STATUS_OK = 0,
+  // A required pointer argument was null.
+  TERNARY_STATUS_NULL_POINTER = 1,
+  // A string argument was not valid UTF-8.
+  TERNARY_STATUS_INVALID_UTF8 = 2,
+  // A scale word was empty or contained characters that aren't step letters.
+  TERNARY_STATUS_INVALID_WORD = 3,
+  // A step size couldn't be read, or a tuning has fewer step sizes than the word has letters.
+  TERNARY_STATUS_INVALID_TUNING = 4,
+  // A numeric argument was out of its valid range.
+  TERNARY_STATUS_INVALID_ARGUMENT = 5,
+  // An index was out of bounds.
+  TERNARY_STATUS_OUT_OF_RANGE = 6,
+  // The library panicked; this is a bug.
+  TERNARY_STATUS_PANIC = 7,
+} TernaryStatus;
+
+// The handedness of a scale word; see [`Chirality`].
+typedef enum TernaryChirality {
+  TERNARY_CHIRALITY_LEFT = 0,
+  TERNARY_CHIRALITY_ACHIRAL = 1,
+  TERNARY_CHIRALITY_RIGHT = 2,
+} TernaryChirality;
+
+// An opaque step tuning.
+typedef struct TernaryTuning TernaryTuning;
+
+// An opaque list of step tunings.
+typedef struct TernaryTuningList TernaryTuningList;
+
+// An opaque scale word.
+typedef struct TernaryWord TernaryWord;
+
+// The properties of a ternary scale word, as in [`crate::ScaleProfile`].
+typedef struct TernaryProfile {
+  // The number of L, m and s steps.
+  size_t step_sig[3];
+  // The maximum variety.
+  uint16_t max_variety;
+  // The chirality.
+  enum TernaryChirality chirality;
+  // Whether the scale is an L=m monotone MOS.
+  bool lm;
+  // Whether the scale is an m=s monotone MOS.
+  bool ms;
+  // Whether the scale is an s=0 monotone MOS.
+  bool s0;
+  // Whether the scale is a MOS substitution scale aL(bmcs).
+  bool subst_l_ms;
+  // Whether the scale is a MOS substitution scale bm(aLcs).
+  bool subst_m_ls;
+  // Whether the scale is a MOS substitution scale cs(aLbm).
+  bool subst_s_lm;
+  // The temperament-agnostic ED join.
+  int32_t ed_join[3];
+  // The complexity of the simplest guide frame, or 0 if there is none.
+  uint16_t guide_complexity;
+  // The multiplicity of the simplest guide frame, or 0 if there is none.
+  uint16_t guide_multiplicity;
+  // Whether the pitch class lattice has a unimodular basis.
+  bool has_lattice_basis;
+} TernaryProfile;
+
+// A placement of a scale on the MIDI keyboard; see [`KeyboardMapping`].
+typedef struct TernaryKeyboardMapping {
+  // The degree of the scale word the mode starts on.
+  size_t mode;
+  // The MIDI note playing the tonic of the mode.
+  uint8_t root_note;
+  // The MIDI note with a given frequency.
+  uint8_t reference_note;
+  // The frequency of `reference_note` in Hz.
+  double reference_frequency;
+} TernaryKeyboardMapping;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// A static, NUL-terminated description of `status`. The string must not be freed.
+const char *ternary_status_message(enum TernaryStatus status);
+
+// Release a string returned by the library. Does nothing for null.
+//
+// # Safety
+//
+// `s` must be null or a string returned by the library that hasn't been freed yet.
+void ternary_string_free(char *s);
+
+// Release a byte buffer returned by the library. Does nothing for null.
+//
+// # Safety
+//
+// `bytes` must be null or a buffer returned by the library with length `len` that hasn't been
+// freed yet.
+void ternary_bytes_free(uint8_t *bytes, size_t len);
+
+// Read a scale word written with step letters, such as `"LmLsLmLsL"`.
+//
+// # Safety
+//
+// `word` must be a NUL-terminated string and `out` must be valid for writes.
+enum TernaryStatus ternary_word_new(const char *word, struct TernaryWord **out);
+
+// Release a scale word. Does nothing for null.
+//
+// # Safety
+//
+// `word` must be null or a live handle from [`ternary_word_new`].
+void ternary_word_free(struct TernaryWord *word);
+
+// The number of notes of a scale word, or 0 for null.
+//
+// # Safety
+//
+// `word` must be null or a live handle.
+size_t ternary_word_len(const struct TernaryWord *word);
+
+// The brightest mode of a scale word, written with step letters.
+//
+// # Safety
+//
+// `word` must be a live handle and `out` must be valid for writes.
+enum TernaryStatus ternary_word_brightest_mode(const struct TernaryWord *word, char **out);
+
+// The profile of a ternary scale word.
+//
+// # Safety
+//
+// `word` must be a live handle and `out` must be valid for writes.
+enum TernaryStatus ternary_word_profile(const struct TernaryWord *word, struct TernaryProfile *out);
+
+// The full profile of a ternary scale word as JSON, with the same shape as in WASM.
+//
+// # Safety
+//
+// `word` must be a live handle and `out` must be valid for writes.
+enum TernaryStatus ternary_word_profile_json(const struct TernaryWord *word, char **out);
+
+// Read a tuning from `count` step size strings such as `"9/8"`, `"4\\22"` or `"203.9c"`,
+// one for each step letter.
+//
+// # Safety
+//
+// `steps` must point to `count` NUL-terminated strings and `out` must be valid for writes.
+enum TernaryStatus ternary_tuning_new(const char *const *steps,
+                                      size_t count,
+                                      struct TernaryTuning **out);
+
+// Release a tuning. Does nothing for null.
+//
+// # Safety
+//
+// `tuning` must be null or a live handle.
+void ternary_tuning_free(struct TernaryTuning *tuning);
+
+// The number of step sizes of a tuning, or 0 for null.
+//
+// # Safety
+//
+// `tuning` must be null or a live handle.
+size_t ternary_tuning_arity(const struct TernaryTuning *tuning);
+
+// The size in cents of step `letter` of a tuning.
+//
+// # Safety
+//
+// `tuning` must be a live handle and `out` must be valid for writes.
+enum TernaryStatus ternary_tuning_step_cents(const struct TernaryTuning *tuning,
+                                             size_t letter,
+                                             double *out);
+
+// Step `letter` of a tuning as a string such as `"9/8"` or `"4\\22"`, as in SonicWeave.
+//
+// # Safety
+//
+// `tuning` must be a live handle and `out` must be valid for writes.
+enum TernaryStatus ternary_tuning_step_string(const struct TernaryTuning *tuning,
+                                              size_t letter,
+                                              char **out);
+
+// ED tunings of a ternary scale word's step signature, with at most `ed_bound` steps to the
+// equave `equave_numer/equave_denom` and the small step between `s_lower` and `s_upper` cents.
+//
+// # Safety
+//
+// `word` must be a live handle and `out` must be valid for writes.
+enum TernaryStatus ternary_ed_tunings(const struct TernaryWord *word,
+                                      uint32_t equave_numer,
+                                      uint32_t equave_denom,
+                                      int32_t ed_bound,
+                                      double s_lower,
+                                      double s_upper,
+                                      struct TernaryTuningList **out);
+
+// JI tunings of a ternary scale word's step signature to the equave `equave_numer/equave_denom`,
+// with steps between `s_lower` and `s_upper` cents.
+//
+// # Safety
+//
+// `word` must be a live handle and `out` must be valid for writes.
+enum TernaryStatus ternary_ji_tunings(const struct TernaryWord *word,
+                                      uint32_t equave_numer,
+                                      uint32_t equave_denom,
+                                      double s_lower,
+                                      double s_upper,
+                                      struct TernaryTuningList **out);
+
+// Release a tuning list. Does nothing for null.
+//
+// # Safety
+//
+// `list` must be null or a live handle.
+void ternary_tuning_list_free(struct TernaryTuningList *list);
+
+// The number of tunings in a list, or 0 for null.
+//
+// # Safety
+//
+// `list` must be null or a live handle.
+size_t ternary_tuning_list_len(const struct TernaryTuningList *list);
+
+// A copy of tuning `index` of a list, to be released with [`ternary_tuning_free`].
+//
+// # Safety
+//
+// `list` must be a live handle and `out` must be valid for writes.
+enum TernaryStatus ternary_tuning_list_get(const struct TernaryTuningList *list,
+                                           size_t index,
+                                           struct TernaryTuning **out);
+
+// The contents of a Scala `.scl` file for a scale word under a tuning.
+//
+// # Safety
+//
+// `word` and `tuning` must be live handles and `out` must be valid for writes.
+enum TernaryStatus ternary_scl(const struct TernaryWord *word,
+                               const struct TernaryTuning *tuning,
+                               char **out);
+
+// The default keyboard mapping: the tonic on middle C at its 12edo frequency.
+struct TernaryKeyboardMapping ternary_keyboard_mapping_default(void);
+
+// A MIDI Tuning Standard bulk tuning dump of a scale word under a tuning. A null `mapping` uses
+// [`ternary_keyboard_mapping_default`] and a null `name` uses the scale word. The message is
+// released with [`ternary_bytes_free`].
+//
+// # Safety
+//
+// `word` and `tuning` must be live handles, `mapping` and `name` must be null or valid, and
+// `out` and `out_len` must be valid for writes.
+enum TernaryStatus ternary_mts_bulk_dump(const struct TernaryWord *word,
+                                         const struct TernaryTuning *tuning,
+                                         const struct TernaryKeyboardMapping *mapping,
+                                         uint8_t device_id,
+                                         uint8_t program,
+                                         const char *name,
+                                         uint8_t **out,
+                                         size_t *out_len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* TERNARY_H */
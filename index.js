@@ -9,6 +9,10 @@ const UNOCCUPIED_DOT_RADIUS = 7;
 const EDGE_WIDTH = 2;
 
 const GROUND_INDIGO = "#76f";
+// the JI interval the lattice bases can be ranked by closeness to
+const NEAR_INTERVAL = "3/2";
+// fill colors of the layers of scales without a 2D lattice
+const LAYER_COLORS = ["white", "#fc6", "#6cf", "#f9c", "#9f9"];
 
 // Default bounds for tuning search
 const DEFAULT_ED_BOUND = 111;
@@ -102,6 +106,103 @@ function parseEquaveToCents(ratioStr) {
   return 1200 * Math.log2(numerator / denominator);
 }
 
+/**
+ * The text of an error thrown by the WASM module, which throws `{code, field, message}` objects
+ * for invalid input
+ */
+function errorMessage(err) {
+  return err && err.message ? err.message : String(err);
+}
+
+// Units of work per chunk of a resumable search in each stage: scales found or filtered
+// for `SigSearch`, choices of the first interval class for `JiTuningSearch`
+const SIG_SEARCH_BUDGETS = { enumerate: 4096, filter: 20, tunings: 1 };
+const JI_TUNING_SEARCH_BUDGETS = { solve: 1 };
+// Scales per page of the scale table
+const SCALE_PAGE_SIZE = 50;
+// The abort reason of a search stopped by the user
+const STOPPED = "stopped";
+
+const SEARCH_STAGES = {
+  enumerate: "Enumerating scales",
+  filter: "Filtering scales",
+  tunings: "Computing tunings",
+  solve: "Solving for JI tunings",
+};
+
+/**
+ * Drive a resumable WASM search (`SigSearch` or `JiTuningSearch`) in chunks of `budgets[stage]`
+ * units, where `stage` is the stage the search is at (the smallest budget before the first chunk),
+ * yielding to the event loop between chunks so the page stays responsive.
+ * `onProgress` gets each `{stage, done, total}`. Returns true once the search has finished,
+ * leaving the caller to read its result and free it, or frees the search and returns false
+ * if `signal` was aborted first.
+ */
+async function runSearch(search, budgets, onProgress, signal) {
+  let finished = false;
+  let budget = Math.min(...Object.values(budgets));
+  try {
+    for (;;) {
+      const progress = search.step(budget);
+      if (progress.stage === "done") {
+        finished = true;
+        return true;
+      }
+      budget = budgets[progress.stage];
+      onProgress(progress);
+      await new Promise((resolve) => setTimeout(resolve, 0));
+      if (signal.aborted) {
+        return false;
+      }
+    }
+  } finally {
+    if (!finished) {
+      search.free();
+    }
+  }
+}
+
+/**
+ * The text explaining why a scale has no 2D lattice and how it is drawn instead
+ */
+function describeLatticeFallback(fallback) {
+  const reason = fallback.reason;
+  switch (reason.kind) {
+    case "torsion":
+      return `This scale repeats ${reason.periods} times per equave, so its pitch classes have torsion and no 2D lattice exists. Pitches are drawn in ${reason.periods} layers, one per period, shifted and colored by layer.`;
+    case "no_guide_frame":
+      return "This scale has no guide frame, so the basis below completes the equave to a lattice basis without being generators of the scale.";
+    default:
+      return "No pair of guide frame vectors forms a lattice basis with the equave, so the basis below completes the equave without being generators of the scale.";
+  }
+}
+
+/**
+ * The text describing the progress `{stage, done, total}` of a search
+ */
+function progressText(progress) {
+  const stage = SEARCH_STAGES[progress.stage] ?? "Computing";
+  return progress.total
+    ? `${stage} (${progress.done}/${progress.total})...`
+    : `${stage}...`;
+}
+
+/**
+ * Show a search's progress in the status element, with a button aborting `controller`.
+ * Returns the function to call with each progress update.
+ */
+function showSearchStatus(controller) {
+  const text = document.createElement("span");
+  text.textContent = "Computing... ";
+  const stopButton = document.createElement("button");
+  stopButton.textContent = "Stop";
+  stopButton.addEventListener("click", () => controller.abort(STOPPED));
+  statusElement.replaceChildren(text, stopButton);
+  return (progress) => {
+    text.textContent = `${progressText(progress)} `;
+  };
+}
+
 /**
  * Get the current equave ratio string from the input field (normalized)
  * Returns { ratio: "m/n", num: m, den: n }
@@ -189,22 +290,22 @@ function addMultipleOfFirstRowToSecond(arr, _, n, i1, i2, coeff) {
 }
 
 // Makes a table in `tableElement` with the given `data`.
-function makeTable(tableElement, data, header = "") {
+function makeTable(tableElement, data, header = "", start = 0) {
   const tableViewTr = document.createElement("tr");
-  let tableView = tableContent(data, header);
+  let tableView = tableContent(data, header, start);
   tableViewTr.appendChild(tableView);
   tableElement.appendChild(tableViewTr);
 }
 
-// Return a new table view
-function tableContent(data, header = "") {
+// Return a new table view, numbering the rows from `start + 1`
+function tableContent(data, header = "", start = 0) {
   const table = tableHead(data, header);
   const tbody = table.createTBody();
   if (data[0] instanceof Array) {
     for (const [i] of data.entries()) {
       let row = tbody.insertRow();
       let cell1 = row.insertCell();
-      cell1.appendChild(document.createTextNode(`${i + 1}`)); // row numbering
+      cell1.appendChild(document.createTextNode(`${start + i + 1}`)); // row numbering
       for (const value of data[i].values()) {
         // iterate over columns
         let td = document.createElement("td");
@@ -216,7 +317,7 @@ function tableContent(data, header = "") {
     for (let i = 0; i < data.length; i++) {
       let row = tbody.insertRow();
       let cell1 = row.insertCell();
-      cell1.appendChild(document.createTextNode(`${i + 1}`)); // row numbering
+      cell1.appendChild(document.createTextNode(`${start + i + 1}`)); // row numbering
       for (const value of Object.values(data[i])) {
         // iterate over columns
         let td = document.createElement("td");
@@ -228,7 +329,7 @@ function tableContent(data, header = "") {
     for (let i = 0; i < data.length; i++) {
       let row = tbody.insertRow();
       let cell1 = row.insertCell();
-      cell1.appendChild(document.createTextNode(`${i + 1}`)); //row numbering
+      cell1.appendChild(document.createTextNode(`${start + i + 1}`)); //row numbering
       let td = document.createElement("td");
       td.appendChild(document.createTextNode(data[i]));
       row.appendChild(td);
@@ -316,7 +417,29 @@ import("./pkg")
         fill: black;
       }`;
           if (state.word) {
-            if (state.latticeBasis) {
+            // Get lattice coordinates and basis directly from WASM
+            const latticeResult = wasm.word_to_lattice(state.word);
+            if (latticeResult) {
+              // Scales without a 2D lattice are drawn in layers
+              const fallback = latticeResult.fallback;
+              // Other bases showing the scale as a parallelogram, if any
+              const choice =
+                state.latticeChoice?.word === state.word
+                  ? state.latticeChoice
+                  : { word: state.word, score: "fifth", index: -1 };
+              state.latticeChoice = choice;
+              const bases = fallback ? [] : latticeBases(state);
+              const chosen = bases[choice.index];
+              if (chosen) {
+                latticeResult.coordinates = chosen.coordinates;
+                latticeResult.basis = [chosen.basis.vx, chosen.basis.vy];
+              }
+              // Update the state with the better basis
+              state.latticeBasis = latticeResult.basis;
+              const layerCount =
+                fallback && fallback.projection.kind === "layers"
+                  ? fallback.projection.count
+                  : 1;
               let n = state.word.length;
               [A, B, C, D] = [1, 0, 0, 1];
 
@@ -357,18 +480,15 @@ import("./pkg")
 
               // Track cumulative step counts for pitch calculation
               let stepCounts = { L: 0, m: 0, s: 0 };
-              // Get lattice coordinates and basis directly from WASM
-              const latticeResult = wasm.word_to_lattice(state.word);
-              if (latticeResult && latticeResult.basis) {
-                // Update the state with the better basis
-                state.latticeBasis = latticeResult.basis;
-              }
               const latticeCoords = latticeResult.coordinates;
               for (let deg = 0; deg < latticeCoords.length; ++deg) {
                 // Get coordinates directly from WASM-computed lattice
                 const [latticeX, latticeY] = latticeCoords[deg];
-                const currentX = ORIGIN_X + latticeX * SPACING_X;
-                const currentY = ORIGIN_Y + latticeY * SPACING_Y;
+                // Shift each layer along the diagonal, off the lattice points
+                const layer = fallback ? fallback.coordinates[deg][0] : 0;
+                const shift = layer / layerCount;
+                const currentX = ORIGIN_X + (latticeX + shift) * SPACING_X;
+                const currentY = ORIGIN_Y + (latticeY + shift) * SPACING_Y;
 
                 const { pitch, cents } = getPitchInfo(
                   stepCounts,
@@ -386,11 +506,11 @@ import("./pkg")
               cx="${currentX}"
               cy="${currentY}"
               r="${UNOCCUPIED_DOT_RADIUS}"
-              fill="white"
+              fill="${LAYER_COLORS[layer % LAYER_COLORS.length]}"
               stroke="white"
               stroke-width="1"
             >
-              <title>${tooltipText}</title>
+              <title>${tooltipText}${layerCount > 1 ? ` (layer ${layer})` : ""}</title>
             </circle>
             <text
               x="${currentX - 3}"
@@ -406,7 +526,9 @@ import("./pkg")
               }
               // We deferred appending elements until now
               // Initial viewBox will be set by updateViewBox() below
-              latticeElement.innerHTML += `<hr/><h2>Lattice view</h2><br/><small>Ternary scales are special in that they admit a JI-agnostic 2D lattice representation.</small>`;
+              latticeElement.innerHTML += fallback
+                ? `<hr/><h2>Lattice view</h2><br/><small>${describeLatticeFallback(fallback)}</small>`
+                : `<hr/><h2>Lattice view</h2><br/><small>Ternary scales are special in that they admit a JI-agnostic 2D lattice representation.</small>`;
               latticeElement.innerHTML += `<br/><small>Hover over the dots to see pitch information. Click and drag to pan, use mouse wheel or buttons to zoom.</small>`;
               // Add zoom buttons
               latticeElement.innerHTML += `<div class="controls">
@@ -415,7 +537,24 @@ import("./pkg")
         <button id="reset-view">Reset View</button>
         <span style="margin-left: 20px;">Zoom: <span id="zoom-level">100%</span></span>
     </div>`;
+              if (bases.length > 0) {
+                latticeElement.innerHTML += `<div class="controls">
+        Rank bases by <select id="lattice-basis-score">
+          <option value="fifth">fifths and fourths</option>
+          <option value="shortest">shortest vectors</option>
+          <option value="guide_frames">guide frame generators</option>
+          <option value="near_interval">closeness to 3/2 in this tuning</option>
+        </select>
+        <select id="lattice-basis">
+          <option value="-1">default basis</option>
+          ${bases.map((b, i) => `<option value="${i}">${i + 1}. ${displayStepVector(b.basis.vx)}, ${displayStepVector(b.basis.vy)}</option>`).join("")}
+        </select>
+    </div>`;
+              }
               latticeElement.innerHTML += `Lattice basis:<br/>[gx, gy] = [${alsoInCurrentTuning(state.latticeBasis[0], state.tuning, equave)}, ${alsoInCurrentTuning(state.latticeBasis[1], state.tuning, equave)}]`;
+              if (layerCount > 1) {
+                latticeElement.innerHTML += `<br/>Period = ${alsoInCurrentTuning(fallback.basis[0], state.tuning, equave)}`;
+              }
               latticeElement.appendChild(svgTag);
 
               // Zoom functionality
@@ -625,6 +764,25 @@ import("./pkg")
               }
 
               // Button functions
+              // Basis choice
+              const scoreSelect = document.getElementById("lattice-basis-score");
+              const basisSelect = document.getElementById("lattice-basis");
+              if (scoreSelect && basisSelect) {
+                scoreSelect.value = choice.score;
+                basisSelect.value = `${choice.index}`;
+                scoreSelect.addEventListener("change", () => {
+                  state.latticeChoice = {
+                    word: state.word,
+                    score: scoreSelect.value,
+                    index: 0,
+                  };
+                  createLatticeView(state, equave);
+                });
+                basisSelect.addEventListener("change", () => {
+                  choice.index = Number(basisSelect.value);
+                  createLatticeView(state, equave);
+                });
+              }
               zoomInButton.addEventListener("click", () => zoomBy(0.8));
               zoomOutButton.addEventListener("click", () => zoomBy(1.25));
               resetViewButton.addEventListener("click", () => {
@@ -640,7 +798,7 @@ import("./pkg")
               // Initialize
               updateViewBox();
             } else {
-              // No lattice basis available - show a message instead of throwing
+              // Only the empty word has no lattice
               latticeElement.innerHTML = `<hr/><h2>Lattice view</h2><br/><small>No suitable lattice basis found for this scale.</small>`;
             }
           } else {
@@ -654,7 +812,6 @@ import("./pkg")
     // Function for showing the SonicWeave code
     function showSonicWeaveCode(state) {
       if (state.word) {
-        const arity = new Set(Array.from(state.word)).size;
         if (state.tuning) {
           const element = document.getElementById("sw-code");
           if (element) {
@@ -697,25 +854,16 @@ import("./pkg")
             });
 
             let codeblock = document.getElementById("codeblock");
-            const arr = Array.from(state.word);
             if (codeblock) {
-              codeblock.innerHTML =
-                arity === 3
-                  ? `let L = ${state.tuning[0]}
-let m = ${state.tuning[1]}
-let s = ${state.tuning[2]}
-${arr.join(";")};
-stack()`
-                  : arity === 2
-                    ? `let L = ${state.tuning[0]}
-let s = ${state.tuning[1]}
-${arr.join(";")};
-stack()`
-                    : arity === 1
-                      ? `let X = ${state.tuning[0]}
-${arr.join(";")};
-stack()`
-                      : "Scales of rank > 3 are not supported";
+              try {
+                codeblock.innerHTML = wasm.word_to_sonicweave(
+                  state.word,
+                  state.tuning,
+                  0,
+                );
+              } catch (err) {
+                codeblock.innerHTML = `Error: ${errorMessage(err)}`;
+              }
             }
           }
         }
@@ -732,6 +880,11 @@ stack()`
         if (state.profile) {
           // const ploidacot = state.profile["ploidacot"];
 
+          // Name (only if the scale has one)
+          if (state.profile["name"]) {
+            el.innerHTML += `Name: ${escapeHtml(state.profile["name"])}<br/>`;
+          }
+
           const [ed1, ed2, ed3] = state.profile["ed_join"];
           // Ed join (always shown)
           el.innerHTML += `Temp-agnostic ed join: ${ed1} & ${ed2} & ${ed3}<br/>`;
@@ -803,6 +956,29 @@ stack()`
       }
     }
 
+    /**
+     * The bases showing the current scale as a parallelogram substring,
+     * best first by the score in `state.latticeChoice`
+     */
+    function latticeBases(state) {
+      const score = state.latticeChoice.score;
+      // Closeness to an interval needs a tuning
+      if (score !== "near_interval" || !state.tuning) {
+        return wasm.word_to_lattice_bases(
+          state.word,
+          score === "near_interval" ? "fifth" : score,
+          "",
+          [],
+        );
+      }
+      return wasm.word_to_lattice_bases(
+        state.word,
+        score,
+        NEAR_INTERVAL,
+        [0, 1, 2].map((i) => state.tuning[i]),
+      );
+    }
+
     // display both the step vector in sum form and what interval it is in the current tuning
     function alsoInCurrentTuning(v, tuning, equave) {
       if (tuning["0"].includes("\\")) {
@@ -986,14 +1162,31 @@ stack()`
     const ONLY_TERNARY_SCALES =
       "Only ternary (3 step sizes) scales are supported.";
     const INVALID_SCALE_WORD =
-      "Scale word provided is not ternary with L, m, s. Make sure the scale word has no spaces.";
+      "Scale word provided is not ternary with L, m, s, nor the name of a known scale. Make sure the scale word has no spaces.";
     const NO_SCALE_WORD = "No scale word provided.";
     const NO_STEP_SIGNATURE = "No step signature specified.";
+    const SEARCH_STOPPED = "Search stopped.";
 
     const btnSig = document.getElementById("btn-sig");
     const btnWord = document.getElementById("btn-word");
+    // Aborts the running searches when a new query starts
+    let searchController = new AbortController();
+    // The finished step signature search whose scales are being paged through
+    let sigSearch = null;
+
+    // Start a new query, stopping the running searches and freeing the last step signature search
+    function newQuery() {
+      searchController.abort();
+      searchController = new AbortController();
+      if (sigSearch) {
+        sigSearch.free();
+        sigSearch = null;
+      }
+      return searchController.signal;
+    }
 
-    btnSig.addEventListener("click", () => {
+    btnSig.addEventListener("click", async () => {
+      const signal = newQuery();
       const sigQuery = document.getElementById("input-step-sig").value;
       let sig = `${sigQuery}`
         .split(" ")
@@ -1028,6 +1221,26 @@ stack()`
       <div class="tables-row">
                       <div class="table-column">
                         Scales
+                        <div class="page-controls">
+                          <label>
+                            sort by
+                            <select id="scale-sort">
+                              <option value="complexity">complexity</option>
+                              <option value="mv">max variety</option>
+                              <option value="chirality">chirality</option>
+                              <option value="lattice">lattice</option>
+                              <option value="guide_frames">guide frames</option>
+                              <option value="word">word</option>
+                            </select>
+                          </label>
+                          <label>
+                            <input type="checkbox" id="scale-sort-descending" />
+                            descending
+                          </label>
+                          <button id="scales-prev">&lt;</button>
+                          <span id="scales-page"></span>
+                          <button id="scales-next">&gt;</button>
+                        </div>
                         <div
                           style="
                             overflow-y: auto;
@@ -1072,7 +1285,7 @@ stack()`
             const jiTuningTable = document.getElementById("table-ji-tunings");
             const edTuningTable = document.getElementById("table-ed-tunings");
             const equave = getEquaveRatio();
-            const sigResultData = wasm.sig_result(
+            const search = new wasm.SigSearch(
               sig,
               document.getElementById("monotone-lm").checked,
               document.getElementById("monotone-ms").checked,
@@ -1090,11 +1303,22 @@ stack()`
               getSLower(),
               getSUpper(),
             );
-            const scales = sigResultData["profiles"].map((j) => j["word"]);
-            const latticeBases = sigResultData["profiles"].map(
-              (j) => j["lattice_basis"],
+            const finished = await runSearch(
+              search,
+              SIG_SEARCH_BUDGETS,
+              showSearchStatus(searchController),
+              signal,
             );
-            const profiles = sigResultData["profiles"];
+            if (!finished) {
+              // A new query replaces the status instead
+              if (signal.reason === STOPPED) {
+                statusElement.textContent = SEARCH_STOPPED;
+              }
+              return;
+            }
+            // Keep the scales in WASM memory and fetch them a page at a time
+            sigSearch = search;
+            const sigResultData = search.tunings();
 
             const jiTunings = sigResultData["ji_tunings"];
             const edTunings = sigResultData["ed_tunings"];
@@ -1109,33 +1333,76 @@ stack()`
               letters = [...Array(arity).keys()].map((i) => `X${i}`);
             }
             statusElement.innerHTML = `<h1>Results for ${escapeHtml([...Array(arity).keys()].map((i) => `${sig[i]}${letters[i]}`).join(""))}</h1> (click on a table row to select a scale or a tuning)`;
-            makeTable(scaleTable, scales, "scale");
-            // add event listener for each non-head row
-            const scaleRows = scaleTable.getElementsByTagName("tr");
-            if (scaleRows.length >= 3) {
-              scaleRows[2].classList.add("selected"); // For some reason 2 is the first row of a nonempty table.
-              appState.word = scales[0];
-              appState.profile = profiles[0];
-              appState.latticeBasis = appState.profile["lattice_basis"];
-
-              for (let i = 2; i < scaleRows.length; ++i) {
-                scaleRows[i].addEventListener("click", async () => {
-                  // unselect selected row in either JI tuning table or ED tuning table
-                  scaleTable
-                    .querySelector(`.selected`)
-                    .classList.remove("selected");
-
-                  // select the row clicked on
-                  scaleRows[i].classList.add("selected");
-                  // get scale pattern
-                  let scaleWord = scales[i - 2];
-                  appState.profile = profiles[i - 2];
-                  appState.latticeBasis = latticeBases[i - 2];
-                  appState.word = scaleWord;
-                  updateViews(equave);
-                });
+            const sortSelect = document.getElementById("scale-sort");
+            const descendingBox = document.getElementById(
+              "scale-sort-descending",
+            );
+            const prevButton = document.getElementById("scales-prev");
+            const nextButton = document.getElementById("scales-next");
+            const pageLabel = document.getElementById("scales-page");
+            let pageOffset = 0;
+            // Show the page of scales at `pageOffset`, selecting its first scale if `select` is true
+            function showScalePage(select) {
+              const page = search.page(
+                pageOffset,
+                SCALE_PAGE_SIZE,
+                sortSelect.value,
+                descendingBox.checked,
+              );
+              const profiles = page["profiles"];
+              const scales = profiles.map((j) => j["word"]);
+              scaleTable.innerHTML = "";
+              makeTable(scaleTable, scales, "scale", page["offset"]);
+              pageLabel.textContent =
+                page["total"] === 0
+                  ? "0 of 0"
+                  : `${page["offset"] + 1}–${page["offset"] + scales.length} of ${page["total"]}`;
+              prevButton.disabled = page["offset"] === 0;
+              nextButton.disabled =
+                page["offset"] + scales.length >= page["total"];
+              // add event listener for each non-head row
+              const scaleRows = scaleTable.getElementsByTagName("tr");
+              if (scaleRows.length >= 3) {
+                if (select) {
+                  scaleRows[2].classList.add("selected"); // For some reason 2 is the first row of a nonempty table.
+                  appState.word = scales[0];
+                  appState.profile = profiles[0];
+                  appState.latticeBasis = appState.profile["lattice_basis"];
+                }
+
+                for (let i = 2; i < scaleRows.length; ++i) {
+                  scaleRows[i].addEventListener("click", async () => {
+                    // unselect the selected row, if it is on this page
+                    scaleTable
+                      .querySelector(`.selected`)
+                      ?.classList.remove("selected");
+
+                    // select the row clicked on
+                    scaleRows[i].classList.add("selected");
+                    // get scale pattern
+                    appState.profile = profiles[i - 2];
+                    appState.latticeBasis = appState.profile["lattice_basis"];
+                    appState.word = scales[i - 2];
+                    updateViews(equave);
+                  });
+                }
               }
             }
+            showScalePage(true);
+            prevButton.addEventListener("click", () => {
+              pageOffset = Math.max(0, pageOffset - SCALE_PAGE_SIZE);
+              showScalePage(false);
+            });
+            nextButton.addEventListener("click", () => {
+              pageOffset += SCALE_PAGE_SIZE;
+              showScalePage(false);
+            });
+            for (const control of [sortSelect, descendingBox]) {
+              control.addEventListener("change", () => {
+                pageOffset = 0;
+                showScalePage(false);
+              });
+            }
             makeTable(jiTuningTable, jiTunings);
             const jiRows = jiTuningTable.getElementsByTagName("tr");
             for (let i = 2; i < jiRows.length; ++i) {
@@ -1172,57 +1439,64 @@ stack()`
             let currentJiTunings = jiTunings;
             const moreSolsBtn = document.getElementById("more-sols");
             if (moreSolsBtn) {
-              moreSolsBtn.addEventListener("click", () => {
+              moreSolsBtn.addEventListener("click", async () => {
                 moreSolsBtn.textContent = "Computing...";
                 moreSolsBtn.disabled = true;
-                setTimeout(() => {
-                  try {
-                    const moreJiTunings = wasm.more_ji_tunings(
-                      sig,
-                      equave.num,
-                      equave.den,
-                      getSLower(),
-                      getSUpper(),
-                    );
-                    // Merge with existing tunings (union by string comparison)
-                    const existingSet = new Set(
-                      currentJiTunings.map((t) => JSON.stringify(t)),
-                    );
-                    for (const tuning of moreJiTunings) {
-                      const key = JSON.stringify(tuning);
-                      if (!existingSet.has(key)) {
-                        existingSet.add(key);
-                        currentJiTunings.push(tuning);
-                      }
-                    }
-                    // Re-render the JI tuning table
-                    jiTuningTable.innerHTML = "";
-                    makeTable(jiTuningTable, currentJiTunings);
-                    const newJiRows = jiTuningTable.getElementsByTagName("tr");
-                    for (let i = 2; i < newJiRows.length; ++i) {
-                      const thisRow = newJiRows[i];
-                      thisRow.addEventListener("click", () => {
-                        if (arity === 3) {
-                          selectTuningRow(
-                            jiTuningTable,
-                            edTuningTable,
-                            thisRow,
-                          );
-                          appState.tuning = currentJiTunings[i - 2];
-                          updateViews(equave);
-                        } else {
-                          statusElement.textContent = ONLY_TERNARY_SCALES;
-                        }
-                      });
+                try {
+                  const jiSearch = new wasm.JiTuningSearch(
+                    sig,
+                    equave.num,
+                    equave.den,
+                    getSLower(),
+                    getSUpper(),
+                  );
+                  const finished = await runSearch(
+                    jiSearch,
+                    JI_TUNING_SEARCH_BUDGETS,
+                    (progress) => {
+                      moreSolsBtn.textContent = progressText(progress);
+                    },
+                    signal,
+                  );
+                  if (!finished) {
+                    return;
+                  }
+                  const moreJiTunings = jiSearch.result();
+                  jiSearch.free();
+                  // Merge with existing tunings (union by string comparison)
+                  const existingSet = new Set(
+                    currentJiTunings.map((t) => JSON.stringify(t)),
+                  );
+                  for (const tuning of moreJiTunings) {
+                    const key = JSON.stringify(tuning);
+                    if (!existingSet.has(key)) {
+                      existingSet.add(key);
+                      currentJiTunings.push(tuning);
                     }
-                    moreSolsBtn.textContent = `Get more JI tunings (${currentJiTunings.length} total)`;
-                    moreSolsBtn.disabled = false;
-                  } catch (err) {
-                    moreSolsBtn.textContent = "Error - try again";
-                    moreSolsBtn.disabled = false;
-                    console.error(err);
                   }
-                }, 10);
+                  // Re-render the JI tuning table
+                  jiTuningTable.innerHTML = "";
+                  makeTable(jiTuningTable, currentJiTunings);
+                  const newJiRows = jiTuningTable.getElementsByTagName("tr");
+                  for (let i = 2; i < newJiRows.length; ++i) {
+                    const thisRow = newJiRows[i];
+                    thisRow.addEventListener("click", () => {
+                      if (arity === 3) {
+                        selectTuningRow(jiTuningTable, edTuningTable, thisRow);
+                        appState.tuning = currentJiTunings[i - 2];
+                        updateViews(equave);
+                      } else {
+                        statusElement.textContent = ONLY_TERNARY_SCALES;
+                      }
+                    });
+                  }
+                  moreSolsBtn.textContent = `Get more JI tunings (${currentJiTunings.length} total)`;
+                  moreSolsBtn.disabled = false;
+                } catch (err) {
+                  moreSolsBtn.textContent = "Error - try again";
+                  moreSolsBtn.disabled = false;
+                  console.error(err);
+                }
               });
             }
 
@@ -1230,11 +1504,19 @@ stack()`
           }
         }
       } catch (err) {
-        statusElement.innerText = err;
+        statusElement.innerText = errorMessage(err);
       }
     });
     btnWord.addEventListener("click", () => {
-      const query = document.getElementById("input-word").value;
+      const signal = newQuery();
+      let query = document.getElementById("input-word").value.trim();
+      // A scale name such as "diasem" stands for its brightest word
+      if (query && !/^[Lms]*$/.test(query)) {
+        const named = wasm.find_scales(query);
+        if (named.length > 0) {
+          query = named[0]["word"];
+        }
+      }
       const arity = new Set(Array.from(query)).size;
       const queryIsValid = arity === 3 && /^[Lms]*$/.test(query);
       if (queryIsValid) {
@@ -1332,53 +1614,64 @@ stack()`
               countChar(query, "m"),
               countChar(query, "s"),
             ];
-            moreSolsBtn.addEventListener("click", () => {
+            moreSolsBtn.addEventListener("click", async () => {
               moreSolsBtn.textContent = "Computing...";
               moreSolsBtn.disabled = true;
-              setTimeout(() => {
-                try {
-                  const moreJiTunings = wasm.more_ji_tunings(
-                    stepSig,
-                    equave.num,
-                    equave.den,
-                    getSLower(),
-                    getSUpper(),
-                  );
-                  // Merge with existing tunings (union by string comparison)
-                  const existingSet = new Set(
-                    currentJiTunings.map((t) => JSON.stringify(t)),
-                  );
-                  for (const tuning of moreJiTunings) {
-                    const key = JSON.stringify(tuning);
-                    if (!existingSet.has(key)) {
-                      existingSet.add(key);
-                      currentJiTunings.push(tuning);
-                    }
-                  }
-                  // Re-render the JI tuning table
-                  jiTuningTable.innerHTML = "";
-                  makeTable(jiTuningTable, currentJiTunings);
-                  const newJiRows = jiTuningTable.getElementsByTagName("tr");
-                  for (let i = 2; i < newJiRows.length; ++i) {
-                    const thisRow = newJiRows[i];
-                    thisRow.addEventListener("click", () => {
-                      if (arity === 3) {
-                        selectTuningRow(jiTuningTable, edTuningTable, thisRow);
-                        appState.tuning = currentJiTunings[i - 2];
-                        updateViews(equave);
-                      } else {
-                        statusElement.textContent = ONLY_TERNARY_SCALES;
-                      }
-                    });
+              try {
+                const jiSearch = new wasm.JiTuningSearch(
+                  stepSig,
+                  equave.num,
+                  equave.den,
+                  getSLower(),
+                  getSUpper(),
+                );
+                const finished = await runSearch(
+                  jiSearch,
+                  JI_TUNING_SEARCH_BUDGETS,
+                  (progress) => {
+                    moreSolsBtn.textContent = progressText(progress);
+                  },
+                  signal,
+                );
+                if (!finished) {
+                  return;
+                }
+                const moreJiTunings = jiSearch.result();
+                jiSearch.free();
+                // Merge with existing tunings (union by string comparison)
+                const existingSet = new Set(
+                  currentJiTunings.map((t) => JSON.stringify(t)),
+                );
+                for (const tuning of moreJiTunings) {
+                  const key = JSON.stringify(tuning);
+                  if (!existingSet.has(key)) {
+                    existingSet.add(key);
+                    currentJiTunings.push(tuning);
                   }
-                  moreSolsBtn.textContent = `Get more JI tunings (${currentJiTunings.length} total)`;
-                  moreSolsBtn.disabled = false;
-                } catch (err) {
-                  moreSolsBtn.textContent = "Error - try again";
-                  moreSolsBtn.disabled = false;
-                  console.error(err);
                 }
-              }, 10);
+                // Re-render the JI tuning table
+                jiTuningTable.innerHTML = "";
+                makeTable(jiTuningTable, currentJiTunings);
+                const newJiRows = jiTuningTable.getElementsByTagName("tr");
+                for (let i = 2; i < newJiRows.length; ++i) {
+                  const thisRow = newJiRows[i];
+                  thisRow.addEventListener("click", () => {
+                    if (arity === 3) {
+                      selectTuningRow(jiTuningTable, edTuningTable, thisRow);
+                      appState.tuning = currentJiTunings[i - 2];
+                      updateViews(equave);
+                    } else {
+                      statusElement.textContent = ONLY_TERNARY_SCALES;
+                    }
+                  });
+                }
+                moreSolsBtn.textContent = `Get more JI tunings (${currentJiTunings.length} total)`;
+                moreSolsBtn.disabled = false;
+              } catch (err) {
+                moreSolsBtn.textContent = "Error - try again";
+                moreSolsBtn.disabled = false;
+                console.error(err);
+              }
             });
           }
 
@@ -1387,7 +1680,7 @@ stack()`
           appState.latticeBasis = appState.profile["lattice_basis"];
           updateViews(equave);
         } catch (err) {
-          statusElement.innerText = err;
+          statusElement.innerText = errorMessage(err);
         }
       } else if (query) {
         statusElement.textContent = INVALID_SCALE_WORD;
@@ -0,0 +1,505 @@
+//! Newline-delimited JSON batch queries.
+//!
+//! Each input line is a JSON request with a `type` and an optional `id`, which is echoed back in
+//! the response. Each response is one line of JSON holding either a `result`, serialized with the
+//! same structs as the WASM API, or an `error` object with a `code` and a `message`. A bad
+//! request produces an error response and processing continues with the next line.
+//!
+//! | `type`       | fields                                                        | result            |
+//! |--------------|---------------------------------------------------------------|-------------------|
+//! | `profile`    | `word`                                                        | [`ScaleProfile`]  |
+//! | `word`       | `word`, tuning bounds                                         | [`WordResult`]    |
+//! | `sig`        | `sig`, tuning bounds, [`SigFilter`] fields                    | [`SigResult`]     |
+//! | `ji_tunings` | `sig`, `equave`, `s_lower`, `s_upper`, `slow`                 | step ratios       |
+//! | `ed_tunings` | `sig`, tuning bounds                                          | ED steps and vals |
+//! | `lattice`    | `word`                                                        | [`LatticeResult`] |
+//!
+//! The tuning bounds are `equave` (such as `"3/1"`, default `"2/1"`), `ed_bound` (default 111),
+//! `s_lower` and `s_upper` (default 20 and 250 cents), as on the web page.
+//! A [`Batch`] caches scale profiles and tunings in a [`ProfileCache`], so repeated words,
+//! modes of the same scale and step signatures are only analysed once per batch, and it can
+//! start from a precomputed catalog with [`Batch::with_cache`].
+//!
+//! # Examples
+//!
+//! ```
+//! use ternary::batch::Batch;
+//!
+//! let input = r#"{"id": 1, "type": "profile", "word": "LmLsLmLsL"}
+//! {"id": 2, "type": "ed_tunings", "sig": [5, 2, 2], "ed_bound": 30}
+//! {"id": 3, "type": "profile", "word": "LxL"}
+//! "#;
+//! let mut output = vec![];
+//! let stats = Batch::new().run(input.as_bytes(), &mut output).unwrap();
+//! assert_eq!((stats.requests, stats.errors), (3, 1));
+//!
+//! let lines: Vec<&str> = std::str::from_utf8(&output).unwrap().lines().collect();
+//! assert!(lines[0].starts_with(r#"{"id":1,"result":{"word":"LLmLsLmLs","#));
+//! assert!(lines[1].contains(r#""ed_tunings":[["3\\21","2\\21","1\\21"],"#));
+//! assert!(lines[2].contains(r#""error":{"code":"invalid_word","#));
+//! ```
+
+use std::io::{self, BufRead, Write};
+
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+
+pub use crate::cache::EdTuningsResult;
+use crate::cache::ProfileCache;
+use crate::error::FieldError;
+use crate::ji_ratio::RawJiRatio;
+use crate::words::Letter;
+use crate::{
+    LatticeResult, ScaleProfile, SigFilter, SigResult, WordResult, check_ternary_sig,
+    check_tuning_bounds, parse_equave, parse_ternary_word, word_to_lattice_result, word_to_sig,
+};
+
+/// The error object of a failed batch request.
+///
+/// Serialized with a `code` and a `message`, as well as the `field` of the request it concerns
+/// for invalid fields.
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum BatchError {
+    /// The line was not valid JSON or not a known request. Has the code `bad_request`.
+    #[error("{0}")]
+    BadRequest(String),
+    /// A field of the request was invalid. Has the code of the [`Error`](crate::error::Error).
+    #[error(transparent)]
+    Invalid(#[from] FieldError),
+}
+
+impl BatchError {
+    /// A short identifier for the kind of error, such as `"invalid_word"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::BadRequest(_) => "bad_request",
+            Self::Invalid(e) => e.error.code(),
+        }
+    }
+}
+
+impl Serialize for BatchError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::BadRequest(message) => {
+                let mut state = serializer.serialize_struct("BatchError", 2)?;
+                state.serialize_field("code", self.code())?;
+                state.serialize_field("message", message)?;
+                state.end()
+            }
+            Self::Invalid(e) => e.serialize(serializer),
+        }
+    }
+}
+
+fn default_equave() -> String {
+    "2/1".to_string()
+}
+
+fn default_ed_bound() -> i32 {
+    111
+}
+
+fn default_s_lower() -> f64 {
+    20.0
+}
+
+fn default_s_upper() -> f64 {
+    250.0
+}
+
+/// The tuning search bounds shared by several requests, with the web page's defaults.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct TuningBounds {
+    /// the equave as a ratio such as `"3/1"` or `"3"`
+    #[serde(default = "default_equave")]
+    pub equave: String,
+    /// the largest ED to search
+    #[serde(default = "default_ed_bound")]
+    pub ed_bound: i32,
+    /// the smallest step size in cents
+    #[serde(default = "default_s_lower")]
+    pub s_lower: f64,
+    /// the largest step size in cents
+    #[serde(default = "default_s_upper")]
+    pub s_upper: f64,
+}
+
+/// A batch query, tagged by its `type` field.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BatchQuery {
+    /// The profile of a scale word.
+    Profile { word: String },
+    /// The profile and tunings of a scale word, as `word_result` in WASM.
+    Word {
+        word: String,
+        #[serde(flatten)]
+        bounds: TuningBounds,
+    },
+    /// The scales and tunings of a step signature, as `sig_result` in WASM.
+    Sig {
+        sig: Vec<usize>,
+        #[serde(flatten)]
+        bounds: TuningBounds,
+        #[serde(flatten)]
+        filter: SigFilter,
+    },
+    /// The JI tunings of a step signature, optionally from the slow solver.
+    JiTunings {
+        sig: Vec<usize>,
+        #[serde(flatten)]
+        bounds: TuningBounds,
+        #[serde(default)]
+        slow: bool,
+    },
+    /// The ED tunings of a step signature with their vals.
+    EdTunings {
+        sig: Vec<usize>,
+        #[serde(flatten)]
+        bounds: TuningBounds,
+    },
+    /// The pitch class lattice of a scale word.
+    Lattice { word: String },
+}
+
+/// A batch request: a query with an optional `id` of any JSON type.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct BatchRequest {
+    /// echoed back in the response
+    #[serde(default)]
+    pub id: Value,
+    #[serde(flatten)]
+    pub query: BatchQuery,
+}
+
+/// The result of a successful batch request.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum BatchResult {
+    Profile(ScaleProfile),
+    Word(WordResult),
+    Sig(SigResult),
+    JiTunings(Vec<Vec<String>>),
+    EdTunings(EdTuningsResult),
+    Lattice(Option<LatticeResult>),
+}
+
+/// One line of batch output.
+#[derive(Debug, Serialize)]
+pub struct BatchResponse {
+    /// the `id` of the request, or `null`
+    pub id: Value,
+    /// the result, if the request succeeded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<BatchResult>,
+    /// the error, if the request failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<BatchError>,
+}
+
+/// Counts of the requests processed by [`Batch::run`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BatchStats {
+    /// nonblank input lines
+    pub requests: usize,
+    /// requests that produced an error response
+    pub errors: usize,
+}
+
+/// A validated tuning query.
+struct Tunings<'a> {
+    sig: &'a [usize],
+    equave: RawJiRatio,
+    ed_bound: i32,
+    s_lower: f64,
+    s_upper: f64,
+}
+
+impl<'a> Tunings<'a> {
+    fn new(sig: &'a [usize], bounds: &TuningBounds) -> Result<Self, FieldError> {
+        check_tuning_bounds(bounds.ed_bound, bounds.s_lower, bounds.s_upper)?;
+        Ok(Self {
+            sig,
+            equave: parse_equave(&bounds.equave).map_err(|e| e.in_field("equave"))?,
+            ed_bound: bounds.ed_bound,
+            s_lower: bounds.s_lower,
+            s_upper: bounds.s_upper,
+        })
+    }
+}
+
+/// A batch session, caching profiles and tunings across requests in a [`ProfileCache`].
+#[derive(Debug, Default)]
+pub struct Batch {
+    cache: ProfileCache,
+}
+
+fn parse_word(word: &str) -> Result<Vec<Letter>, FieldError> {
+    parse_ternary_word(word).map_err(|e| e.in_field("word"))
+}
+
+fn check_sig(sig: &[usize]) -> Result<(), FieldError> {
+    check_ternary_sig(sig).map_err(|e| e.in_field("sig"))
+}
+
+impl Batch {
+    /// An empty batch session.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A batch session starting from the entries of `cache`, such as a loaded catalog.
+    pub fn with_cache(cache: ProfileCache) -> Self {
+        Self { cache }
+    }
+
+    /// The profiles and tunings cached so far.
+    pub fn cache(&self) -> &ProfileCache {
+        &self.cache
+    }
+
+    fn ji_tunings(&mut self, query: &Tunings, slow: bool) -> Vec<Vec<String>> {
+        self.cache
+            .ji_tunings(query.sig, query.equave, query.s_lower, query.s_upper, slow)
+    }
+
+    fn ed_tunings(&mut self, query: &Tunings) -> EdTuningsResult {
+        self.cache.ed_tunings(
+            query.sig,
+            query.equave,
+            query.ed_bound,
+            query.s_lower,
+            query.s_upper,
+        )
+    }
+
+    /// Answer a single query.
+    pub fn query(&mut self, query: &BatchQuery) -> Result<BatchResult, FieldError> {
+        Ok(match query {
+            BatchQuery::Profile { word } => {
+                BatchResult::Profile(self.cache.profile(&parse_word(word)?))
+            }
+            BatchQuery::Word { word, bounds } => {
+                let word = parse_word(word)?;
+                let sig = word_to_sig(&word);
+                let tunings = Tunings::new(&sig, bounds)?;
+                BatchResult::Word(self.cache.word_result(
+                    &word,
+                    tunings.equave,
+                    tunings.ed_bound,
+                    tunings.s_lower,
+                    tunings.s_upper,
+                ))
+            }
+            BatchQuery::Sig {
+                sig,
+                bounds,
+                filter,
+            } => {
+                check_sig(sig)?;
+                let tunings = Tunings::new(sig, bounds)?;
+                BatchResult::Sig(self.cache.sig_result(
+                    sig,
+                    filter,
+                    tunings.equave,
+                    tunings.ed_bound,
+                    tunings.s_lower,
+                    tunings.s_upper,
+                ))
+            }
+            BatchQuery::JiTunings { sig, bounds, slow } => {
+                check_sig(sig)?;
+                BatchResult::JiTunings(self.ji_tunings(&Tunings::new(sig, bounds)?, *slow))
+            }
+            BatchQuery::EdTunings { sig, bounds } => {
+                check_sig(sig)?;
+                BatchResult::EdTunings(self.ed_tunings(&Tunings::new(sig, bounds)?))
+            }
+            BatchQuery::Lattice { word } => {
+                BatchResult::Lattice(word_to_lattice_result(&parse_word(word)?))
+            }
+        })
+    }
+
+    /// Answer one line of input. Requests that can't be read still get a response, carrying
+    /// their `id` if the line was a JSON object with one.
+    pub fn respond(&mut self, line: &str) -> BatchResponse {
+        let request = serde_json::from_str::<Value>(line)
+            .map_err(|e| (Value::Null, e))
+            .and_then(|value| {
+                let id = value.get("id").cloned().unwrap_or(Value::Null);
+                serde_json::from_value::<BatchRequest>(value).map_err(|e| (id, e))
+            });
+        match request {
+            Ok(request) => match self.query(&request.query) {
+                Ok(result) => BatchResponse {
+                    id: request.id,
+                    result: Some(result),
+                    error: None,
+                },
+                Err(error) => BatchResponse {
+                    id: request.id,
+                    result: None,
+                    error: Some(error.into()),
+                },
+            },
+            Err((id, e)) => BatchResponse {
+                id,
+                result: None,
+                error: Some(BatchError::BadRequest(e.to_string())),
+            },
+        }
+    }
+
+    /// Answer each nonblank line of `input`, writing one line of JSON per request to `output`
+    /// and flushing after each, so responses stream as they are computed.
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, mut output: W) -> io::Result<BatchStats> {
+        let mut stats = BatchStats::default();
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = self.respond(&line);
+            stats.requests += 1;
+            if response.error.is_some() {
+                stats.errors += 1;
+            }
+            serde_json::to_writer(&mut output, &response)?;
+            output.write_all(b"\n")?;
+            output.flush()?;
+        }
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::CacheStats;
+    use crate::string_to_numbers;
+
+    fn respond(batch: &mut Batch, line: &str) -> Value {
+        serde_json::to_value(batch.respond(line)).unwrap()
+    }
+
+    #[test]
+    fn test_results_match_wasm_shapes() {
+        let mut batch = Batch::new();
+        let response = respond(
+            &mut batch,
+            r#"{"id": "a", "type": "word", "word": "LmLsLmLsL", "ed_bound": 30}"#,
+        );
+        let expected = serde_json::to_value(crate::word_to_result(
+            &string_to_numbers("LmLsLmLsL"),
+            RawJiRatio::OCTAVE,
+            30,
+            20.0,
+            250.0,
+        ))
+        .unwrap();
+        assert_eq!(response["id"], "a");
+        assert_eq!(response["result"], expected);
+        assert!(response.get("error").is_none());
+
+        let response = respond(
+            &mut batch,
+            r#"{"type": "sig", "sig": [3, 2, 2], "mv": 3, "equave": "3", "ed_bound": 40}"#,
+        );
+        let filter = SigFilter {
+            mv: 3,
+            ..Default::default()
+        };
+        let expected = serde_json::to_value(crate::sig_to_result(
+            &[3, 2, 2],
+            &filter,
+            RawJiRatio::TRITAVE,
+            40,
+            20.0,
+            250.0,
+        ))
+        .unwrap();
+        assert_eq!(response["id"], Value::Null);
+        assert_eq!(response["result"], expected);
+    }
+
+    #[test]
+    fn test_errors_dont_abort() {
+        let input = "not json\n\
+                     {\"id\": 7, \"type\": \"nonsense\"}\n\
+                     \n\
+                     {\"id\": 8, \"type\": \"ed_tunings\", \"sig\": [5, 2]}\n\
+                     {\"id\": 9, \"type\": \"word\", \"word\": \"LmLsLmLsL\", \"equave\": \"1/2\"}\n\
+                     {\"id\": 10, \"type\": \"lattice\", \"word\": \"LLmLLms\"}\n";
+        let mut output = vec![];
+        let stats = Batch::new().run(input.as_bytes(), &mut output).unwrap();
+        assert_eq!(
+            stats,
+            BatchStats {
+                requests: 5,
+                errors: 4
+            }
+        );
+        let responses: Vec<Value> = output
+            .split(|&b| b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect();
+        let codes: Vec<Value> = responses
+            .iter()
+            .map(|response| response["error"]["code"].clone())
+            .collect();
+        assert_eq!(
+            codes,
+            [
+                "bad_request".into(),
+                "bad_request".into(),
+                "invalid_sig".into(),
+                "invalid_equave".into(),
+                Value::Null
+            ]
+        );
+        assert_eq!(responses[1]["id"], 7);
+        assert_eq!(responses[2]["error"]["field"], "sig");
+        assert_eq!(responses[3]["error"]["field"], "equave");
+        assert_eq!(
+            responses[4]["result"]["basis"],
+            serde_json::json!([[1, 0, 0], [2, 1, 0]])
+        );
+    }
+
+    #[test]
+    fn test_caches_are_reused() {
+        let mut batch = Batch::new();
+        batch.respond(r#"{"type": "sig", "sig": [2, 2, 1], "ed_bound": 20}"#);
+        let profiles = batch.cache().stats().profiles;
+        assert!(profiles > 0);
+        assert_eq!(batch.cache().stats().ed_tunings, 1);
+        // The same signature with the same bounds reuses everything.
+        batch.respond(r#"{"type": "sig", "sig": [2, 2, 1], "ed_bound": 20, "lm": true}"#);
+        batch.respond(r#"{"type": "ed_tunings", "sig": [2, 2, 1], "ed_bound": 20}"#);
+        // So does another mode of one of its scales.
+        batch.respond(r#"{"type": "profile", "word": "LmLsm"}"#);
+        assert_eq!(
+            batch.cache().stats(),
+            CacheStats {
+                profiles,
+                ji_tunings: 1,
+                ed_tunings: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_with_cache() {
+        let mut cache = ProfileCache::new();
+        cache.precompute(5);
+        let catalog = cache.to_catalog();
+        let mut batch = Batch::with_cache(ProfileCache::from_catalog(catalog).unwrap());
+        let response = respond(&mut batch, r#"{"type": "profile", "word": "LLmLs"}"#);
+        assert_eq!(response["result"]["word"], "LLmLs");
+        assert_eq!(batch.cache().stats(), cache.stats());
+    }
+}
@@ -0,0 +1,878 @@
+//! Command-line interface to the scale and step signature queries of the web app.
+//!
+//! ```text
+//! ternary word LmLsLmLsL
+//! ternary sig 5L2m2s --mv 3 --json
+//! ternary sig 5L2m2s --sort mv --descending
+//! ternary ji 5L2m2s --slow
+//! ternary ed 5L2m2s --ed-bound 60
+//! ternary lattice LLmLLms
+//! ternary lattice diasem --bases shortest
+//! ternary lattice diasem --bases near_interval --interval 7/4 --tuning 9/8,28/27,64/63
+//! ternary lattice zarlino --svg zarlino.svg --tuning 9/8,10/9,16/15 --chord 0,2,4
+//! ternary word zarlino
+//! ternary temper diasem --tuning 9/8,28/27,64/63 --comma 64/63
+//! ternary temper zarlino --tuning 9/8,10/9,16/15 --comma 81/80 --method cte
+//! ternary names diasem
+//! ternary names --sig 5L2m2s
+//! ternary batch requests.jsonl > responses.jsonl
+//! ternary catalog --max-notes 12 catalog.json
+//! ternary batch --catalog catalog.json requests.jsonl
+//! ```
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::process::ExitCode;
+
+use clap::{Arg, ArgAction, ArgMatches, Command, value_parser};
+use serde::Serialize;
+use serde_json::json;
+
+use ternary::batch::Batch;
+use ternary::cache::ProfileCache;
+use ternary::ji_ratio::RawJiRatio;
+use ternary::lattice::{BASIS_SCORES, BasisScore, ranked_lattice_bases};
+use ternary::monzo::Monzo;
+use ternary::named::{find_scale, scales_with_sig, search_scales};
+use ternary::optimal::{TuningMethod, optimal_tuning};
+use ternary::svg::{Highlight, SvgOptions, lattice_svg};
+use ternary::temperament::{Temperament, step_tuning_name};
+use ternary::tuning::StepTuning;
+use ternary::words::Chirality;
+use ternary::{
+    PROFILE_SORTS, ProfileSort, ScaleProfile, SigFilter, check_ternary_sig, numbers_to_string,
+    parse_ternary_word, sig_to_ed_tunings_with_vals, sig_to_ji_tunings, sig_to_ji_tunings_slow,
+    sig_to_result, string_to_numbers, word_to_lattice_result, word_to_result, word_to_sig,
+};
+
+// Defaults of the web app.
+const DEFAULT_ED_BOUND: &str = "111";
+const DEFAULT_S_LOWER: &str = "20";
+const DEFAULT_S_UPPER: &str = "250";
+
+fn json_arg() -> Arg {
+    Arg::new("json")
+        .long("json")
+        .action(ArgAction::SetTrue)
+        .help("Print the result as JSON")
+}
+
+fn equave_arg() -> Arg {
+    Arg::new("equave")
+        .long("equave")
+        .default_value("2/1")
+        .help("Equave as a JI ratio, e.g. 3/1")
+}
+
+fn ed_bound_arg() -> Arg {
+    Arg::new("ed-bound")
+        .long("ed-bound")
+        .value_parser(value_parser!(i32))
+        .default_value(DEFAULT_ED_BOUND)
+        .help("Largest ED to search for ED tunings")
+}
+
+fn s_bound_args() -> [Arg; 2] {
+    [
+        Arg::new("s-lower")
+            .long("s-lower")
+            .value_parser(value_parser!(f64))
+            .default_value(DEFAULT_S_LOWER)
+            .help("Smallest size of the small step in cents"),
+        Arg::new("s-upper")
+            .long("s-upper")
+            .value_parser(value_parser!(f64))
+            .default_value(DEFAULT_S_UPPER)
+            .help("Largest size of the small step in cents"),
+    ]
+}
+
+fn flag(name: &'static str, help: &'static str) -> Arg {
+    Arg::new(name)
+        .long(name)
+        .action(ArgAction::SetTrue)
+        .help(help)
+}
+
+fn command() -> Command {
+    let word = Arg::new("word")
+        .required(true)
+        .help("Scale word in step letters, e.g. LmLsLmLsL, or the name of a scale, e.g. diasem");
+    let sig = Arg::new("sig")
+        .required(true)
+        .help("Step signature, e.g. 5L2m2s, 5+2+2 or 5,2,2");
+    Command::new("ternary")
+        .about("Query ternary scales and step signatures")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .subcommand(
+            Command::new("word")
+                .about("Profile and tunings of a scale word")
+                .arg(word.clone())
+                .arg(equave_arg())
+                .arg(ed_bound_arg())
+                .args(s_bound_args())
+                .arg(json_arg()),
+        )
+        .subcommand(
+            Command::new("sig")
+                .about("Scales with a step signature, with filters, and its tunings")
+                .arg(sig.clone())
+                .arg(flag("lm", "Only L=m monotone MOS scales"))
+                .arg(flag("ms", "Only m=s monotone MOS scales"))
+                .arg(flag("s0", "Only s=0 monotone MOS scales"))
+                .arg(
+                    Arg::new("ggs-len")
+                        .long("ggs-len")
+                        .value_parser(value_parser!(u8))
+                        .default_value("0")
+                        .help("Maximum guided generator sequence length (0 for any)"),
+                )
+                .arg(flag("ggs-exact", "Require exactly --ggs-len"))
+                .arg(
+                    Arg::new("mv")
+                        .long("mv")
+                        .value_parser(value_parser!(u8))
+                        .default_value("0")
+                        .help("Maximum maximum variety (0 for any)"),
+                )
+                .arg(flag("mv-exact", "Require exactly --mv"))
+                .arg(flag("mos-subst", "Only MOS substitution scales"))
+                .arg(
+                    Arg::new("sort")
+                        .long("sort")
+                        .value_parser(PROFILE_SORTS.to_vec())
+                        .default_value("complexity")
+                        .help("Profile field to sort the scales by"),
+                )
+                .arg(flag("descending", "Sort the scales in descending order"))
+                .arg(equave_arg())
+                .arg(ed_bound_arg())
+                .args(s_bound_args())
+                .arg(json_arg()),
+        )
+        .subcommand(
+            Command::new("ji")
+                .about("JI tunings of a step signature")
+                .arg(sig.clone())
+                .arg(flag("slow", "Use the slow solver for more tunings"))
+                .arg(equave_arg())
+                .args(s_bound_args())
+                .arg(json_arg()),
+        )
+        .subcommand(
+            Command::new("ed")
+                .about("ED tunings of a step signature")
+                .arg(sig)
+                .arg(equave_arg())
+                .arg(ed_bound_arg())
+                .args(s_bound_args())
+                .arg(json_arg()),
+        )
+        .subcommand(
+            Command::new("names")
+                .about("Named scales whose names or aliases contain a string")
+                .arg(Arg::new("query").help("Part of a name; lists every named scale if omitted"))
+                .arg(
+                    Arg::new("sig")
+                        .long("sig")
+                        .conflicts_with("query")
+                        .help("List the named scales with this step signature instead, e.g. 5L2m2s"),
+                )
+                .arg(equave_arg().requires("sig"))
+                .arg(json_arg()),
+        )
+        .subcommand(
+            Command::new("lattice")
+                .about("Pitch class lattice coordinates of a scale word")
+                .arg(word.clone())
+                .arg(
+                    Arg::new("bases")
+                        .long("bases")
+                        .value_parser(BASIS_SCORES.to_vec())
+                        .requires_if("near_interval", "tuning")
+                        .help("List every basis showing the scale as a parallelogram substring, best first by this score"),
+                )
+                .arg(
+                    Arg::new("svg")
+                        .long("svg")
+                        .help("Draw the lattice as SVG to this file, or to standard output if `-`; with --bases, in the best basis"),
+                )
+                .arg(
+                    Arg::new("tuning")
+                        .long("tuning")
+                        .help("Label the drawing with pitches under these comma-separated step sizes, e.g. 9/8,10/9,16/15; also the tuning of --bases near_interval"),
+                )
+                .arg(
+                    Arg::new("interval")
+                        .long("interval")
+                        .default_value("3/2")
+                        .help("JI interval that --bases near_interval prefers a basis vector near"),
+                )
+                .arg(
+                    Arg::new("mode")
+                        .long("mode")
+                        .requires("svg")
+                        .conflicts_with("chord")
+                        .value_parser(value_parser!(usize))
+                        .help("Highlight the mode starting on this degree in the drawing"),
+                )
+                .arg(
+                    Arg::new("chord")
+                        .long("chord")
+                        .requires("svg")
+                        .help("Highlight these comma-separated degrees in the drawing, e.g. 0,2,4"),
+                )
+                .arg(json_arg()),
+        )
+        .subcommand(
+            Command::new("temper")
+                .about("The temperament a JI tuning of a scale word represents once commas are tempered out")
+                .arg(word)
+                .arg(
+                    Arg::new("tuning")
+                        .long("tuning")
+                        .required(true)
+                        .help("Comma-separated JI step sizes, e.g. 9/8,28/27,64/63"),
+                )
+                .arg(
+                    Arg::new("comma")
+                        .long("comma")
+                        .help("Comma-separated commas to temper out, e.g. 64/63,81/80"),
+                )
+                .arg(
+                    Arg::new("method")
+                        .long("method")
+                        .value_parser(["te", "cte", "pote", "minimax"])
+                        .help("Also tune the steps by this optimal tuning of the temperament; cte and minimax keep the scale's equave pure"),
+                )
+                .arg(
+                    Arg::new("odd")
+                        .long("odd")
+                        .value_parser(value_parser!(u32))
+                        .default_value("9")
+                        .help("Odd limit of the tonality diamond for --method minimax"),
+                )
+                .arg(json_arg()),
+        )
+        .subcommand(
+            Command::new("batch")
+                .about("Answer newline-delimited JSON requests, writing one JSON response per line")
+                .arg(
+                    Arg::new("input").help(
+                        "File of requests, one per line; reads standard input if omitted or `-`",
+                    ),
+                )
+                .arg(
+                    Arg::new("catalog")
+                        .long("catalog")
+                        .help("Catalog of precomputed profiles and tunings to start from"),
+                ),
+        )
+        .subcommand(
+            Command::new("catalog")
+                .about("Write a catalog of the profiles of all ternary scales up to a size")
+                .arg(
+                    Arg::new("max-notes")
+                        .long("max-notes")
+                        .required(true)
+                        .value_parser(value_parser!(usize))
+                        .help("Largest number of notes of the scales"),
+                )
+                .arg(
+                    Arg::new("output")
+                        .required(true)
+                        .help("File to write the catalog to"),
+                ),
+        )
+}
+
+/// Read a step signature such as `5L2m2s`, `5+2+2` or `5,2,2` as its step counts.
+fn parse_sig(s: &str) -> Result<Vec<usize>, String> {
+    let counts: Vec<usize> = s
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            part.parse()
+                .map_err(|_| format!("invalid step count `{part}`"))
+        })
+        .collect::<Result<_, _>>()?;
+    check_ternary_sig(&counts).map_err(|e| e.to_string())?;
+    Ok(counts)
+}
+
+/// Read an equave such as `2/1` or `3`.
+fn parse_equave(s: &str) -> Result<RawJiRatio, String> {
+    ternary::parse_equave(s).map_err(|e| e.to_string())
+}
+
+/// Read a ternary scale word in the letters `L`, `m` and `s`, as the web page does.
+/// Read a scale word, or the name of a scale such as `diasem` as its brightest word.
+fn parse_word(s: &str) -> Result<Vec<usize>, String> {
+    match find_scale(s) {
+        Some(scale) => Ok(string_to_numbers(scale.word)),
+        None => parse_ternary_word(s).map_err(|e| e.to_string()),
+    }
+}
+
+/// Read comma-separated step sizes such as `9/8,10/9,16/15` as a tuning.
+fn parse_tuning(s: &str) -> Result<StepTuning, String> {
+    let steps: Vec<&str> = s.split(',').map(str::trim).collect();
+    StepTuning::from_strings(&steps).map_err(|e| e.to_string())
+}
+
+/// Read JI step sizes for every letter of `word`, such as `9/8,28/27,64/63`, as monzos.
+fn parse_ji_tuning(s: &str, word: &[usize]) -> Result<Vec<Monzo>, String> {
+    let tuning = parse_tuning(s)?;
+    tuning.check_word(word).map_err(|e| e.to_string())?;
+    match tuning {
+        StepTuning::Ji(steps) => Ok(steps),
+        _ => Err(format!("`{s}` is not a JI tuning")),
+    }
+}
+
+/// Read comma-separated JI ratios such as `81/80,64/63` as monzos.
+fn parse_commas(s: &str) -> Result<Vec<Monzo>, String> {
+    s.split(',')
+        .map(|comma| {
+            let comma = comma.trim();
+            comma
+                .parse::<RawJiRatio>()
+                .ok()
+                .and_then(|ratio| Monzo::try_from_ratio(ratio).ok())
+                .ok_or_else(|| format!("invalid comma `{comma}`"))
+        })
+        .collect()
+}
+
+fn monzo_string(monzo: &Monzo) -> String {
+    monzo
+        .try_to_ratio()
+        .map_or_else(|| monzo.to_string(), |ratio| ratio.to_string())
+}
+
+/// Print rows as a table with left-aligned columns.
+fn print_table(headers: &[&str], rows: &[Vec<String>]) {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let print_row = |cells: Vec<&str>| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| format!("{cell:width$}"))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    };
+    print_row(headers.to_vec());
+    print_row(
+        widths
+            .iter()
+            .map(|&w| "-".repeat(w))
+            .collect::<Vec<_>>()
+            .iter()
+            .map(String::as_str)
+            .collect(),
+    );
+    for row in rows {
+        print_row(row.iter().map(String::as_str).collect());
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    println!("{json}");
+    Ok(())
+}
+
+fn sig_string(word: &str) -> String {
+    let sig = word_to_sig(&string_to_numbers(word));
+    format!("{}L{}m{}s", sig[0], sig[1], sig[2])
+}
+
+fn chirality_name(chirality: Chirality) -> &'static str {
+    match chirality {
+        Chirality::Left => "left",
+        Chirality::Achiral => "achiral",
+        Chirality::Right => "right",
+    }
+}
+
+fn vector_string(v: &[u16]) -> String {
+    format!("{v:?}")
+}
+
+fn guide_string(profile: &ScaleProfile) -> String {
+    match &profile.structure {
+        Some(guide) => {
+            let gs: Vec<String> = guide.gs.iter().map(|g| vector_string(g)).collect();
+            format!(
+                "GS({}) complexity {} multiplicity {}",
+                gs.join(", "),
+                guide.complexity,
+                guide.multiplicity
+            )
+        }
+        None => "none".to_string(),
+    }
+}
+
+fn monotone_string(profile: &ScaleProfile) -> String {
+    let flags: Vec<&str> = [
+        (profile.lm, "L=m"),
+        (profile.ms, "m=s"),
+        (profile.s0, "s=0"),
+    ]
+    .into_iter()
+    .filter_map(|(holds, name)| holds.then_some(name))
+    .collect();
+    if flags.is_empty() {
+        "-".to_string()
+    } else {
+        flags.join(" ")
+    }
+}
+
+fn print_profile(profile: &ScaleProfile) {
+    let basis = profile
+        .lattice_basis
+        .as_ref()
+        .map_or("none".to_string(), |basis| format!("{basis:?}"));
+    let rows = [
+        ("Name", profile.name.clone().unwrap_or("-".to_string())),
+        ("Brightest mode", profile.word.clone()),
+        ("Step signature", sig_string(&profile.word)),
+        ("Reversed", profile.reversed.clone()),
+        ("Chirality", chirality_name(profile.chirality).to_string()),
+        ("Maximum variety", profile.mv.to_string()),
+        ("Guide frame", guide_string(profile)),
+        ("Monotone MOS", monotone_string(profile)),
+        ("Lattice basis", basis),
+        ("ED join", format!("{:?}", profile.ed_join)),
+    ];
+    for (name, value) in rows {
+        println!("{name:16}{value}");
+    }
+}
+
+fn print_ji_tunings(tunings: &[Vec<String>]) {
+    let arity = tunings.first().map_or(3, Vec::len);
+    let letters = ["L", "m", "s"];
+    let headers: Vec<&str> = letters.iter().copied().take(arity).collect();
+    print_table(&headers, tunings);
+}
+
+fn print_ed_tunings(tunings: &[Vec<String>], vals: &[String]) {
+    let rows: Vec<Vec<String>> = tunings
+        .iter()
+        .zip(vals)
+        .map(|(tuning, val)| [tuning.clone(), vec![val.clone()]].concat())
+        .collect();
+    print_table(&["L", "m", "s", "val"], &rows);
+}
+
+/// Stream batch responses for the requests in `input` (or standard input) to standard output,
+/// starting from the profiles and tunings in `catalog` if given.
+fn run_batch(input: Option<&str>, catalog: Option<&str>) -> Result<(), String> {
+    let mut batch = match catalog {
+        None => Batch::new(),
+        Some(path) => {
+            let file = File::open(path).map_err(|e| format!("can't open `{path}`: {e}"))?;
+            let cache = ProfileCache::read_catalog(BufReader::new(file))
+                .map_err(|e| format!("can't read catalog `{path}`: {e}"))?;
+            Batch::with_cache(cache)
+        }
+    };
+    let stdout = io::stdout().lock();
+    let stats = match input {
+        None | Some("-") => batch.run(io::stdin().lock(), stdout),
+        Some(path) => {
+            let file = File::open(path).map_err(|e| format!("can't open `{path}`: {e}"))?;
+            batch.run(BufReader::new(file), stdout)
+        }
+    }
+    .map_err(|e| e.to_string())?;
+    if stats.errors > 0 {
+        eprintln!("{} of {} requests failed", stats.errors, stats.requests);
+    }
+    Ok(())
+}
+
+/// Draw the lattice of `word` as SVG to `output`, or to standard output if `output` is `-`.
+fn write_lattice_svg(word: &[usize], options: &SvgOptions, output: &str) -> Result<(), String> {
+    let svg = lattice_svg(word, options).map_err(|e| e.to_string())?;
+    if output == "-" {
+        print!("{svg}");
+        return Ok(());
+    }
+    std::fs::write(output, svg).map_err(|e| format!("can't write `{output}`: {e}"))
+}
+
+/// Write the profiles of all ternary scales with at most `max_notes` notes to `output`.
+fn run_catalog(max_notes: usize, output: &str) -> Result<(), String> {
+    let mut cache = ProfileCache::new();
+    cache.precompute(max_notes);
+    let file = File::create(output).map_err(|e| format!("can't create `{output}`: {e}"))?;
+    let mut writer = BufWriter::new(file);
+    cache
+        .write_catalog(&mut writer)
+        .and_then(|()| writer.flush())
+        .map_err(|e| format!("can't write `{output}`: {e}"))?;
+    eprintln!("{} profiles written to `{output}`", cache.stats().profiles);
+    Ok(())
+}
+
+fn run(matches: &ArgMatches) -> Result<(), String> {
+    let (name, sub) = matches.subcommand().expect("a subcommand is required");
+    match name {
+        "batch" => {
+            return run_batch(
+                sub.get_one::<String>("input").map(String::as_str),
+                sub.get_one::<String>("catalog").map(String::as_str),
+            );
+        }
+        "catalog" => {
+            return run_catalog(
+                *sub.get_one::<usize>("max-notes").expect("required"),
+                sub.get_one::<String>("output").expect("required"),
+            );
+        }
+        _ => {}
+    }
+    let json = sub.get_flag("json");
+    let equave = || parse_equave(sub.get_one::<String>("equave").expect("has a default"));
+    let s_bounds = || {
+        (
+            *sub.get_one::<f64>("s-lower").expect("has a default"),
+            *sub.get_one::<f64>("s-upper").expect("has a default"),
+        )
+    };
+    let ed_bound = || *sub.get_one::<i32>("ed-bound").expect("has a default");
+    match name {
+        "word" => {
+            let word = parse_word(sub.get_one::<String>("word").expect("required"))?;
+            let (s_lower, s_upper) = s_bounds();
+            let result = word_to_result(&word, equave()?, ed_bound(), s_lower, s_upper);
+            if json {
+                return print_json(&result);
+            }
+            println!("{:16}{}", "Word", numbers_to_string(&word));
+            print_profile(&result.profile);
+            println!("\nJI tunings");
+            print_ji_tunings(&result.ji_tunings);
+            println!("\nED tunings");
+            print_ed_tunings(&result.ed_tunings, &result.ed_vals);
+        }
+        "sig" => {
+            let step_sig = parse_sig(sub.get_one::<String>("sig").expect("required"))?;
+            let filter = SigFilter {
+                lm: sub.get_flag("lm"),
+                ms: sub.get_flag("ms"),
+                s0: sub.get_flag("s0"),
+                ggs_len: *sub.get_one::<u8>("ggs-len").expect("has a default"),
+                ggs_len_exact: sub.get_flag("ggs-exact"),
+                mv: *sub.get_one::<u8>("mv").expect("has a default"),
+                mv_exact: sub.get_flag("mv-exact"),
+                mos_subst: sub.get_flag("mos-subst"),
+            };
+            let (s_lower, s_upper) = s_bounds();
+            let mut result =
+                sig_to_result(&step_sig, &filter, equave()?, ed_bound(), s_lower, s_upper);
+            let sort: ProfileSort = sub
+                .get_one::<String>("sort")
+                .expect("has a default")
+                .parse()
+                .map_err(|e: ternary::Error| e.to_string())?;
+            let descending = sub.get_flag("descending");
+            result
+                .profiles
+                .sort_by(|a, b| sort.compare(a, b, descending));
+            if json {
+                return print_json(&result);
+            }
+            let rows: Vec<Vec<String>> = result
+                .profiles
+                .iter()
+                .enumerate()
+                .map(|(i, profile)| {
+                    vec![
+                        (i + 1).to_string(),
+                        profile.word.clone(),
+                        profile.mv.to_string(),
+                        chirality_name(profile.chirality).to_string(),
+                        profile
+                            .structure
+                            .as_ref()
+                            .map_or("-".to_string(), |guide| guide.complexity.to_string()),
+                        monotone_string(profile),
+                    ]
+                })
+                .collect();
+            println!("Scales ({})", rows.len());
+            print_table(
+                &["#", "word", "mv", "chirality", "complexity", "monotone"],
+                &rows,
+            );
+            println!("\nJI tunings");
+            print_ji_tunings(&result.ji_tunings);
+            println!("\nED tunings");
+            print_ed_tunings(&result.ed_tunings, &result.ed_vals);
+        }
+        "ji" => {
+            let step_sig = parse_sig(sub.get_one::<String>("sig").expect("required"))?;
+            let (s_lower, s_upper) = s_bounds();
+            let tunings = if sub.get_flag("slow") {
+                sig_to_ji_tunings_slow(&step_sig, equave()?, s_lower, s_upper)
+            } else {
+                sig_to_ji_tunings(&step_sig, equave()?, s_lower, s_upper)
+            };
+            if json {
+                return print_json(&json!({ "ji_tunings": tunings }));
+            }
+            print_ji_tunings(&tunings);
+        }
+        "ed" => {
+            let step_sig = parse_sig(sub.get_one::<String>("sig").expect("required"))?;
+            let equave = equave()?;
+            let (s_lower, s_upper) = s_bounds();
+            let (tunings, vals) = sig_to_ed_tunings_with_vals(
+                &step_sig,
+                equave,
+                ed_bound(),
+                s_lower,
+                s_upper,
+                ternary::ED_VAL_ODD_LIMIT,
+            );
+            if json {
+                return print_json(&json!({ "ed_tunings": tunings, "ed_vals": vals }));
+            }
+            print_ed_tunings(&tunings, &vals);
+        }
+        "names" => {
+            let scales = match sub.get_one::<String>("sig") {
+                Some(sig) => scales_with_sig(&parse_sig(sig)?, equave()?),
+                None => search_scales(sub.get_one::<String>("query").map_or("", String::as_str)),
+            };
+            if json {
+                return print_json(&scales);
+            }
+            let rows: Vec<Vec<String>> = scales
+                .iter()
+                .map(|scale| {
+                    vec![
+                        scale.name.to_string(),
+                        scale.word.to_string(),
+                        sig_string(scale.word),
+                        scale.equave.to_string(),
+                        scale.aliases.join(", "),
+                    ]
+                })
+                .collect();
+            print_table(&["name", "word", "sig", "equave", "aliases"], &rows);
+        }
+        "lattice" => {
+            let word = parse_word(sub.get_one::<String>("word").expect("required"))?;
+            let tuning = sub
+                .get_one::<String>("tuning")
+                .map(|steps| parse_tuning(steps))
+                .transpose()?;
+            let score = sub
+                .get_one::<String>("bases")
+                .map(|score| {
+                    let interval = sub.get_one::<String>("interval").expect("has a default");
+                    let interval: RawJiRatio = interval
+                        .parse()
+                        .map_err(|_| format!("invalid interval `{interval}`"))?;
+                    BasisScore::with_name(score, interval, tuning.clone())
+                        .map_err(|e| format!("--bases {score}: {e}"))
+                })
+                .transpose()?;
+            if let Some(output) = sub.get_one::<String>("svg") {
+                let highlight = if let Some(&mode) = sub.get_one::<usize>("mode") {
+                    Highlight::Mode(mode)
+                } else if let Some(chord) = sub.get_one::<String>("chord") {
+                    let degrees = chord
+                        .split(',')
+                        .map(|degree| {
+                            degree
+                                .trim()
+                                .parse()
+                                .map_err(|_| format!("invalid degree `{degree}`"))
+                        })
+                        .collect::<Result<_, _>>()?;
+                    Highlight::Chord(degrees)
+                } else {
+                    Highlight::None
+                };
+                let basis = match score {
+                    Some(score) => ranked_lattice_bases(&word, &score)
+                        .map_err(|e| e.to_string())?
+                        .into_iter()
+                        .next()
+                        .map(|scored| scored.basis),
+                    None => None,
+                };
+                let options = SvgOptions {
+                    tuning,
+                    basis,
+                    highlight,
+                    ..Default::default()
+                };
+                return write_lattice_svg(&word, &options, output);
+            }
+            if let Some(score) = score {
+                let bases = ranked_lattice_bases(&word, &score).map_err(|e| e.to_string())?;
+                if json {
+                    return print_json(&bases);
+                }
+                let rows: Vec<Vec<String>> = bases
+                    .iter()
+                    .map(|scored| {
+                        vec![
+                            format!("{:?}", scored.basis.vx()),
+                            format!("{:?}", scored.basis.vy()),
+                            scored.shape.row_count().to_string(),
+                            scored.shape.full_row_len().to_string(),
+                            format!("{:.3}", scored.score),
+                        ]
+                    })
+                    .collect();
+                print_table(
+                    &["row generator", "other", "rows", "row length", "score"],
+                    &rows,
+                );
+                return Ok(());
+            }
+            let result = word_to_lattice_result(&word);
+            if json {
+                return print_json(&result);
+            }
+            let Some(result) = result else {
+                println!("No lattice for an empty word.");
+                return Ok(());
+            };
+            if let Some(fallback) = result.fallback {
+                println!("No 2D lattice: {}.", fallback.reason);
+                let [period, v, w] = fallback.basis;
+                println!("Basis: {period:?} (period), {v:?}, {w:?}");
+                let rows: Vec<Vec<String>> = fallback
+                    .coordinates
+                    .iter()
+                    .enumerate()
+                    .map(|(degree, coords)| {
+                        let mut row = vec![degree.to_string()];
+                        row.extend(coords.iter().map(i32::to_string));
+                        row
+                    })
+                    .collect();
+                print_table(&["degree", "layer", "x", "y"], &rows);
+                return Ok(());
+            }
+            println!("Basis: {:?}, {:?}", result.basis[0], result.basis[1]);
+            let rows: Vec<Vec<String>> = result
+                .coordinates
+                .iter()
+                .enumerate()
+                .map(|(degree, coords)| {
+                    let mut row = vec![degree.to_string()];
+                    row.extend(coords.iter().map(i32::to_string));
+                    row
+                })
+                .collect();
+            print_table(&["degree", "x", "y"], &rows);
+        }
+        "temper" => {
+            let word = parse_word(sub.get_one::<String>("word").expect("required"))?;
+            let steps = parse_ji_tuning(sub.get_one::<String>("tuning").expect("required"), &word)?;
+            let commas = sub
+                .get_one::<String>("comma")
+                .map_or(Ok(vec![]), |commas| parse_commas(commas))?;
+            let temperament =
+                Temperament::from_step_tuning(&steps, &commas).map_err(|e| e.to_string())?;
+            let name = step_tuning_name(&steps, &commas).map_err(|e| e.to_string())?;
+            let comma_basis: Vec<String> =
+                temperament.comma_basis().iter().map(monzo_string).collect();
+            // The scale's equave is what the word's steps add up to.
+            let equave = word.iter().map(|&letter| steps[letter]).sum::<Monzo>();
+            let method = sub
+                .get_one::<String>("method")
+                .map(|method| match method.as_str() {
+                    "te" => TuningMethod::Te,
+                    "cte" => TuningMethod::Cte { equave },
+                    "pote" => TuningMethod::Pote,
+                    "minimax" => TuningMethod::Minimax {
+                        odd: *sub.get_one::<u32>("odd").expect("has a default"),
+                        equave,
+                    },
+                    _ => unreachable!("checked by the value parser"),
+                });
+            let optimal = method
+                .map(|method| optimal_tuning(&temperament, &method))
+                .transpose()
+                .map_err(|e| e.to_string())?;
+            let step_cents = optimal
+                .as_ref()
+                .map(|optimal| optimal.step_tuning(&steps).step_cents());
+            if json {
+                let mapping: Vec<Vec<i32>> = temperament
+                    .mapping()
+                    .iter()
+                    .map(|val| val.into_inner().as_array()[..temperament.dim()].to_vec())
+                    .collect();
+                return print_json(&json!({
+                    "temperament": name,
+                    "rank": temperament.rank(),
+                    "mapping": mapping,
+                    "commas": comma_basis,
+                    "generators": optimal.map(|optimal| optimal.generators),
+                    "step_cents": step_cents,
+                }));
+            }
+            let rows = [
+                ("Temperament", name),
+                ("Rank", temperament.rank().to_string()),
+                ("Mapping", temperament.to_string()),
+                (
+                    "Commas",
+                    if comma_basis.is_empty() {
+                        "-".to_string()
+                    } else {
+                        comma_basis.join(", ")
+                    },
+                ),
+            ];
+            for (name, value) in rows {
+                println!("{name:16}{value}");
+            }
+            if let (Some(optimal), Some(step_cents)) = (optimal, step_cents) {
+                let generators: Vec<String> = optimal
+                    .generators
+                    .iter()
+                    .map(|cents| format!("{cents:.3}"))
+                    .collect();
+                println!("{:16}{}", "Generators", generators.join(", "));
+                println!("\nStep sizes in cents");
+                print_ji_tunings(&[step_cents
+                    .iter()
+                    .map(|cents| format!("{cents:.3}"))
+                    .collect()]);
+            }
+        }
+        _ => unreachable!("unknown subcommand"),
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let matches = command().get_matches();
+    match run(&matches) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,335 @@
+//! Tuning bridges between ED and JI tunings of a scale.
+//!
+//! An ED tuning of a scale word (step counts of an ED) and a JI tuning of the same word
+//! (step sizes as monzos) are related by a [`Val`]: the ED tuning is a tempering of the JI
+//! tuning exactly when the val maps every JI step to the corresponding ED step count.
+//! A [`TuningBridge`] records, for the steps and for every k-step of the scale, whether the
+//! JI interpretation is consistent with the ED, and which commas the ED tempers out.
+//!
+//! # Examples
+//!
+//! ```
+//! use ternary::bridge::{ed_val, tuning_bridge};
+//! use ternary::ji_ratio::RawJiRatio;
+//! use ternary::monzo::Monzo;
+//! use ternary::monzo;
+//!
+//! // Diasem (LmLsLmLsL) in 26edo with L = 9/8, m = 28/27, s = 64/63
+//! let diasem = [0, 1, 0, 2, 0, 1, 0, 2, 0];
+//! let ji = [monzo![-3, 2], monzo![2, -3, 0, 1], monzo![6, -2, 0, -1]];
+//! let commas = [Monzo::try_new(81, 80).unwrap(), Monzo::try_new(64, 63).unwrap()];
+//! let val = ed_val(26, RawJiRatio::OCTAVE);
+//! let bridge = tuning_bridge(&diasem, &[4, 2, 1], 26, RawJiRatio::OCTAVE, &val, &ji, &commas);
+//!
+//! assert!(bridge.is_consistent());
+//! // 26edo tempers out 81/80 but not 64/63.
+//! assert_eq!(bridge.tempered_commas, vec![Monzo::try_new(81, 80).unwrap()]);
+//! ```
+
+use itertools::Itertools;
+
+use crate::equal::{Val, gpval};
+use crate::interval::Dyad;
+use crate::ji::{odd_limit, solve_step_sig_fast};
+use crate::ji_ratio::RawJiRatio;
+use crate::monzo::Monzo;
+use crate::primes::{SMALL_PRIMES, SMALL_PRIMES_COUNT};
+use crate::tuning::StepTuning;
+use crate::words::{CountVector, Letter};
+
+/// An interval of a scale with both its JI interpretation and its ED tuning.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IntervalBridge {
+    /// The step content of the interval.
+    pub interval: CountVector<Letter>,
+    /// The JI interpretation of the interval.
+    pub ji: Monzo,
+    /// The step count of the interval in the ED tuning.
+    pub ed_steps: i32,
+    /// The step count the val maps the JI interpretation to.
+    pub val_steps: i32,
+}
+
+impl IntervalBridge {
+    /// Whether the val maps the JI interpretation to the interval's step count in the ED tuning.
+    pub fn is_consistent(&self) -> bool {
+        self.ed_steps == self.val_steps
+    }
+}
+
+/// A report linking an ED tuning of a scale word with a JI tuning of it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TuningBridge {
+    /// The number of equal divisions of the equave.
+    pub ed: i32,
+    /// The equave.
+    pub equave: RawJiRatio,
+    /// The val of the ED used to map JI intervals to ED steps.
+    pub val: Val,
+    /// The steps of the scale, one per step letter.
+    pub steps: Vec<IntervalBridge>,
+    /// Entry `k - 1` contains every size of `k`-step in the scale.
+    pub interval_classes: Vec<Vec<IntervalBridge>>,
+    /// The commas from the candidate list that the val tempers out.
+    pub tempered_commas: Vec<Monzo>,
+}
+
+impl TuningBridge {
+    /// Whether every interval of the scale is consistent between the JI and ED tunings.
+    pub fn is_consistent(&self) -> bool {
+        self.steps.iter().all(IntervalBridge::is_consistent)
+            && self
+                .interval_classes
+                .iter()
+                .flatten()
+                .all(IntervalBridge::is_consistent)
+    }
+}
+
+/// The generalized patent val of `ed`-ed<`equave`>, which is the patent val of the possibly
+/// fractional EDO with the same step size.
+pub fn ed_val(ed: i32, equave: RawJiRatio) -> Val {
+    gpval(ed as f64 * 1200.0 / equave.cents())
+}
+
+/// The commas in `candidates` tempered out by `val`.
+pub fn tempered_commas(val: &Val, candidates: &[Monzo]) -> Vec<Monzo> {
+    candidates
+        .iter()
+        .copied()
+        .filter(|&comma| comma != Monzo::UNISON && val.evaluate(comma) == 0)
+        .collect()
+}
+
+/// The primes whose exponent is nonzero in at least one of `monzos`, as indices into `SMALL_PRIMES`.
+fn prime_support(monzos: &[Monzo]) -> Vec<usize> {
+    (0..SMALL_PRIMES_COUNT)
+        .filter(|&i| monzos.iter().any(|m| m[i] != 0))
+        .collect()
+}
+
+/// Tenney height log2(n * d) of a monzo n/d.
+pub(crate) fn tenney_height(monzo: Monzo) -> f64 {
+    (0..SMALL_PRIMES_COUNT)
+        .map(|i| monzo[i].abs() as f64 * (SMALL_PRIMES[i] as f64).log2())
+        .sum()
+}
+
+/// Search for commas tempered out by `val` among the quotients of pairs of `odd`-odd-limit intervals
+/// that are at most `max_cents` in size, using only the primes in `subgroup`.
+/// The result is sorted by Tenney height.
+pub fn comma_search(val: &Val, odd: u32, max_cents: f64, subgroup: &[Monzo]) -> Vec<Monzo> {
+    let support = prime_support(subgroup);
+    let diamond: Vec<Monzo> = odd_limit(odd)
+        .into_iter()
+        .filter_map(|r| Monzo::try_from_ratio(r).ok())
+        .collect();
+    let candidates: Vec<Monzo> = diamond
+        .iter()
+        .cartesian_product(diamond.iter())
+        .map(|(&a, &b)| a - b)
+        .filter(|&comma| comma.cents() > 0.0 && comma.cents() <= max_cents)
+        .filter(|&comma| (0..SMALL_PRIMES_COUNT).all(|i| comma[i] == 0 || support.contains(&i)))
+        // Different pairs give the same comma, with commas of equal height in between
+        .sorted()
+        .dedup()
+        .sorted_by(|&a, &b| tenney_height(a).total_cmp(&tenney_height(b)))
+        .collect();
+    tempered_commas(val, &candidates)
+}
+
+/// Link the ED tuning `ed_steps` of `word` in `ed`-ed<`equave`> with the JI tuning `ji_steps`
+/// under `val`, and report which of `commas` the val tempers out.
+/// Pass the val the ED tuning is read under, such as the one
+/// [`sig_to_ed_tunings_with_vals`](crate::sig_to_ed_tunings_with_vals) reports, or [`ed_val`].
+pub fn tuning_bridge(
+    word: &[Letter],
+    ed_steps: &[i32],
+    ed: i32,
+    equave: RawJiRatio,
+    val: &Val,
+    ji_steps: &[Monzo],
+    commas: &[Monzo],
+) -> TuningBridge {
+    let ji_tuning = StepTuning::Ji(ji_steps.to_vec());
+    let ed_tuning = StepTuning::Ed {
+        steps: ed_steps.to_vec(),
+        ed,
+        equave,
+    };
+    let bridge_interval = |interval: CountVector<Letter>| {
+        let ji = ji_tuning
+            .interval_monzo(&interval)
+            .expect("a JI tuning gives every interval a monzo");
+        IntervalBridge {
+            ji,
+            ed_steps: ed_tuning
+                .interval_ed_steps(&interval)
+                .expect("an ED tuning gives every interval a step count"),
+            val_steps: val.evaluate(ji),
+            interval,
+        }
+    };
+    let steps = (0..ji_steps.len())
+        .map(|letter| bridge_interval(CountVector::from_slice(&[letter])))
+        .collect();
+    let interval_classes = (1..word.len())
+        .map(|k| {
+            CountVector::spectrum(word, k)
+                .into_inner()
+                .into_keys()
+                .map(bridge_interval)
+                .collect()
+        })
+        .collect();
+    TuningBridge {
+        ed,
+        equave,
+        val: *val,
+        steps,
+        interval_classes,
+        tempered_commas: tempered_commas(val, commas),
+    }
+}
+
+/// The JI tunings of `step_sig` found by [`solve_step_sig_fast`] whose steps the generalized
+/// patent val of `ed`-ed<`equave`> maps to `ed_steps`, i.e. the JI tunings the ED tuning tempers.
+pub fn consistent_ji_tunings(
+    step_sig: &[usize],
+    ed_steps: &[i32],
+    ed: i32,
+    equave: RawJiRatio,
+    cents_lower_bound: f64,
+    cents_upper_bound: f64,
+) -> Vec<Vec<Monzo>> {
+    let Ok(equave_monzo) = Monzo::try_from_ratio(equave) else {
+        return vec![];
+    };
+    let val = ed_val(ed, equave);
+    solve_step_sig_fast(step_sig, equave_monzo, cents_lower_bound, cents_upper_bound)
+        .into_iter()
+        .filter(|steps| {
+            steps
+                .iter()
+                .zip(ed_steps)
+                .all(|(&ji, &ed_step)| val.evaluate(ji) == ed_step)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monzo;
+
+    fn diasem() -> (Vec<Letter>, Vec<Monzo>) {
+        (
+            vec![0, 1, 0, 2, 0, 1, 0, 2, 0],
+            vec![monzo![-3, 2], monzo![2, -3, 0, 1], monzo![6, -2, 0, -1]],
+        )
+    }
+
+    #[test]
+    fn test_tuning_bridge() {
+        let (word, ji) = diasem();
+        let commas = [
+            Monzo::try_new(81, 80).unwrap(),
+            Monzo::try_new(64, 63).unwrap(),
+            Monzo::try_new(50, 49).unwrap(),
+        ];
+        let val = ed_val(26, RawJiRatio::OCTAVE);
+        let bridge = tuning_bridge(
+            &word,
+            &[4, 2, 1],
+            26,
+            RawJiRatio::OCTAVE,
+            &val,
+            &ji,
+            &commas,
+        );
+        assert!(bridge.is_consistent());
+        assert_eq!(bridge.steps.len(), 3);
+        assert_eq!(bridge.interval_classes.len(), 8);
+        // The equave-complement of each k-step class has the same number of sizes.
+        for k in 1..8 {
+            assert_eq!(
+                bridge.interval_classes[k - 1].len(),
+                bridge.interval_classes[8 - k].len()
+            );
+        }
+        assert_eq!(
+            bridge.tempered_commas,
+            vec![
+                Monzo::try_new(81, 80).unwrap(),
+                Monzo::try_new(50, 49).unwrap()
+            ]
+        );
+
+        // In 23edo with L = 3, m = s = 2, the patent val maps 28/27 to 3 steps instead of 2.
+        let val = ed_val(23, RawJiRatio::OCTAVE);
+        let bridge = tuning_bridge(&word, &[3, 2, 2], 23, RawJiRatio::OCTAVE, &val, &ji, &[]);
+        assert!(!bridge.is_consistent());
+        assert!(bridge.steps[0].is_consistent());
+        assert_eq!(bridge.steps[1].val_steps, 3);
+
+        // 35edo's tuning L = 5, m = 4, s = 1 is read as 9/8, 28/27, 64/63 under 35df, not the patent val.
+        let (ed_tunings, vals) = crate::sig_to_ed_tunings_with_vals(
+            &[5, 2, 2],
+            RawJiRatio::OCTAVE,
+            35,
+            20.0,
+            250.0,
+            crate::ED_VAL_ODD_LIMIT,
+        );
+        let index = ed_tunings
+            .iter()
+            .position(|t| *t == ["5\\35", "4\\35", "1\\35"])
+            .unwrap();
+        assert_eq!(vals[index], "35df");
+        let val: Val = vals[index].parse().unwrap();
+        let bridge = tuning_bridge(&word, &[5, 4, 1], 35, RawJiRatio::OCTAVE, &val, &ji, &[]);
+        assert!(bridge.is_consistent());
+        let patent = ed_val(35, RawJiRatio::OCTAVE);
+        assert!(
+            !tuning_bridge(&word, &[5, 4, 1], 35, RawJiRatio::OCTAVE, &patent, &ji, &[])
+                .is_consistent()
+        );
+    }
+
+    #[test]
+    fn test_comma_search() {
+        let (_, ji) = diasem();
+        let val = ed_val(26, RawJiRatio::OCTAVE);
+        // In the 2.3.5.7 subgroup, 26edo tempers out both 81/80 and 50/49.
+        let subgroup = [ji.clone(), vec![monzo![0, 0, 1]]].concat();
+        let commas = comma_search(&val, 9, 50.0, &subgroup);
+        assert!(commas.contains(&Monzo::try_new(81, 80).unwrap()));
+        assert!(commas.contains(&Monzo::try_new(50, 49).unwrap()));
+        assert_eq!(commas.iter().sorted().dedup().count(), commas.len());
+        for &comma in &commas {
+            assert_eq!(val.evaluate(comma), 0);
+            assert!(comma.cents() > 0.0 && comma.cents() <= 50.0);
+        }
+        // Diasem's JI tuning is in the 2.3.7 subgroup, so no comma involving 5 is found.
+        assert!(
+            comma_search(&val, 9, 50.0, &ji)
+                .iter()
+                .all(|comma| comma[2] == 0)
+        );
+    }
+
+    #[test]
+    fn test_consistent_ji_tunings() {
+        let tunings =
+            consistent_ji_tunings(&[5, 2, 2], &[4, 2, 1], 26, RawJiRatio::OCTAVE, 20.0, 300.0);
+        let (_, ji) = diasem();
+        assert!(tunings.contains(&ji));
+        let val = ed_val(26, RawJiRatio::OCTAVE);
+        for tuning in tunings {
+            assert_eq!(
+                tuning.iter().map(|&m| val.evaluate(m)).collect::<Vec<_>>(),
+                vec![4, 2, 1]
+            );
+        }
+    }
+}
@@ -0,0 +1,559 @@
+//! Memoized scale profiles and tunings, and catalogs of them for saving to disk.
+//!
+//! Profiling a scale runs the guide frame search, the maximum variety computation and the
+//! lattice basis search, and tuning a step signature runs the JI and ED solvers. A
+//! [`ProfileCache`] keeps the results, so a word or step signature queried again, such as the
+//! scales of a step signature queried with another filter, is only analysed once.
+//!
+//! Profiles are keyed by the brightest mode [`least_mode`] of a word, so every mode of a scale
+//! shares one entry: the profile of its brightest mode, which is what the scales of a step
+//! signature are profiled as. Tunings are keyed by the step signature, the equave and the
+//! search bounds.
+//!
+//! A cache converts to and from a [`Catalog`], a serializable list of its entries tagged with
+//! the [`CATALOG_VERSION`] of its layout and the [`SCHEMA_VERSION`] of the result types.
+//! With the `catalog` feature, [`ProfileCache::write_catalog`] and
+//! [`ProfileCache::read_catalog`] save and load catalogs as JSON, so that the profiles of all
+//! scales up to some size can be precomputed once with [`ProfileCache::precompute`].
+//!
+//! # Examples
+//!
+//! ```
+//! use ternary::cache::ProfileCache;
+//! use ternary::ji_ratio::RawJiRatio;
+//! use ternary::{SigFilter, sig_to_result, string_to_numbers, word_to_profile};
+//!
+//! let mut cache = ProfileCache::new();
+//! let result = cache.sig_result(&[4, 2, 1], &SigFilter::default(), RawJiRatio::OCTAVE, 53, 20.0, 250.0);
+//! assert_eq!(result, sig_to_result(&[4, 2, 1], &SigFilter::default(), RawJiRatio::OCTAVE, 53, 20.0, 250.0));
+//! assert_eq!(cache.stats().profiles, 15);
+//!
+//! // Another mode of a cached scale is a cache hit.
+//! let profile = cache.profile(&string_to_numbers("LmLLsLm"));
+//! assert_eq!(profile, word_to_profile(&string_to_numbers("LLsLmLm")));
+//! assert_eq!(cache.stats().profiles, 15);
+//!
+//! let reloaded = ProfileCache::from_catalog(cache.to_catalog()).unwrap();
+//! assert_eq!(reloaded.stats(), cache.stats());
+//! ```
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::par_map;
+use crate::interval::JiRatio;
+use crate::ji_ratio::RawJiRatio;
+use crate::words::{Letter, least_mode};
+use crate::{
+    ED_VAL_ODD_LIMIT, SCHEMA_VERSION, ScaleProfile, SchemaMismatch, SigFilter, SigResult,
+    SigSearch, WordResult, parse_equave, parse_ternary_word, sig_to_ed_tunings_with_vals,
+    sig_to_ji_tunings, sig_to_ji_tunings_slow, with_name, word_to_profile, word_to_sig,
+};
+
+/// The version of the layout of a [`Catalog`].
+/// Bumped whenever a field is added, removed or changes meaning.
+pub const CATALOG_VERSION: u32 = 1;
+
+/// The ED tunings of a step signature with the val each is best read under.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EdTuningsResult {
+    /// ED tunings as step counts such as `4\22`
+    pub ed_tunings: Vec<Vec<String>>,
+    /// the val each ED tuning is best read under
+    pub ed_vals: Vec<String>,
+}
+
+/// A key for cached tunings: step signature, equave and the bit patterns of the numeric bounds.
+/// JI tunings don't depend on the ED bound, so their keys have an ED bound of 0.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct TuningKey {
+    sig: Vec<usize>,
+    equave: (u32, u32),
+    ed_bound: i32,
+    s_lower: u64,
+    s_upper: u64,
+}
+
+impl TuningKey {
+    fn new(sig: &[usize], equave: RawJiRatio, ed_bound: i32, s_lower: f64, s_upper: f64) -> Self {
+        Self {
+            sig: sig.to_vec(),
+            equave: (equave.numer(), equave.denom()),
+            ed_bound,
+            s_lower: s_lower.to_bits(),
+            s_upper: s_upper.to_bits(),
+        }
+    }
+
+    fn equave(&self) -> String {
+        format!("{}/{}", self.equave.0, self.equave.1)
+    }
+}
+
+/// Numbers of entries in a [`ProfileCache`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// scale profiles
+    pub profiles: usize,
+    /// JI tuning lists, counting those of the fast and the slow solver separately
+    pub ji_tunings: usize,
+    /// ED tuning lists
+    pub ed_tunings: usize,
+}
+
+/// Scale profiles and tunings, memoized across queries.
+#[derive(Clone, Debug, Default)]
+pub struct ProfileCache {
+    profiles: HashMap<Vec<Letter>, ScaleProfile>,
+    ji_tunings: HashMap<(TuningKey, bool), Vec<Vec<String>>>,
+    ed_tunings: HashMap<TuningKey, EdTuningsResult>,
+}
+
+impl ProfileCache {
+    /// An empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of entries of each kind.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            profiles: self.profiles.len(),
+            ji_tunings: self.ji_tunings.len(),
+            ed_tunings: self.ed_tunings.len(),
+        }
+    }
+
+    /// The profile of the brightest mode of `word`.
+    pub fn profile(&mut self, word: &[Letter]) -> ScaleProfile {
+        let mode = least_mode(word);
+        self.profiles
+            .entry(mode)
+            .or_insert_with_key(|mode| word_to_profile(mode))
+            .clone()
+    }
+
+    /// The profiles of the brightest modes of `words`, computing the missing ones
+    /// on all cores with the `parallel` feature.
+    pub fn profiles(&mut self, words: &[Vec<Letter>]) -> Vec<ScaleProfile> {
+        let modes: Vec<Vec<Letter>> = words.iter().map(|word| least_mode(word)).collect();
+        let mut missing: Vec<Vec<Letter>> = modes
+            .iter()
+            .filter(|mode| !self.profiles.contains_key(*mode))
+            .cloned()
+            .collect();
+        missing.sort();
+        missing.dedup();
+        let profiles = par_map(&missing, |mode| word_to_profile(mode));
+        self.profiles.extend(missing.into_iter().zip(profiles));
+        modes
+            .iter()
+            .map(|mode| self.profiles[mode].clone())
+            .collect()
+    }
+
+    /// The JI tunings of `step_sig`, as [`sig_to_ji_tunings`], or as
+    /// [`sig_to_ji_tunings_slow`] if `slow` is true.
+    pub fn ji_tunings(
+        &mut self,
+        step_sig: &[usize],
+        equave: RawJiRatio,
+        s_lower: f64,
+        s_upper: f64,
+        slow: bool,
+    ) -> Vec<Vec<String>> {
+        let key = TuningKey::new(step_sig, equave, 0, s_lower, s_upper);
+        self.ji_tunings
+            .entry((key, slow))
+            .or_insert_with(|| {
+                if slow {
+                    sig_to_ji_tunings_slow(step_sig, equave, s_lower, s_upper)
+                } else {
+                    sig_to_ji_tunings(step_sig, equave, s_lower, s_upper)
+                }
+            })
+            .clone()
+    }
+
+    /// The ED tunings of `step_sig` with their vals, as [`sig_to_ed_tunings_with_vals`].
+    pub fn ed_tunings(
+        &mut self,
+        step_sig: &[usize],
+        equave: RawJiRatio,
+        ed_bound: i32,
+        s_lower: f64,
+        s_upper: f64,
+    ) -> EdTuningsResult {
+        let key = TuningKey::new(step_sig, equave, ed_bound, s_lower, s_upper);
+        self.ed_tunings
+            .entry(key)
+            .or_insert_with(|| {
+                let (ed_tunings, ed_vals) = sig_to_ed_tunings_with_vals(
+                    step_sig,
+                    equave,
+                    ed_bound,
+                    s_lower,
+                    s_upper,
+                    ED_VAL_ODD_LIMIT,
+                );
+                EdTuningsResult {
+                    ed_tunings,
+                    ed_vals,
+                }
+            })
+            .clone()
+    }
+
+    /// The profile and tunings of a scale word, as [`word_to_result`](crate::word_to_result)
+    /// but with the profile of its brightest mode.
+    pub fn word_result(
+        &mut self,
+        word: &[Letter],
+        equave: RawJiRatio,
+        ed_bound: i32,
+        s_lower: f64,
+        s_upper: f64,
+    ) -> WordResult {
+        let step_sig = word_to_sig(word);
+        let ed = self.ed_tunings(&step_sig, equave, ed_bound, s_lower, s_upper);
+        WordResult {
+            profile: with_name(self.profile(word), equave),
+            ji_tunings: self.ji_tunings(&step_sig, equave, s_lower, s_upper, false),
+            ed_tunings: ed.ed_tunings,
+            ed_vals: ed.ed_vals,
+        }
+    }
+
+    /// The scales and tunings of a step signature, as [`sig_to_result`](crate::sig_to_result).
+    pub fn sig_result(
+        &mut self,
+        step_sig: &[usize],
+        filter: &SigFilter,
+        equave: RawJiRatio,
+        ed_bound: i32,
+        s_lower: f64,
+        s_upper: f64,
+    ) -> SigResult {
+        let search = SigSearch::new(step_sig, filter.clone(), equave, ed_bound, s_lower, s_upper);
+        let (result, cache) = search.with_cache(std::mem::take(self)).finish_with_cache();
+        *self = cache;
+        result
+    }
+
+    /// Profile every scale of every ternary step signature with at most `max_notes` notes.
+    pub fn precompute(&mut self, max_notes: usize) {
+        for notes in 3..=max_notes {
+            for l in 1..=notes - 2 {
+                for m in 1..=notes - l - 1 {
+                    let scales = crate::comb::necklaces_fixed_content(&[l, m, notes - l - m]);
+                    self.profiles(&scales);
+                }
+            }
+        }
+    }
+
+    /// The entries of this cache in a serializable form, sorted so that equal caches give
+    /// equal catalogs.
+    pub fn to_catalog(&self) -> Catalog {
+        let mut profiles: Vec<ScaleProfile> = self.profiles.values().cloned().collect();
+        profiles.sort_by(|a, b| (a.word.len(), &a.word).cmp(&(b.word.len(), &b.word)));
+        let mut ji_tunings: Vec<JiTuningsEntry> = self
+            .ji_tunings
+            .iter()
+            .map(|((key, slow), tunings)| JiTuningsEntry {
+                sig: key.sig.clone(),
+                equave: key.equave(),
+                s_lower: f64::from_bits(key.s_lower),
+                s_upper: f64::from_bits(key.s_upper),
+                slow: *slow,
+                ji_tunings: tunings.clone(),
+            })
+            .collect();
+        ji_tunings.sort_by(|a, b| {
+            (&a.sig, &a.equave, a.slow)
+                .cmp(&(&b.sig, &b.equave, b.slow))
+                .then(a.s_lower.total_cmp(&b.s_lower))
+                .then(a.s_upper.total_cmp(&b.s_upper))
+        });
+        let mut ed_tunings: Vec<EdTuningsEntry> = self
+            .ed_tunings
+            .iter()
+            .map(|(key, result)| EdTuningsEntry {
+                sig: key.sig.clone(),
+                equave: key.equave(),
+                ed_bound: key.ed_bound,
+                s_lower: f64::from_bits(key.s_lower),
+                s_upper: f64::from_bits(key.s_upper),
+                result: result.clone(),
+            })
+            .collect();
+        ed_tunings.sort_by(|a, b| {
+            (&a.sig, &a.equave, a.ed_bound)
+                .cmp(&(&b.sig, &b.equave, b.ed_bound))
+                .then(a.s_lower.total_cmp(&b.s_lower))
+                .then(a.s_upper.total_cmp(&b.s_upper))
+        });
+        Catalog {
+            catalog_version: CATALOG_VERSION,
+            schema_version: SCHEMA_VERSION,
+            profiles,
+            ji_tunings,
+            ed_tunings,
+        }
+    }
+
+    /// A cache holding the entries of `catalog`, if it has the current versions
+    /// and all its words and equaves are valid, with every profile of a brightest mode.
+    pub fn from_catalog(catalog: Catalog) -> Result<Self, BadCatalog> {
+        if catalog.catalog_version != CATALOG_VERSION {
+            return Err(BadCatalog::Version {
+                found: catalog.catalog_version,
+            });
+        }
+        if catalog.schema_version != SCHEMA_VERSION {
+            return Err(SchemaMismatch {
+                found: catalog.schema_version,
+            }
+            .into());
+        }
+        let entry_error = |e: crate::Error| BadCatalog::Entry(e.to_string());
+        let mut cache = Self::new();
+        for profile in catalog.profiles {
+            let word = parse_ternary_word(&profile.word).map_err(entry_error)?;
+            // Profiles are looked up by brightest mode, so another mode's profile would never be found.
+            if least_mode(&word) != word {
+                return Err(BadCatalog::Entry(format!(
+                    "`{}` is not the brightest mode of its scale",
+                    profile.word
+                )));
+            }
+            cache.profiles.insert(word, profile);
+        }
+        for entry in catalog.ji_tunings {
+            let equave = parse_equave(&entry.equave).map_err(entry_error)?;
+            let key = TuningKey::new(&entry.sig, equave, 0, entry.s_lower, entry.s_upper);
+            cache.ji_tunings.insert((key, entry.slow), entry.ji_tunings);
+        }
+        for entry in catalog.ed_tunings {
+            let equave = parse_equave(&entry.equave).map_err(entry_error)?;
+            let key = TuningKey::new(
+                &entry.sig,
+                equave,
+                entry.ed_bound,
+                entry.s_lower,
+                entry.s_upper,
+            );
+            cache.ed_tunings.insert(key, entry.result);
+        }
+        Ok(cache)
+    }
+}
+
+#[cfg(feature = "catalog")]
+impl ProfileCache {
+    /// Read a cache from a catalog written by [`write_catalog`](ProfileCache::write_catalog).
+    /// The version of the catalog is checked before the rest is read, so a catalog in an older
+    /// layout gives a [`BadCatalog`] error, with [`io::ErrorKind::InvalidData`](std::io::ErrorKind::InvalidData).
+    pub fn read_catalog<R: std::io::Read>(reader: R) -> std::io::Result<Self> {
+        use std::io::{Error, ErrorKind};
+        let value: serde_json::Value = serde_json::from_reader(reader)?;
+        let found = value
+            .get("catalog_version")
+            .and_then(|version| version.as_u64())
+            .unwrap_or(0) as u32;
+        if found != CATALOG_VERSION {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                BadCatalog::Version { found },
+            ));
+        }
+        let catalog: Catalog = serde_json::from_value(value)?;
+        Self::from_catalog(catalog).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+
+    /// Write this cache as a JSON [`Catalog`].
+    pub fn write_catalog<W: std::io::Write>(&self, writer: W) -> std::io::Result<()> {
+        serde_json::to_writer(writer, &self.to_catalog())?;
+        Ok(())
+    }
+}
+
+/// The JI tunings of a step signature in a [`Catalog`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JiTuningsEntry {
+    /// the step signature
+    pub sig: Vec<usize>,
+    /// the equave as a ratio such as `"3/1"`
+    pub equave: String,
+    /// the smallest step size in cents
+    pub s_lower: f64,
+    /// the largest step size in cents
+    pub s_upper: f64,
+    /// whether the tunings are from the slow solver
+    pub slow: bool,
+    /// the tunings, as step ratios
+    pub ji_tunings: Vec<Vec<String>>,
+}
+
+/// The ED tunings of a step signature in a [`Catalog`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EdTuningsEntry {
+    /// the step signature
+    pub sig: Vec<usize>,
+    /// the equave as a ratio such as `"3/1"`
+    pub equave: String,
+    /// the largest ED searched
+    pub ed_bound: i32,
+    /// the smallest step size in cents
+    pub s_lower: f64,
+    /// the largest step size in cents
+    pub s_upper: f64,
+    /// the tunings and their vals
+    #[serde(flatten)]
+    pub result: EdTuningsResult,
+}
+
+/// The entries of a [`ProfileCache`], as saved to disk.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Catalog {
+    /// the [`CATALOG_VERSION`] the catalog was written under
+    pub catalog_version: u32,
+    /// the [`SCHEMA_VERSION`] of the profiles and tunings
+    pub schema_version: u32,
+    /// scale profiles, by number of notes and then by word
+    pub profiles: Vec<ScaleProfile>,
+    /// JI tuning lists
+    pub ji_tunings: Vec<JiTuningsEntry>,
+    /// ED tuning lists
+    pub ed_tunings: Vec<EdTuningsEntry>,
+}
+
+/// Error type for catalogs that can't be loaded into a [`ProfileCache`].
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum BadCatalog {
+    /// A catalog written under another layout.
+    #[error("catalog has version {found} but this crate reads version {CATALOG_VERSION}")]
+    Version {
+        /// the catalog version of the catalog
+        found: u32,
+    },
+    /// A catalog whose results were written under another schema.
+    #[error(transparent)]
+    Schema(#[from] SchemaMismatch),
+    /// An entry with an invalid word or equave, or a profile of a mode that isn't the brightest.
+    #[error("invalid catalog entry: {0}")]
+    Entry(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sig_to_result;
+
+    #[test]
+    fn test_profiles_are_shared_by_modes() {
+        let mut cache = ProfileCache::new();
+        let word = vec![0, 1, 0, 2, 0, 1, 0, 2, 0];
+        let profile = cache.profile(&word);
+        assert_eq!(profile, word_to_profile(&least_mode(&word)));
+        for rotation in 1..word.len() {
+            let mut mode = word.clone();
+            mode.rotate_left(rotation);
+            assert_eq!(cache.profile(&mode), profile);
+        }
+        assert_eq!(cache.stats().profiles, 1);
+    }
+
+    #[test]
+    fn test_sig_result_matches() {
+        let mut cache = ProfileCache::new();
+        for filter in [
+            SigFilter::default(),
+            SigFilter {
+                mv: 3,
+                ..Default::default()
+            },
+        ] {
+            assert_eq!(
+                cache.sig_result(&[3, 2, 2], &filter, RawJiRatio::OCTAVE, 40, 20.0, 250.0),
+                sig_to_result(&[3, 2, 2], &filter, RawJiRatio::OCTAVE, 40, 20.0, 250.0)
+            );
+        }
+        // The second query only hits the cache.
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                profiles: 30,
+                ji_tunings: 1,
+                ed_tunings: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_precompute() {
+        let mut cache = ProfileCache::new();
+        cache.precompute(5);
+        // 3L1m1s and its permutations: 4 scales each; 2L2m1s and permutations: 6 each;
+        // 2L1m1s and permutations: 3 each; 1L1m1s: 2
+        assert_eq!(cache.stats().profiles, 3 * 4 + 3 * 6 + 3 * 3 + 2);
+    }
+
+    #[test]
+    fn test_catalog_round_trip() {
+        let mut cache = ProfileCache::new();
+        cache.sig_result(
+            &[2, 2, 1],
+            &SigFilter::default(),
+            RawJiRatio::TRITAVE,
+            20,
+            20.0,
+            250.0,
+        );
+        cache.ji_tunings(&[2, 2, 1], RawJiRatio::OCTAVE, 20.0, 250.0, true);
+        let catalog = cache.to_catalog();
+        let reloaded = ProfileCache::from_catalog(catalog.clone()).unwrap();
+        assert_eq!(reloaded.to_catalog(), catalog);
+        assert_eq!(catalog.ji_tunings[0].equave, "2/1");
+
+        let old = Catalog {
+            catalog_version: 0,
+            ..catalog.clone()
+        };
+        assert_eq!(
+            ProfileCache::from_catalog(old).unwrap_err(),
+            BadCatalog::Version { found: 0 }
+        );
+        let mut bad = catalog.clone();
+        bad.profiles[0].word = "LxL".to_string();
+        assert!(matches!(
+            ProfileCache::from_catalog(bad),
+            Err(BadCatalog::Entry(_))
+        ));
+        // A profile filed under another mode than the brightest
+        let mut bad = catalog;
+        let word = &bad.profiles[0].word;
+        bad.profiles[0].word = format!("{}{}", &word[1..], &word[..1]);
+        assert!(matches!(
+            ProfileCache::from_catalog(bad),
+            Err(BadCatalog::Entry(_))
+        ));
+    }
+
+    #[cfg(feature = "catalog")]
+    #[test]
+    fn test_read_write() {
+        let mut cache = ProfileCache::new();
+        cache.precompute(4);
+        let mut file = vec![];
+        cache.write_catalog(&mut file).unwrap();
+        let reloaded = ProfileCache::read_catalog(file.as_slice()).unwrap();
+        assert_eq!(reloaded.to_catalog(), cache.to_catalog());
+
+        let error =
+            ProfileCache::read_catalog(r#"{"catalog_version": 99}"#.as_bytes()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert!(error.to_string().contains("version 99"));
+    }
+}
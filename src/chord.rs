@@ -0,0 +1,315 @@
+//! Chord census for scale words under a tuning.
+//!
+//! Given a scale word, a [`StepTuning`] and a chord size `k`, this module lists every
+//! `k`-note chord of the scale up to transposition within the scale, i.e. two chords
+//! are the same if their notes have the same step content above the root. For each
+//! chord we record the degrees it occurs on and, where possible, its identity as a
+//! segment of the harmonic series, such as 4:5:6 or 6:7:9.
+//!
+//! - For JI tunings the identity is exact.
+//! - For ED and cents tunings, the identity is the harmonic-series chord with the
+//!   smallest root harmonic that matches every note within a tolerance in cents.
+//!
+//! # Examples
+//!
+//! ```
+//! use ternary::chord::chord_census;
+//! use ternary::tuning::StepTuning;
+//! use ternary::monzo;
+//!
+//! // Zarlino (LmsLmLs) with L = 9/8, m = 10/9, s = 16/15
+//! let zarlino = [0, 1, 2, 0, 1, 0, 2];
+//! let tuning = StepTuning::Ji(vec![monzo![-3, 2], monzo![1, -2, 1], monzo![4, -1, -1]]);
+//! let triads = chord_census(&zarlino, &tuning, 3, 0.0, 64).unwrap();
+//!
+//! // The stacked-thirds triad on degrees 0, 3 and 4 is 4:5:6.
+//! let major = triads
+//!     .iter()
+//!     .find(|c| c.identity.as_ref().is_some_and(|h| h.harmonics == vec![4, 5, 6]))
+//!     .unwrap();
+//! assert_eq!(major.shape, vec![0, 2, 4]);
+//! assert_eq!(major.degrees, vec![0, 3, 4]);
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::gcd;
+use crate::monzo::Monzo;
+use crate::primes::{SMALL_PRIMES, SMALL_PRIMES_COUNT};
+use crate::tuning::{BadTuning, StepTuning};
+use crate::words::{CountVector, Letter, dyad_on_degree};
+
+/// A chord written as a segment of the harmonic series, e.g. 4:5:6.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HarmonicChord {
+    /// The harmonics of the chord tones from the root up, in lowest terms.
+    pub harmonics: Vec<u32>,
+    /// The largest odd limit of any dyad in the chord.
+    pub odd_limit: u32,
+    /// The largest deviation in cents of any chord tone from its harmonic (0 for JI).
+    pub max_error: f64,
+}
+
+impl fmt::Display for HarmonicChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.harmonics.iter().join(":"))
+    }
+}
+
+/// A chord of a scale up to transposition within the scale.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChordClass {
+    /// The scale degrees of the chord tones above the root. Always starts with 0.
+    pub shape: Vec<usize>,
+    /// The step content of each chord tone above the root, one count per step letter.
+    pub intervals: Vec<Vec<i32>>,
+    /// The degrees of the scale the chord occurs on.
+    pub degrees: Vec<usize>,
+    /// The size of each chord tone above the root in cents.
+    pub cents: Vec<f64>,
+    /// The harmonic-series identity of the chord if one was found.
+    pub identity: Option<HarmonicChord>,
+}
+
+impl ChordClass {
+    /// Whether the chord has a harmonic-series identity within the given odd limit.
+    pub fn is_within_odd_limit(&self, odd_limit: u32) -> bool {
+        self.identity
+            .as_ref()
+            .is_some_and(|identity| identity.odd_limit <= odd_limit)
+    }
+}
+
+/// The odd part of a positive integer.
+fn odd_part(n: u32) -> u32 {
+    n >> n.trailing_zeros()
+}
+
+/// The odd limit of a chord given by its harmonics: the largest odd limit of any of its dyads.
+pub fn chord_odd_limit(harmonics: &[u32]) -> u32 {
+    harmonics
+        .iter()
+        .tuple_combinations()
+        .map(|(&a, &b)| {
+            let d = gcd(a, b);
+            std::cmp::max(odd_part(a / d), odd_part(b / d))
+        })
+        .max()
+        .unwrap_or(1)
+}
+
+/// The exact harmonic-series form of a JI chord given as monzos above the root.
+/// Returns `None` if a harmonic doesn't fit in a `u32`.
+pub fn ji_chord_harmonics(tones: &[Monzo]) -> Option<Vec<u32>> {
+    // Include the root, then shift every prime's exponents so the smallest is 0.
+    let tones = [&[Monzo::UNISON], tones].concat();
+    let mins: Vec<i32> = (0..SMALL_PRIMES_COUNT)
+        .map(|i| tones.iter().map(|m| m[i]).min().unwrap_or(0))
+        .collect();
+    tones
+        .iter()
+        .map(|m| {
+            (0..SMALL_PRIMES_COUNT).try_fold(1u32, |acc, i| {
+                acc.checked_mul(SMALL_PRIMES[i].checked_pow((m[i] - mins[i]) as u32)?)
+            })
+        })
+        .collect()
+}
+
+/// The harmonic-series chord with the smallest root harmonic (at most `max_harmonic`)
+/// matching every tone of the chord within `tolerance` cents.
+/// `tones` are the sizes in cents of the chord tones above the root.
+pub fn approximate_harmonics(
+    tones: &[f64],
+    tolerance: f64,
+    max_harmonic: u32,
+) -> Option<HarmonicChord> {
+    (1..=max_harmonic).find_map(|root| {
+        let mut harmonics = vec![root];
+        let mut max_error: f64 = 0.0;
+        for &cents in tones {
+            let exact = root as f64 * 2f64.powf(cents / 1200.0);
+            let nearest = exact.round();
+            if nearest < 1.0 || nearest > max_harmonic as f64 {
+                return None;
+            }
+            let nearest = nearest as u32;
+            if nearest <= *harmonics.last().expect("`harmonics` is nonempty") {
+                return None;
+            }
+            let error = (1200.0 * (nearest as f64 / root as f64).log2() - cents).abs();
+            if error > tolerance {
+                return None;
+            }
+            max_error = max_error.max(error);
+            harmonics.push(nearest);
+        }
+        Some(HarmonicChord {
+            odd_limit: chord_odd_limit(&harmonics),
+            harmonics,
+            max_error,
+        })
+    })
+}
+
+/// Write a `CountVector` of letters as a list of counts, one per letter `0..arity`.
+fn counts(interval: &CountVector<Letter>, arity: usize) -> Vec<i32> {
+    (0..arity)
+        .map(|letter| *interval.get(&letter).unwrap_or(&0))
+        .collect()
+}
+
+/// Every `note_count`-note chord of `word` (within one equave) up to transposition within the scale,
+/// sorted by shape and then by the first degree it occurs on.
+///
+/// For JI tunings the harmonic-series identity is exact and `tolerance` and `max_harmonic` are ignored.
+/// For other tunings the identity is searched for with root harmonics up to `max_harmonic`,
+/// allowing an error of `tolerance` cents on each chord tone.
+/// Fails if `tuning` has no step size for some letter of `word`.
+pub fn chord_census(
+    word: &[Letter],
+    tuning: &StepTuning,
+    note_count: usize,
+    tolerance: f64,
+    max_harmonic: u32,
+) -> Result<Vec<ChordClass>, BadTuning> {
+    tuning.check_word(word)?;
+    let scale_len = word.len();
+    if note_count == 0 || note_count > scale_len {
+        return Ok(vec![]);
+    }
+    let arity = tuning.arity();
+    let mut result = vec![];
+    for upper_tones in (1..scale_len).combinations(note_count - 1) {
+        let shape = [vec![0], upper_tones].concat();
+        // Group the degrees by the step content of the chord built on them.
+        let mut classes: BTreeMap<Vec<CountVector<Letter>>, Vec<usize>> = BTreeMap::new();
+        for degree in 0..scale_len {
+            let intervals = shape[1..]
+                .iter()
+                .map(|&offset| dyad_on_degree(word, degree, offset))
+                .collect::<Vec<_>>();
+            classes.entry(intervals).or_default().push(degree);
+        }
+        let mut chords: Vec<ChordClass> = classes
+            .into_iter()
+            .map(|(intervals, degrees)| {
+                let cents: Vec<f64> = intervals.iter().map(|v| tuning.interval_cents(v)).collect();
+                let identity = if let StepTuning::Ji(_) = tuning {
+                    intervals
+                        .iter()
+                        .map(|v| tuning.interval_monzo(v))
+                        .collect::<Option<Vec<_>>>()
+                        .and_then(|tones| ji_chord_harmonics(&tones))
+                        .map(|harmonics| HarmonicChord {
+                            odd_limit: chord_odd_limit(&harmonics),
+                            harmonics,
+                            max_error: 0.0,
+                        })
+                } else {
+                    approximate_harmonics(&cents, tolerance, max_harmonic)
+                };
+                ChordClass {
+                    shape: shape.clone(),
+                    intervals: intervals.iter().map(|v| counts(v, arity)).collect(),
+                    degrees,
+                    cents,
+                    identity,
+                }
+            })
+            .collect();
+        chords.sort_by_key(|chord| chord.degrees[0]);
+        result.extend(chords);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ji_ratio::RawJiRatio;
+    use crate::monzo;
+
+    #[test]
+    fn test_chord_odd_limit() {
+        assert_eq!(chord_odd_limit(&[4, 5, 6]), 5);
+        assert_eq!(chord_odd_limit(&[6, 7, 9]), 9);
+        assert_eq!(chord_odd_limit(&[1, 2]), 1);
+        assert_eq!(chord_odd_limit(&[10, 12, 15]), 5);
+    }
+
+    #[test]
+    fn test_ji_chord_harmonics() {
+        // 1/1 7/6 3/2 -> 6:7:9
+        let tones = [monzo![-1, -1, 0, 1], monzo![-1, 1]];
+        assert_eq!(ji_chord_harmonics(&tones), Some(vec![6, 7, 9]));
+        // 1/1 6/5 3/2 -> 10:12:15
+        let tones = [monzo![1, 1, -1], monzo![-1, 1]];
+        assert_eq!(ji_chord_harmonics(&tones), Some(vec![10, 12, 15]));
+    }
+
+    #[test]
+    fn test_approximate_harmonics() {
+        // 12edo major triad
+        let major = approximate_harmonics(&[400.0, 700.0], 15.0, 32).unwrap();
+        assert_eq!(major.harmonics, vec![4, 5, 6]);
+        assert!(major.max_error < 14.0);
+        // A stricter tolerance finds something more complex, or nothing at all
+        let closer = approximate_harmonics(&[400.0, 700.0], 10.0, 64).unwrap();
+        assert_eq!(closer.harmonics, vec![30, 38, 45]);
+        assert_eq!(approximate_harmonics(&[400.0, 700.0], 1.0, 32), None);
+    }
+
+    #[test]
+    fn test_diasem_census() {
+        let diasem = [0, 1, 0, 2, 0, 1, 0, 2, 0];
+        let ji = StepTuning::Ji(vec![
+            monzo![-3, 2],
+            monzo![2, -3, 0, 1],
+            monzo![6, -2, 0, -1],
+        ]);
+        let triads = chord_census(&diasem, &ji, 3, 0.0, 0).unwrap();
+        // Every degree occurs exactly once for each shape.
+        for shape in triads.iter().map(|c| c.shape.clone()).dedup() {
+            let degree_count: usize = triads
+                .iter()
+                .filter(|c| c.shape == shape)
+                .map(|c| c.degrees.len())
+                .sum();
+            assert_eq!(degree_count, 9);
+        }
+        assert!(triads.iter().any(|c| {
+            c.identity
+                .as_ref()
+                .is_some_and(|h| h.harmonics == vec![6, 7, 9])
+        }));
+
+        // 26edo tuning of the same scale still finds 6:7:9 within 12 cents.
+        let ed = StepTuning::Ed {
+            steps: vec![4, 2, 1],
+            ed: 26,
+            equave: RawJiRatio::OCTAVE,
+        };
+        let triads = chord_census(&diasem, &ed, 3, 12.0, 32).unwrap();
+        assert!(triads.iter().any(|c| {
+            c.is_within_odd_limit(9)
+                && c.identity
+                    .as_ref()
+                    .is_some_and(|h| h.harmonics == vec![6, 7, 9])
+        }));
+
+        // A tuning without a size for s is rejected rather than tuning s as a unison.
+        let two_steps = StepTuning::Ji(vec![monzo![-3, 2], monzo![2, -3, 0, 1]]);
+        assert_eq!(
+            chord_census(&diasem, &two_steps, 3, 0.0, 0).unwrap_err(),
+            BadTuning::TooFewSteps {
+                steps: 2,
+                letters: 3
+            }
+        );
+    }
+}
@@ -33,6 +33,7 @@
 use std::collections::BTreeSet;
 
 use crate::helpers::{first_index_desc, first_index_smaller};
+use crate::progress::{Cancelled, Monitor, Progress, Stage};
 use crate::words::Letter;
 
 /// Recursive helper for computing partitions with exact part count.
@@ -125,62 +126,166 @@ fn partitions_rec(n: usize, m: usize) -> Vec<Vec<usize>> {
 ///
 /// Empty vector if all content values are zero.
 pub fn necklaces_fixed_content(content: &[Letter]) -> Vec<Vec<Letter>> {
-    if content.iter().all(|x| *x == 0) {
-        vec![]
-    } else {
-        let (mut rem_content, perm) = VecPerm::sift_zeros(content);
-        while *rem_content
-            .last()
-            .expect("`rem_content` is a permutation of a nonempty `content` that is not all 0's")
-            == 0
-        {
-            rem_content.pop();
+    necklaces_fixed_content_monitored(content, &mut Monitor::new())
+        .expect("a monitor without a cancellation token never cancels")
+}
+
+/// [`necklaces_fixed_content`], reporting the number of necklaces found so far to `monitor`
+/// and stopping if it is cancelled.
+pub fn necklaces_fixed_content_monitored(
+    content: &[Letter],
+    monitor: &mut Monitor,
+) -> Result<Vec<Vec<Letter>>, Cancelled> {
+    let mut necklaces = vec![];
+    visit_necklaces(content, None, &mut |necklace| {
+        necklaces.push(necklace);
+        if necklaces.len().is_multiple_of(REPORT_INTERVAL) {
+            monitor.report(Progress {
+                stage: Stage::Enumerate,
+                done: necklaces.len(),
+                total: None,
+            })?;
         }
-        let arity = rem_content.len();
-        rem_content[0] -= 1;
-        let scale_len: usize = content.iter().sum();
-        let mut word = vec![0];
-        word.extend(&vec![arity - 1; scale_len - 1]);
-        let mut avail_letters: Vec<usize>; // List containing available letters in reverse order
-        if rem_content[0] == 0 {
-            // Remove 0 if we no longer have one
-            avail_letters = (1..arity).rev().collect();
-        } else {
-            avail_letters = (0..arity).rev().collect();
+        Ok(())
+    })?;
+    necklaces.shrink_to_fit();
+    Ok(necklaces)
+}
+
+/// A resumable enumeration of the necklaces with a given content, as in
+/// [`necklaces_fixed_content`] and in the same order, finding a given number of necklaces at a time.
+///
+/// # Examples
+///
+/// ```
+/// use ternary::comb::{NecklaceSearch, necklaces_fixed_content};
+///
+/// let mut search = NecklaceSearch::new(&[6, 4, 3]);
+/// assert!(!search.step(1000));
+/// assert_eq!(search.found().len(), 1000);
+/// while !search.step(1000) {}
+/// assert_eq!(search.into_necklaces(), necklaces_fixed_content(&[6, 4, 3]));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NecklaceSearch {
+    content: Vec<Letter>,
+    necklaces: Vec<Vec<Letter>>,
+    done: bool,
+}
+
+/// Stops [`NecklaceSearch::step`] once it has found its budget of necklaces.
+struct BudgetSpent;
+
+impl NecklaceSearch {
+    /// An enumeration of the necklaces with content `content` that hasn't started yet.
+    pub fn new(content: &[Letter]) -> Self {
+        Self {
+            content: content.to_vec(),
+            necklaces: vec![],
+            done: false,
         }
-        let mut coll: Vec<Vec<usize>> = vec![];
-        sawada_rec(
-            &mut rem_content,
-            &mut vec![0; scale_len],
-            &mut avail_letters,
-            &mut word,
-            1,
-            1,
-            1,
-            &mut coll,
-        );
-        coll.shrink_to_fit();
-        // Rename letters of the scale. We can use the same permutation, as it's a product of disjoint transpositions, thus order 2.
-        coll = coll
-            .into_iter()
-            .map(|scale| {
-                scale
-                    .into_iter()
-                    .map(|letter| {
-                        perm.at(letter)
-                            .expect("`perm` witnesses that `letter` was in the scale.")
-                    })
-                    .collect()
-            })
-            .collect();
-        coll.shrink_to_fit();
-        coll
     }
+
+    /// Find at most `budget` more necklaces and return whether every necklace has been found.
+    ///
+    /// Each call restarts Sawada's algorithm and skips to the last necklace found,
+    /// which takes time proportional to the length of the necklaces.
+    pub fn step(&mut self, budget: usize) -> bool {
+        if self.done || budget == 0 {
+            return self.done;
+        }
+        let resume_after = self.necklaces.last().cloned();
+        let mut found = 0;
+        let finished = visit_necklaces(&self.content, resume_after.as_deref(), &mut |necklace| {
+            self.necklaces.push(necklace);
+            found += 1;
+            if found == budget {
+                Err(BudgetSpent)
+            } else {
+                Ok(())
+            }
+        });
+        self.done = finished.is_ok();
+        self.done
+    }
+
+    /// Whether every necklace has been found.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// The necklaces found so far.
+    pub fn found(&self) -> &[Vec<Letter>] {
+        &self.necklaces
+    }
+
+    /// The necklaces found so far, which are all of them if the search is done.
+    pub fn into_necklaces(mut self) -> Vec<Vec<Letter>> {
+        self.necklaces.shrink_to_fit();
+        self.necklaces
+    }
+}
+
+/// Call `visit` on each necklace with content `content` in the order of [`sawada_rec`],
+/// starting after the necklace `resume_after` if given, and stop at the first error.
+fn visit_necklaces<E>(
+    content: &[Letter],
+    resume_after: Option<&[Letter]>,
+    visit: &mut impl FnMut(Vec<Letter>) -> Result<(), E>,
+) -> Result<(), E> {
+    if content.iter().all(|x| *x == 0) {
+        return Ok(());
+    }
+    let (mut rem_content, perm) = VecPerm::sift_zeros(content);
+    while *rem_content
+        .last()
+        .expect("`rem_content` is a permutation of a nonempty `content` that is not all 0's")
+        == 0
+    {
+        rem_content.pop();
+    }
+    let arity = rem_content.len();
+    rem_content[0] -= 1;
+    let scale_len: usize = content.iter().sum();
+    let mut word = vec![0];
+    word.extend(&vec![arity - 1; scale_len - 1]);
+    let mut avail_letters: Vec<usize>; // List containing available letters in reverse order
+    if rem_content[0] == 0 {
+        // Remove 0 if we no longer have one
+        avail_letters = (1..arity).rev().collect();
+    } else {
+        avail_letters = (0..arity).rev().collect();
+    }
+    // Rename letters of the scale. We can use the same permutation, as it's a product of disjoint transpositions, thus order 2.
+    let rename = |scale: &[Letter]| -> Vec<Letter> {
+        scale
+            .iter()
+            .map(|&letter| {
+                perm.at(letter)
+                    .expect("`perm` witnesses that `letter` was in the scale.")
+            })
+            .collect()
+    };
+    let resume_after = resume_after.map(rename);
+    sawada_rec(
+        &mut rem_content,
+        &mut vec![0; scale_len],
+        &mut avail_letters,
+        &mut word,
+        1,
+        1,
+        1,
+        resume_after.as_deref(),
+        &mut |necklace| visit(rename(necklace)),
+    )
 }
 
+/// How many necklaces [`necklaces_fixed_content_monitored`] finds between progress reports.
+const REPORT_INTERVAL: usize = 1024;
+
 // Recursive part of algorithm in Sawada (2002)
 #[allow(clippy::too_many_arguments)]
-fn sawada_rec(
+fn sawada_rec<E>(
     remaining_content: &mut Vec<usize>, // Remaining content to add to the prenecklace (Sawada's n)
     max_suffix_runs: &mut Vec<usize>, // Run of consecutive (arity-1)s starting at each position (Sawada's r)
     avail_letters: &mut Vec<Letter>,  // Available letters, maintained in descending order
@@ -188,8 +293,9 @@ fn sawada_rec(
     current_pos: usize,               // Current position in prenecklace (Sawada's t)
     lyndon_prefix_len: usize,         // Length of longest Lyndon prefix (Sawada's p)
     run_start: usize,                 // Start of current run of (arity-1)s (Sawada's s)
-    results: &mut Vec<Vec<Letter>>,   // Accumulator for valid necklaces
-) {
+    resume: Option<&[Letter]>, // A necklace visited before, if the prenecklace is a prefix of it
+    visit: &mut impl FnMut(&[Letter]) -> Result<(), E>, // Called on each valid necklace
+) -> Result<(), E> {
     let scale_len = prenecklace.len();
     let arity = remaining_content.len(); // TODO: Strip any suffix of 0's
     if remaining_content[arity - 1] == scale_len - current_pos {
@@ -198,16 +304,17 @@ fn sawada_rec(
             && scale_len.is_multiple_of(lyndon_prefix_len))
             || remaining_content[arity - 1] > max_suffix_runs[current_pos - lyndon_prefix_len]
         {
-            let mut new_necklace: Vec<usize> = prenecklace.iter_mut().map(|x| *x).collect();
-            new_necklace.shrink_to_fit(); // remove any extra allocation
-            results.push(new_necklace);
-            results.shrink_to_fit();
+            // A prenecklace that is still a prefix of `resume` here is `resume` itself
+            if resume.is_none() {
+                visit(prenecklace)?;
+            }
         } // else reject
     } else if remaining_content[0] != scale_len - current_pos {
         // else reject since it both begins and ends in a 0
         let maybe_first_letter: Option<usize> = avail_letters.first().copied();
         if let Some(letter) = maybe_first_letter {
-            let mut current_letter = letter;
+            // Letters are tried in descending order, so those above `resume`'s were tried before
+            let mut current_letter = resume.map_or(letter, |resume| resume[current_pos]);
             while current_letter >= prenecklace[current_pos - lyndon_prefix_len] {
                 max_suffix_runs[run_start] = current_pos - run_start;
                 if remaining_content[current_letter] == 1 {
@@ -235,9 +342,10 @@ fn sawada_rec(
                         } else {
                             current_pos + 1
                         },
-                        results,
+                        resume.filter(|resume| resume[current_pos] == current_letter),
+                        visit,
                     )
-                });
+                })?;
                 // If current_letter has been removed from `avail_letters`, add it back.
                 // This is how we backtrack in the tree.
                 if remaining_content[current_letter] == 0 {
@@ -258,35 +366,23 @@ fn sawada_rec(
         }
         prenecklace[current_pos] = arity - 1;
     }
+    Ok(())
 }
 
-#[derive(PartialEq, Hash, Debug, Clone)]
 /// Error types for invalid `VecPerm` construction
+#[derive(PartialEq, Hash, Debug, Clone, thiserror::Error)]
 pub enum PermutationError {
     /// When two perms are composed, their lengths must match
+    #[error("permutations must have matching lengths")]
     DiffLengths,
     /// Index out of bounds for permutation domain
+    #[error("permutation has length {0} but the index is {1}")]
     IndexOutOfBounds(usize, usize),
     /// The image is not {0, ..., n - 1} (not a valid permutation)
+    #[error("wrong image for `VecPerm`: {0:?}")]
     WrongImage(Vec<usize>),
 }
 
-impl std::fmt::Display for PermutationError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::DiffLengths => {
-                write!(f, "permutations must have matching lengths")
-            }
-            Self::IndexOutOfBounds(len, i) => {
-                write!(f, "permutation has length {len} but the index is {i}")
-            }
-            Self::WrongImage(sl) => {
-                write!(f, "wrong image for `VecPerm`: {sl:?}")
-            }
-        }
-    }
-}
-
 /// Encodes how the entries of a `Vec` was permuted.
 /// `pi[x]` stores where the permutation sends element `x`.
 #[derive(Clone, Debug, PartialEq, Hash)]
@@ -1097,4 +1193,50 @@ mod tests {
         ]);
         assert_eq!(attempt, correct_set);
     }
+
+    #[test]
+    fn test_necklaces_monitored() {
+        use crate::progress::CancelToken;
+
+        let mut found = vec![];
+        let mut monitor = Monitor::new().on_progress(|progress| found.push(progress.done));
+        let necklaces = necklaces_fixed_content_monitored(&[6, 4, 3], &mut monitor);
+        drop(monitor);
+        assert_eq!(necklaces, Ok(necklaces_fixed_content(&[6, 4, 3])));
+        // 13!/(6!4!3!)/13 == 4620 necklaces, reported every 1024
+        assert_eq!(found, vec![1024, 2048, 3072, 4096]);
+
+        let token = CancelToken::new();
+        let mut monitor = Monitor::new()
+            .on_progress(|_| token.cancel())
+            .cancel_token(token.clone());
+        assert_eq!(
+            necklaces_fixed_content_monitored(&[6, 4, 3], &mut monitor),
+            Err(Cancelled)
+        );
+    }
+
+    #[test]
+    fn test_necklace_search() {
+        for content in [
+            vec![6, 4, 3],
+            vec![4, 4, 4],
+            vec![1, 1, 0, 0, 1],
+            vec![2, 2],
+            vec![0, 3],
+        ] {
+            let all = necklaces_fixed_content(&content);
+            for budget in [1, 7, 1000] {
+                let mut search = NecklaceSearch::new(&content);
+                let mut steps = 0;
+                while !search.step(budget) {
+                    steps += 1;
+                    assert_eq!(search.found().len(), steps * budget);
+                }
+                assert!(search.is_done());
+                assert_eq!(search.into_necklaces(), all, "{content:?} by {budget}");
+            }
+        }
+        assert!(NecklaceSearch::new(&[0, 0]).step(1));
+    }
 }
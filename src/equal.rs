@@ -86,6 +86,255 @@ impl AddAssign for Val {
     }
 }
 
+impl std::fmt::Display for Val {
+    /// Writes the val in bra notation up to its last nonzero entry, e.g. `<12 19 28]`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let entries = self.0.iter().take(self.dim()).map(|x| x.to_string());
+        write!(f, "<{}]", entries.collect::<Vec<_>>().join(" "))
+    }
+}
+
+impl std::str::FromStr for Val {
+    type Err = BadWart;
+
+    /// Reads a val in bra notation, e.g. `<12 19 28]`, or in wart notation on every prime in
+    /// `SMALL_PRIMES`, e.g. `17c`, as the ED tunings of a step signature report their vals.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ternary::equal::Val;
+    /// use ternary::val;
+    ///
+    /// assert_eq!("<12 19 28]".parse::<Val>().unwrap(), val![12, 19, 28]);
+    /// assert_eq!("17c".parse::<Val>().unwrap(), Val::from_wart("17c", 9).unwrap());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let Some(entries) = s.strip_prefix('<').and_then(|s| s.strip_suffix(']')) else {
+            return Self::from_wart(s, SMALL_PRIMES_COUNT);
+        };
+        let entries = entries
+            .split_whitespace()
+            .map(|x| x.parse().ok())
+            .collect::<Option<Vec<i32>>>()
+            .filter(|entries| entries.len() <= SMALL_PRIMES_COUNT)
+            .ok_or_else(|| BadWart::BadBra(s.to_string()))?;
+        Ok(Self::from_slice(&entries))
+    }
+}
+
+/// The letters used in [wart notation](https://en.xen.wiki/w/Wart_notation), one per prime in `SMALL_PRIMES`.
+const WART_LETTERS: [char; SMALL_PRIMES_COUNT] = ['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i'];
+
+/// Error type for strings that can't be read as a val in wart or bra notation.
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum BadWart {
+    /// The string didn't start with a positive EDO number.
+    #[error("`{0}` doesn't start with a positive EDO number")]
+    BadEdo(String),
+    /// The character isn't a wart letter for an odd prime in `SMALL_PRIMES`, or `p`.
+    #[error("`{0}` is not a wart letter")]
+    BadLetter(char),
+    /// A wart letter is for a prime beyond the prime limit.
+    #[error("wart `{0}` is for a prime beyond the prime limit")]
+    BeyondPrimeLimit(char),
+    /// The string in angle brackets isn't a list of at most `SMALL_PRIMES_COUNT` integers.
+    #[error("`{0}` is not a val in bra notation")]
+    BadBra(String),
+}
+
+/// How many integers are strictly closer to `x` than `n` is.
+/// The nearest integer has rank 0, the second-nearest rank 1, and so on.
+fn approx_rank(n: i32, x: f64) -> usize {
+    let d = (n as f64 - x).abs();
+    ((x - d).floor() as i32 + 1..=(x + d).ceil() as i32 - 1)
+        .filter(|&c| c != n && (c as f64 - x).abs() < d)
+        .count()
+}
+
+/// The `rank`-th nearest integer to `x`, where rank 0 is the nearest.
+fn nth_approx(x: f64, rank: usize) -> i32 {
+    let lo = x.floor() as i32 - rank as i32;
+    let mut candidates: Vec<i32> = (lo..=lo + 2 * rank as i32 + 1).collect();
+    candidates.sort_by(|&a, &b| (a as f64 - x).abs().total_cmp(&(b as f64 - x).abs()));
+    candidates[rank]
+}
+
+impl Val {
+    /// The number of primes up to the last nonzero entry.
+    fn dim(&self) -> usize {
+        (0..SMALL_PRIMES_COUNT)
+            .rev()
+            .find(|&i| self.0[i] != 0)
+            .map_or(0, |i| i + 1)
+    }
+
+    /// The val in [wart notation](https://en.xen.wiki/w/Wart_notation), e.g. `17c` for <17 27 40]
+    /// (5 mapped to its second-best approximation) or `22p` for the patent val of 22edo.
+    /// The octave mapping is taken as the EDO number, and the prime limit is that of the last nonzero entry.
+    pub fn wart_name(&self) -> String {
+        let edo = self.0[0];
+        let warts: String = (1..self.dim())
+            .flat_map(|i| {
+                let exact = edo as f64 * (SMALL_PRIMES[i] as f64).log2();
+                std::iter::repeat_n(WART_LETTERS[i], approx_rank(self.0[i], exact))
+            })
+            .collect();
+        if warts.is_empty() {
+            format!("{edo}p")
+        } else {
+            format!("{edo}{warts}")
+        }
+    }
+
+    /// Read a val of the first `dim` primes from wart notation, e.g. `17c`, `22p` or `12`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ternary::equal::Val;
+    /// use ternary::val;
+    ///
+    /// assert_eq!(Val::from_wart("17c", 3).unwrap(), val![17, 27, 40]);
+    /// assert_eq!(Val::from_wart("17", 3).unwrap(), val![17, 27, 39]);
+    /// assert_eq!(val![17, 27, 40].wart_name(), "17c");
+    /// ```
+    pub fn from_wart(s: &str, dim: usize) -> Result<Self, BadWart> {
+        let digits = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        let edo: i32 = s[..digits]
+            .parse()
+            .ok()
+            .filter(|&edo| edo > 0)
+            .ok_or_else(|| BadWart::BadEdo(s.to_string()))?;
+        let mut ranks = [0usize; SMALL_PRIMES_COUNT];
+        for c in s[digits..].chars() {
+            if c == 'p' {
+                continue;
+            }
+            let i = WART_LETTERS[1..]
+                .iter()
+                .position(|&w| w == c.to_ascii_lowercase())
+                .map(|i| i + 1)
+                .ok_or(BadWart::BadLetter(c))?;
+            if i >= dim {
+                return Err(BadWart::BeyondPrimeLimit(c));
+            }
+            ranks[i] += 1;
+        }
+        let mut arr = [0i32; SMALL_PRIMES_COUNT];
+        arr[0] = edo;
+        for i in 1..dim.min(SMALL_PRIMES_COUNT) {
+            arr[i] = nth_approx(edo as f64 * (SMALL_PRIMES[i] as f64).log2(), ranks[i]);
+        }
+        Ok(Self(RowVector::new(arr)))
+    }
+
+    /// The intervals of the `odd`-odd-limit diamond within the val's prime limit.
+    fn diamond(&self, odd: u32) -> Vec<Monzo> {
+        let dim = self.dim();
+        odd_limit(odd)
+            .into_iter()
+            .filter_map(|r| Monzo::try_from_ratio(r).ok())
+            .filter(|m| m.into_inner().iter().skip(dim).all(|&x| x == 0))
+            .collect()
+    }
+
+    /// Whether the val maps every interval of the `odd`-odd-limit diamond (within its prime limit)
+    /// to that interval's best approximation in the EDO given by its octave mapping.
+    pub fn is_consistent(&self, odd: u32) -> bool {
+        let edo = self.0[0] as f64;
+        self.diamond(odd)
+            .into_iter()
+            .all(|m| self.evaluate(m) == direct_approx(m, edo, Monzo::OCTAVE))
+    }
+
+    /// L^2 error in relative steps of the val over the `odd`-odd-limit diamond (within its prime limit).
+    pub fn odd_limit_l2_error(&self, odd: u32) -> f64 {
+        let edo = self.0[0] as f64;
+        self.diamond(odd)
+            .into_iter()
+            .map(|m| (self.evaluate(m) as f64 - edo * m.cents() / 1200.0).pow(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+}
+
+/// All vals of `edo`-edo on the first `dim` primes where each odd prime is mapped within `budget` steps
+/// of its exact size, sorted by number of warts (so the patent val comes first) and then by wart name.
+///
+/// # Examples
+///
+/// ```
+/// use ternary::equal::vals_for_edo;
+///
+/// let names: Vec<String> = vals_for_edo(17, 3, 1.0).iter().map(|v| v.wart_name()).collect();
+/// assert_eq!(names, vec!["17p", "17b", "17c", "17bc"]);
+/// ```
+pub fn vals_for_edo(edo: i32, dim: usize, budget: f64) -> Vec<Val> {
+    let dim = dim.clamp(1, SMALL_PRIMES_COUNT);
+    let mut vals = vec![[0i32; SMALL_PRIMES_COUNT]];
+    vals[0][0] = edo;
+    for (i, &p) in SMALL_PRIMES.iter().enumerate().take(dim).skip(1) {
+        let exact = edo as f64 * (p as f64).log2();
+        let candidates: Vec<i32> = ((exact - budget).ceil() as i32
+            ..=(exact + budget).floor() as i32)
+            .filter(|&c| (c as f64 - exact).abs() <= budget)
+            .collect();
+        vals = vals
+            .into_iter()
+            .flat_map(|arr| {
+                candidates.iter().map(move |&c| {
+                    let mut arr = arr;
+                    arr[i] = c;
+                    arr
+                })
+            })
+            .collect();
+    }
+    let mut vals: Vec<(usize, String, Val)> = vals
+        .into_iter()
+        .map(|arr| {
+            let val = Val(RowVector::new(arr));
+            let name = val.wart_name();
+            // Every character after the digits except the patent marker is a wart.
+            let warts = name
+                .chars()
+                .filter(|c| c.is_ascii_lowercase() && *c != 'p')
+                .count();
+            (warts, name, val)
+        })
+        .collect();
+    vals.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+    vals.into_iter().map(|(_, _, val)| val).collect()
+}
+
+/// The val of `edo`-edo on the primes up to `odd` with the least L^2 error over the `odd`-odd-limit diamond,
+/// among vals mapping each prime within one step of its exact size. Prefers fewer warts on ties.
+///
+/// # Examples
+///
+/// ```
+/// use ternary::equal::best_val;
+///
+/// // 17edo's 5 is better mapped up: 17c.
+/// assert_eq!(best_val(17, 5).wart_name(), "17c");
+/// assert_eq!(best_val(12, 9).wart_name(), "12p");
+/// ```
+pub fn best_val(edo: i32, odd: u32) -> Val {
+    let dim = SMALL_PRIMES
+        .iter()
+        .take_while(|&&p| p <= odd.max(2))
+        .count();
+    vals_for_edo(edo, dim, 1.0)
+        .into_iter()
+        .min_by(|a, b| {
+            a.odd_limit_l2_error(odd)
+                .total_cmp(&b.odd_limit_l2_error(odd))
+        })
+        .expect("the patent val is always within one step")
+}
+
 /// The [generalized patent val](https://en.xen.wiki/w/Generalized_patent_val) of x-edo where x is any positive real.
 /// Computes the step mapping for each prime in x-tone equal division of the octave.
 ///
@@ -434,4 +683,61 @@ mod tests {
         assert_eq!(val_sixteen_point_nine.0[1], 27);
         assert_eq!(val_sixteen_point_nine.0[2], 39);
     }
+
+    #[test]
+    fn test_wart_names() {
+        assert_eq!(val![12, 19, 28].wart_name(), "12p");
+        assert_eq!(val![17, 27, 40].wart_name(), "17c");
+        assert_eq!(val![17, 26, 40].wart_name(), "17bc");
+        // The third-best mapping of 5 gets two warts.
+        assert_eq!(val![17, 27, 38].wart_name(), "17cc");
+        assert_eq!(val![22, 35, 51, 62].wart_name(), "22p");
+        for name in ["12p", "17c", "17bc", "17cc", "22d", "31p"] {
+            assert_eq!(Val::from_wart(name, 4).unwrap().wart_name(), name);
+        }
+        assert_eq!(Val::from_wart("17c", 4).unwrap(), val![17, 27, 40, 48]);
+        assert_eq!(
+            Val::from_wart("0c", 3),
+            Err(BadWart::BadEdo("0c".to_string()))
+        );
+        assert_eq!(Val::from_wart("17z", 3), Err(BadWart::BadLetter('z')));
+        assert_eq!(
+            Val::from_wart("17d", 3),
+            Err(BadWart::BeyondPrimeLimit('d'))
+        );
+        assert_eq!(val![12, 19, 28].to_string(), "<12 19 28]");
+        assert_eq!("<12 19 28]".parse(), Ok(val![12, 19, 28]));
+        assert_eq!(
+            "<12 x]".parse::<Val>(),
+            Err(BadWart::BadBra("<12 x]".to_string()))
+        );
+        // Wart notation covers every prime, the unwarted ones by their patent mapping.
+        assert_eq!(
+            "17c".parse::<Val>().unwrap().evaluate(monzo![0, 0, 0, 1]),
+            48
+        );
+    }
+
+    #[test]
+    fn test_val_consistency() {
+        assert!(val![12, 19, 28, 34].is_consistent(9));
+        // Intervals with primes beyond the val's prime limit are ignored.
+        assert!(val![12, 19, 28, 34].is_consistent(11));
+        // 11/9 maps to 4 steps, but 3 steps is closer.
+        assert!(!val![12, 19, 28, 34, 42].is_consistent(11));
+        // 17edo is inconsistent in the 5-odd-limit whichever way 5 is mapped.
+        assert!(!val![17, 27, 39].is_consistent(5));
+        assert!(!val![17, 27, 40].is_consistent(5));
+        assert!(val![17, 27].is_consistent(3));
+        assert!(val![31, 49, 72, 87, 107].is_consistent(11));
+    }
+
+    #[test]
+    fn test_best_val() {
+        assert_eq!(best_val(17, 5), val![17, 27, 40]);
+        assert_eq!(best_val(31, 11).wart_name(), "31p");
+        let vals = vals_for_edo(22, 4, 1.0);
+        assert_eq!(vals[0], val![22, 35, 51, 62]);
+        assert_eq!(vals.len(), 8);
+    }
 }
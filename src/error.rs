@@ -0,0 +1,241 @@
+//! The crate-wide error type.
+//!
+//! [`Error`] gathers the ways a query can be rejected: a malformed scale word, step signature
+//! or equave, an unknown option string, a number out of range, and the errors of the other
+//! modules, each of which converts into it with `?`. Each error has a stable
+//! [`code`](Error::code) for programs to match on, and a [`FieldError`] names the argument it
+//! concerns. The WASM API throws field errors as `{code, field, message}` objects.
+//!
+//! Functions that panic on bad input have `try_` variants returning these errors instead,
+//! such as [`try_mos_mode`](crate::words::try_mos_mode) and [`Dyad::try_rd`](crate::interval::Dyad::try_rd).
+//!
+//! # Examples
+//!
+//! ```
+//! use ternary::Error;
+//! use ternary::{check_ternary_sig, parse_ternary_word};
+//!
+//! assert_eq!(parse_ternary_word("LmLsLmLsL"), Ok(vec![0, 1, 0, 2, 0, 1, 0, 2, 0]));
+//! let error = parse_ternary_word("LmLxL").unwrap_err();
+//! assert_eq!(error, Error::InvalidWord("LmLxL".to_string()));
+//! assert_eq!(error.code(), "invalid_word");
+//!
+//! assert_eq!(check_ternary_sig(&[5, 2, 0]).unwrap_err().code(), "invalid_sig");
+//! ```
+//!
+//! ```
+//! use ternary::Error;
+//! use ternary::words::try_mos_mode;
+//!
+//! fn diatonic_mode(brightness: usize) -> Result<Vec<usize>, Error> {
+//!     Ok(try_mos_mode(5, 2, brightness)?)
+//! }
+//!
+//! assert_eq!(diatonic_mode(6), Ok(vec![0, 0, 0, 1, 0, 0, 1]));
+//! assert_eq!(try_mos_mode(0, 0, 0).map_err(Error::from).unwrap_err().code(), "invalid_scale");
+//! ```
+
+use serde::Serialize;
+use serde::ser::SerializeStruct;
+
+use crate::cache::BadCatalog;
+use crate::comb::PermutationError;
+use crate::equal::BadWart;
+use crate::helpers::ScaleError;
+use crate::ji_ratio::{BadJiArith, IllegalJiRatio, ParseJiRatioError};
+use crate::midi::BadMts;
+use crate::monzo::CantMakeMonzo;
+use crate::optimal::NoOptimalTuning;
+use crate::progress::Cancelled;
+use crate::scala::BadScl;
+use crate::temperament::TemperamentError;
+use crate::tuning::BadTuning;
+
+/// An invalid input to one of the crate's queries.
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum Error {
+    /// A scale word that is empty or has characters that aren't step letters.
+    #[error("`{0}` is not a valid scale word")]
+    InvalidWord(String),
+    /// A step signature that doesn't have the required number of positive step counts.
+    #[error("{0:?} is not a valid step signature")]
+    InvalidStepSig(Vec<usize>),
+    /// An equave that isn't a JI ratio greater than 1/1.
+    #[error("`{0}` is not an equave (a JI ratio greater than 1/1)")]
+    InvalidEquave(String),
+    /// An option string that isn't one of the accepted values.
+    #[error("unknown option `{value}`, expected one of {}", expected.join(", "))]
+    InvalidOption {
+        /// the option given
+        value: String,
+        /// the accepted values
+        expected: &'static [&'static str],
+    },
+    /// A number outside its allowed range.
+    #[error("{value} is out of range, expected {expected}")]
+    OutOfRange {
+        /// the number given
+        value: String,
+        /// a description of the allowed range
+        expected: &'static str,
+    },
+    /// A scale that can't be constructed or analyzed as asked.
+    #[error("{0}")]
+    Scale(#[from] ScaleError),
+    /// A ratio that isn't a positive JI ratio.
+    #[error("{0}")]
+    JiRatio(#[from] IllegalJiRatio),
+    /// A string that can't be read as a JI ratio.
+    #[error("{0}")]
+    ParseJiRatio(#[from] ParseJiRatioError),
+    /// Interval arithmetic without a result, such as reducing by the unison.
+    #[error("{0}")]
+    JiArith(#[from] BadJiArith),
+    /// A ratio that can't be written as a monzo.
+    #[error("{0}")]
+    Monzo(#[from] CantMakeMonzo),
+    /// A malformed permutation.
+    #[error("{0}")]
+    Permutation(#[from] PermutationError),
+    /// A val that can't be read from wart notation.
+    #[error("{0}")]
+    Wart(#[from] BadWart),
+    /// Commas or mappings that don't form a temperament.
+    #[error("{0}")]
+    Temperament(#[from] TemperamentError),
+    /// A temperament without an optimal tuning.
+    #[error("{0}")]
+    NoOptimalTuning(#[from] NoOptimalTuning),
+    /// Step sizes that can't be read as a tuning.
+    #[error("{0}")]
+    Tuning(#[from] BadTuning),
+    /// A `.scl` file that can't be read as a scale.
+    #[error("{0}")]
+    Scl(#[from] BadScl),
+    /// A tuning that can't be written as an MTS message.
+    #[error("{0}")]
+    Mts(#[from] BadMts),
+    /// A search stopped by its cancellation token.
+    #[error("{0}")]
+    Cancelled(#[from] Cancelled),
+    /// A catalog of profiles and tunings that can't be loaded.
+    #[error("{0}")]
+    Catalog(#[from] BadCatalog),
+}
+
+impl Error {
+    /// A short identifier for the kind of error, such as `"invalid_word"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidWord(_) => "invalid_word",
+            Self::InvalidStepSig(_) => "invalid_sig",
+            Self::InvalidEquave(_) => "invalid_equave",
+            Self::InvalidOption { .. } => "invalid_option",
+            Self::OutOfRange { .. } => "out_of_range",
+            Self::Scale(_) => "invalid_scale",
+            Self::JiRatio(_) | Self::ParseJiRatio(_) => "invalid_ratio",
+            Self::JiArith(_) => "bad_arithmetic",
+            Self::Monzo(_) => "invalid_monzo",
+            Self::Permutation(_) => "invalid_permutation",
+            Self::Wart(_) => "invalid_wart",
+            Self::Temperament(_) => "invalid_temperament",
+            Self::NoOptimalTuning(_) => "no_optimal_tuning",
+            Self::Tuning(_) => "invalid_tuning",
+            Self::Scl(_) => "invalid_scl",
+            Self::Mts(_) => "invalid_mts",
+            Self::Cancelled(_) => "cancelled",
+            Self::Catalog(_) => "invalid_catalog",
+        }
+    }
+
+    /// This error as an error in the argument or field `field`.
+    pub fn in_field(self, field: &'static str) -> FieldError {
+        FieldError { field, error: self }
+    }
+}
+
+/// An [`Error`] in a named argument or field of a query.
+/// Serialized as an object with the error's `code`, the `field` and a `message`.
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+#[error("{field}: {error}")]
+pub struct FieldError {
+    /// the argument or field with the invalid value
+    pub field: &'static str,
+    /// what's wrong with it
+    #[source]
+    pub error: Error,
+}
+
+impl Serialize for FieldError {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("FieldError", 3)?;
+        state.serialize_field("code", self.error.code())?;
+        state.serialize_field("field", self.field)?;
+        state.serialize_field("message", &self.error.to_string())?;
+        state.end()
+    }
+}
+
+/// Field errors are thrown to JavaScript as `{code, field, message}` objects.
+#[cfg(feature = "wasm")]
+impl From<FieldError> for wasm_bindgen::JsValue {
+    fn from(e: FieldError) -> Self {
+        serde_wasm_bindgen::to_value(&e).unwrap_or_else(|_| Self::from_str(&e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages() {
+        let error = Error::InvalidOption {
+            value: "sometimes".to_string(),
+            expected: &["exactly", "at-most"],
+        };
+        assert_eq!(error.code(), "invalid_option");
+        assert_eq!(
+            error.to_string(),
+            "unknown option `sometimes`, expected one of exactly, at-most"
+        );
+        let error = Error::from(BadTuning::MixedEds);
+        assert_eq!(error.code(), "invalid_tuning");
+        assert_eq!(error.to_string(), BadTuning::MixedEds.to_string());
+        assert!(std::error::Error::source(&error).is_some());
+    }
+
+    #[test]
+    fn test_field_errors() {
+        use crate::{check_tuning_bounds, parse_equave, try_string_to_numbers};
+
+        assert_eq!(check_tuning_bounds(53, 20.0, 250.0), Ok(()));
+        assert_eq!(
+            check_tuning_bounds(0, 20.0, 250.0).unwrap_err().field,
+            "ed_bound"
+        );
+        assert_eq!(
+            check_tuning_bounds(53, f64::NAN, 250.0).unwrap_err().field,
+            "s_lower"
+        );
+        assert_eq!(
+            check_tuning_bounds(53, 20.0, 10.0).unwrap_err().field,
+            "s_upper"
+        );
+
+        assert!(parse_equave("3/2").is_ok());
+        assert!(parse_equave("1/2").is_err());
+        assert_eq!(try_string_to_numbers("LsLLs"), Ok(vec![0, 1, 0, 0, 1]));
+        assert!(try_string_to_numbers("").is_err());
+
+        let error = parse_equave("0/1").unwrap_err().in_field("equave");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "code": "invalid_equave",
+                "field": "equave",
+                "message": "`0/1` is not an equave (a JI ratio greater than 1/1)",
+            })
+        );
+    }
+}
@@ -0,0 +1,809 @@
+//! C API, enabled by the `ffi` feature.
+//!
+//! The header `include/ternary.h` is generated with [cbindgen](https://github.com/mozilla/cbindgen)
+//! into `OUT_DIR` by the build script; after changing this module, update the committed copy
+//! with `TERNARY_UPDATE_HEADER=1 cargo test --features ffi --test ffi`. Link against the
+//! `cdylib` built by Cargo (`libternary.so`, `libternary.dylib` or `ternary.dll`).
+//!
+//! Scale words and tunings are opaque handles created by `ternary_*_new` functions and released
+//! by the matching `ternary_*_free` functions. Every fallible function returns a
+//! [`TernaryStatus`] and writes its result to an out-parameter only on success. Strings returned
+//! through `char **` out-parameters are owned by the caller and released with
+//! [`ternary_string_free`]; byte buffers are released with [`ternary_bytes_free`].
+//! Panics are caught at the boundary and reported as [`TernaryStatus::Panic`].
+//!
+//! ```c
+//! TernaryWord *word;
+//! TernaryTuningList *tunings;
+//! TernaryTuning *tuning;
+//! char *scl;
+//! if (ternary_word_new("LmLsLmLsL", &word) == TERNARY_STATUS_OK
+//!     && ternary_ed_tunings(word, 2, 1, 53, 20.0, 250.0, &tunings) == TERNARY_STATUS_OK
+//!     && ternary_tuning_list_get(tunings, 0, &tuning) == TERNARY_STATUS_OK
+//!     && ternary_scl(word, tuning, &scl) == TERNARY_STATUS_OK) {
+//!     puts(scl);
+//!     ternary_string_free(scl);
+//! }
+//! ```
+
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::ptr;
+
+use crate::guide::guide_frames;
+use crate::ji_ratio::RawJiRatio;
+use crate::midi::{self, KeyboardMapping, MIDI_NOTE_COUNT};
+use crate::monzo::Monzo;
+use crate::tuning::StepTuning;
+use crate::words::{Chirality, Letter};
+use crate::{
+    Error, check_ternary_sig, numbers_to_string, try_string_to_numbers, word_to_profile,
+    word_to_scl, word_to_sig,
+};
+use crate::{equal, ji};
+
+/// The result of a call into the C API.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TernaryStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// A string argument was not valid UTF-8.
+    InvalidUtf8 = 2,
+    /// A scale word was empty or contained characters that aren't step letters.
+    InvalidWord = 3,
+    /// A step size couldn't be read, or a tuning has fewer step sizes than the word has letters.
+    InvalidTuning = 4,
+    /// A numeric argument was out of its valid range.
+    InvalidArgument = 5,
+    /// An index was out of bounds.
+    OutOfRange = 6,
+    /// The library panicked; this is a bug.
+    Panic = 7,
+}
+
+impl From<Error> for TernaryStatus {
+    /// The status of a call that failed with `error`: [`InvalidWord`](TernaryStatus::InvalidWord)
+    /// and [`InvalidTuning`](TernaryStatus::InvalidTuning) for words and tunings, and
+    /// [`InvalidArgument`](TernaryStatus::InvalidArgument) for anything else.
+    fn from(error: Error) -> Self {
+        match error {
+            Error::InvalidWord(_) => Self::InvalidWord,
+            Error::Tuning(_) => Self::InvalidTuning,
+            _ => Self::InvalidArgument,
+        }
+    }
+}
+
+/// The handedness of a scale word; see [`Chirality`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TernaryChirality {
+    Left = 0,
+    #[default]
+    Achiral = 1,
+    Right = 2,
+}
+
+impl From<Chirality> for TernaryChirality {
+    fn from(chirality: Chirality) -> Self {
+        match chirality {
+            Chirality::Left => Self::Left,
+            Chirality::Achiral => Self::Achiral,
+            Chirality::Right => Self::Right,
+        }
+    }
+}
+
+/// The properties of a ternary scale word, as in [`crate::ScaleProfile`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TernaryProfile {
+    /// The number of L, m and s steps.
+    pub step_sig: [usize; 3],
+    /// The maximum variety.
+    pub max_variety: u16,
+    /// The chirality.
+    pub chirality: TernaryChirality,
+    /// Whether the scale is an L=m monotone MOS.
+    pub lm: bool,
+    /// Whether the scale is an m=s monotone MOS.
+    pub ms: bool,
+    /// Whether the scale is an s=0 monotone MOS.
+    pub s0: bool,
+    /// Whether the scale is a MOS substitution scale aL(bmcs).
+    pub subst_l_ms: bool,
+    /// Whether the scale is a MOS substitution scale bm(aLcs).
+    pub subst_m_ls: bool,
+    /// Whether the scale is a MOS substitution scale cs(aLbm).
+    pub subst_s_lm: bool,
+    /// The temperament-agnostic ED join.
+    pub ed_join: [i32; 3],
+    /// The complexity of the simplest guide frame, or 0 if there is none.
+    pub guide_complexity: u16,
+    /// The multiplicity of the simplest guide frame, or 0 if there is none.
+    pub guide_multiplicity: u16,
+    /// Whether the pitch class lattice has a unimodular basis.
+    pub has_lattice_basis: bool,
+}
+
+/// A placement of a scale on the MIDI keyboard; see [`KeyboardMapping`].
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TernaryKeyboardMapping {
+    /// The degree of the scale word the mode starts on.
+    pub mode: usize,
+    /// The MIDI note playing the tonic of the mode.
+    pub root_note: u8,
+    /// The MIDI note with a given frequency.
+    pub reference_note: u8,
+    /// The frequency of `reference_note` in Hz.
+    pub reference_frequency: f64,
+}
+
+impl TryFrom<TernaryKeyboardMapping> for KeyboardMapping {
+    type Error = TernaryStatus;
+
+    fn try_from(mapping: TernaryKeyboardMapping) -> Result<Self, TernaryStatus> {
+        if usize::from(mapping.root_note) >= MIDI_NOTE_COUNT
+            || usize::from(mapping.reference_note) >= MIDI_NOTE_COUNT
+            || !(mapping.reference_frequency.is_finite() && mapping.reference_frequency > 0.0)
+        {
+            Err(TernaryStatus::InvalidArgument)
+        } else {
+            Ok(KeyboardMapping {
+                mode: mapping.mode,
+                root_note: mapping.root_note,
+                reference_note: mapping.reference_note,
+                reference_frequency: mapping.reference_frequency,
+            })
+        }
+    }
+}
+
+/// An opaque scale word.
+pub struct TernaryWord(Vec<Letter>);
+
+/// An opaque step tuning.
+pub struct TernaryTuning(StepTuning);
+
+/// An opaque list of step tunings.
+pub struct TernaryTuningList(Vec<StepTuning>);
+
+/// Run `f`, turning its error or a panic into a status.
+fn guard(f: impl FnOnce() -> Result<(), TernaryStatus>) -> TernaryStatus {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => TernaryStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => TernaryStatus::Panic,
+    }
+}
+
+/// Borrow the object behind a handle.
+///
+/// # Safety
+///
+/// `handle` must be null or point to a live object.
+unsafe fn borrow<'a, T>(handle: *const T) -> Result<&'a T, TernaryStatus> {
+    unsafe { handle.as_ref() }.ok_or(TernaryStatus::NullPointer)
+}
+
+/// Read a NUL-terminated UTF-8 string argument.
+///
+/// # Safety
+///
+/// `s` must be null or point to a NUL-terminated string.
+unsafe fn read_str<'a>(s: *const c_char) -> Result<&'a str, TernaryStatus> {
+    if s.is_null() {
+        return Err(TernaryStatus::NullPointer);
+    }
+    unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map_err(|_| TernaryStatus::InvalidUtf8)
+}
+
+/// Fail unless the out-parameter `out` is non-null.
+fn check_out<T>(out: *mut T) -> Result<(), TernaryStatus> {
+    if out.is_null() {
+        Err(TernaryStatus::NullPointer)
+    } else {
+        Ok(())
+    }
+}
+
+/// Hand a string to the caller, to be released with [`ternary_string_free`].
+fn into_c_string(s: String) -> Result<*mut c_char, TernaryStatus> {
+    CString::new(s)
+        .map(CString::into_raw)
+        .map_err(|_| TernaryStatus::InvalidArgument)
+}
+
+/// The step signature of a ternary scale word, or an error if the word isn't ternary.
+fn ternary_sig(word: &[Letter]) -> Result<Vec<usize>, TernaryStatus> {
+    let step_sig = word_to_sig(word);
+    check_ternary_sig(&step_sig).map_err(|_| TernaryStatus::InvalidWord)?;
+    Ok(step_sig)
+}
+
+fn equave_ratio(numer: u32, denom: u32) -> Result<RawJiRatio, TernaryStatus> {
+    match RawJiRatio::try_new(numer, denom) {
+        Ok(equave) if numer > denom => Ok(equave),
+        _ => Err(TernaryStatus::InvalidArgument),
+    }
+}
+
+/// A static, NUL-terminated description of `status`. The string must not be freed.
+#[unsafe(no_mangle)]
+pub extern "C" fn ternary_status_message(status: TernaryStatus) -> *const c_char {
+    let message: &'static CStr = match status {
+        TernaryStatus::Ok => c"ok",
+        TernaryStatus::NullPointer => c"null pointer argument",
+        TernaryStatus::InvalidUtf8 => c"string argument is not valid UTF-8",
+        TernaryStatus::InvalidWord => c"invalid scale word",
+        TernaryStatus::InvalidTuning => c"invalid tuning",
+        TernaryStatus::InvalidArgument => c"invalid argument",
+        TernaryStatus::OutOfRange => c"index out of range",
+        TernaryStatus::Panic => c"internal error",
+    };
+    message.as_ptr()
+}
+
+/// Release a string returned by the library. Does nothing for null.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by the library that hasn't been freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(unsafe { CString::from_raw(s) });
+    }
+}
+
+/// Release a byte buffer returned by the library. Does nothing for null.
+///
+/// # Safety
+///
+/// `bytes` must be null or a buffer returned by the library with length `len` that hasn't been
+/// freed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_bytes_free(bytes: *mut u8, len: usize) {
+    if !bytes.is_null() {
+        drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(bytes, len)) });
+    }
+}
+
+/// Read a scale word written with step letters, such as `"LmLsLmLsL"`.
+///
+/// # Safety
+///
+/// `word` must be a NUL-terminated string and `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_word_new(
+    word: *const c_char,
+    out: *mut *mut TernaryWord,
+) -> TernaryStatus {
+    guard(|| {
+        check_out(out)?;
+        let word = unsafe { read_str(word) }?;
+        let letters = try_string_to_numbers(word)?;
+        unsafe { out.write(Box::into_raw(Box::new(TernaryWord(letters)))) };
+        Ok(())
+    })
+}
+
+/// Release a scale word. Does nothing for null.
+///
+/// # Safety
+///
+/// `word` must be null or a live handle from [`ternary_word_new`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_word_free(word: *mut TernaryWord) {
+    if !word.is_null() {
+        drop(unsafe { Box::from_raw(word) });
+    }
+}
+
+/// The number of notes of a scale word, or 0 for null.
+///
+/// # Safety
+///
+/// `word` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_word_len(word: *const TernaryWord) -> usize {
+    unsafe { word.as_ref() }.map_or(0, |word| word.0.len())
+}
+
+/// The brightest mode of a scale word, written with step letters.
+///
+/// # Safety
+///
+/// `word` must be a live handle and `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_word_brightest_mode(
+    word: *const TernaryWord,
+    out: *mut *mut c_char,
+) -> TernaryStatus {
+    guard(|| {
+        check_out(out)?;
+        let word = unsafe { borrow(word) }?;
+        let mode = into_c_string(numbers_to_string(&crate::words::least_mode(&word.0)))?;
+        unsafe { out.write(mode) };
+        Ok(())
+    })
+}
+
+/// The profile of a ternary scale word.
+///
+/// # Safety
+///
+/// `word` must be a live handle and `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_word_profile(
+    word: *const TernaryWord,
+    out: *mut TernaryProfile,
+) -> TernaryStatus {
+    guard(|| {
+        check_out(out)?;
+        let word = unsafe { borrow(word) }?;
+        let step_sig = ternary_sig(&word.0)?;
+        let profile = word_to_profile(&word.0);
+        let (ed_l, ed_m, ed_s) = profile.ed_join;
+        let guide = guide_frames(&word.0).into_iter().next();
+        let result = TernaryProfile {
+            step_sig: [step_sig[0], step_sig[1], step_sig[2]],
+            max_variety: profile.mv,
+            chirality: profile.chirality.into(),
+            lm: profile.lm,
+            ms: profile.ms,
+            s0: profile.s0,
+            subst_l_ms: profile.subst_l_ms,
+            subst_m_ls: profile.subst_m_ls,
+            subst_s_lm: profile.subst_s_lm,
+            ed_join: [ed_l, ed_m, ed_s],
+            guide_complexity: guide.as_ref().map_or(0, |g| g.complexity() as u16),
+            guide_multiplicity: guide.as_ref().map_or(0, |g| g.multiplicity() as u16),
+            has_lattice_basis: profile.lattice_basis.is_some(),
+        };
+        unsafe { out.write(result) };
+        Ok(())
+    })
+}
+
+/// The full profile of a ternary scale word as JSON, with the same shape as in WASM.
+///
+/// # Safety
+///
+/// `word` must be a live handle and `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_word_profile_json(
+    word: *const TernaryWord,
+    out: *mut *mut c_char,
+) -> TernaryStatus {
+    guard(|| {
+        check_out(out)?;
+        let word = unsafe { borrow(word) }?;
+        ternary_sig(&word.0)?;
+        let json =
+            serde_json::to_string(&word_to_profile(&word.0)).map_err(|_| TernaryStatus::Panic)?;
+        let json = into_c_string(json)?;
+        unsafe { out.write(json) };
+        Ok(())
+    })
+}
+
+/// Read a tuning from `count` step size strings such as `"9/8"`, `"4\\22"` or `"203.9c"`,
+/// one for each step letter.
+///
+/// # Safety
+///
+/// `steps` must point to `count` NUL-terminated strings and `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_tuning_new(
+    steps: *const *const c_char,
+    count: usize,
+    out: *mut *mut TernaryTuning,
+) -> TernaryStatus {
+    guard(|| {
+        check_out(out)?;
+        if steps.is_null() {
+            return Err(TernaryStatus::NullPointer);
+        }
+        let steps = unsafe { std::slice::from_raw_parts(steps, count) }
+            .iter()
+            .map(|&step| unsafe { read_str(step) })
+            .collect::<Result<Vec<_>, _>>()?;
+        let tuning = StepTuning::from_strings(&steps).map_err(Error::from)?;
+        unsafe { out.write(Box::into_raw(Box::new(TernaryTuning(tuning)))) };
+        Ok(())
+    })
+}
+
+/// Release a tuning. Does nothing for null.
+///
+/// # Safety
+///
+/// `tuning` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_tuning_free(tuning: *mut TernaryTuning) {
+    if !tuning.is_null() {
+        drop(unsafe { Box::from_raw(tuning) });
+    }
+}
+
+/// The number of step sizes of a tuning, or 0 for null.
+///
+/// # Safety
+///
+/// `tuning` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_tuning_arity(tuning: *const TernaryTuning) -> usize {
+    unsafe { tuning.as_ref() }.map_or(0, |tuning| tuning.0.arity())
+}
+
+/// The size in cents of step `letter` of a tuning.
+///
+/// # Safety
+///
+/// `tuning` must be a live handle and `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_tuning_step_cents(
+    tuning: *const TernaryTuning,
+    letter: usize,
+    out: *mut f64,
+) -> TernaryStatus {
+    guard(|| {
+        check_out(out)?;
+        let tuning = unsafe { borrow(tuning) }?;
+        let cents = *tuning
+            .0
+            .step_cents()
+            .get(letter)
+            .ok_or(TernaryStatus::OutOfRange)?;
+        unsafe { out.write(cents) };
+        Ok(())
+    })
+}
+
+/// Step `letter` of a tuning as a string such as `"9/8"` or `"4\\22"`, as in SonicWeave.
+///
+/// # Safety
+///
+/// `tuning` must be a live handle and `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_tuning_step_string(
+    tuning: *const TernaryTuning,
+    letter: usize,
+    out: *mut *mut c_char,
+) -> TernaryStatus {
+    guard(|| {
+        check_out(out)?;
+        let tuning = unsafe { borrow(tuning) }?;
+        if letter >= tuning.0.arity() {
+            return Err(TernaryStatus::OutOfRange);
+        }
+        let step = into_c_string(crate::sonicweave::sonicweave_step(&tuning.0, letter))?;
+        unsafe { out.write(step) };
+        Ok(())
+    })
+}
+
+/// ED tunings of a ternary scale word's step signature, with at most `ed_bound` steps to the
+/// equave `equave_numer/equave_denom` and the small step between `s_lower` and `s_upper` cents.
+///
+/// # Safety
+///
+/// `word` must be a live handle and `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_ed_tunings(
+    word: *const TernaryWord,
+    equave_numer: u32,
+    equave_denom: u32,
+    ed_bound: i32,
+    s_lower: f64,
+    s_upper: f64,
+    out: *mut *mut TernaryTuningList,
+) -> TernaryStatus {
+    guard(|| {
+        check_out(out)?;
+        let word = unsafe { borrow(word) }?;
+        let step_sig = ternary_sig(&word.0)?;
+        let equave = equave_ratio(equave_numer, equave_denom)?;
+        let tunings = equal::ed_tunings_for_ternary(&step_sig, equave, ed_bound, s_lower, s_upper)
+            .into_iter()
+            .map(|steps| {
+                let ed = steps
+                    .iter()
+                    .zip(&step_sig)
+                    .map(|(&step, &count)| step * count as i32)
+                    .sum();
+                StepTuning::Ed { steps, ed, equave }
+            })
+            .collect();
+        unsafe { out.write(Box::into_raw(Box::new(TernaryTuningList(tunings)))) };
+        Ok(())
+    })
+}
+
+/// JI tunings of a ternary scale word's step signature to the equave `equave_numer/equave_denom`,
+/// with steps between `s_lower` and `s_upper` cents.
+///
+/// # Safety
+///
+/// `word` must be a live handle and `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_ji_tunings(
+    word: *const TernaryWord,
+    equave_numer: u32,
+    equave_denom: u32,
+    s_lower: f64,
+    s_upper: f64,
+    out: *mut *mut TernaryTuningList,
+) -> TernaryStatus {
+    guard(|| {
+        check_out(out)?;
+        let word = unsafe { borrow(word) }?;
+        let step_sig = ternary_sig(&word.0)?;
+        let equave = Monzo::try_from_ratio(equave_ratio(equave_numer, equave_denom)?)
+            .map_err(|_| TernaryStatus::InvalidArgument)?;
+        let tunings = ji::solve_step_sig_fast(&step_sig, equave, s_lower, s_upper)
+            .into_iter()
+            .map(StepTuning::Ji)
+            .collect();
+        unsafe { out.write(Box::into_raw(Box::new(TernaryTuningList(tunings)))) };
+        Ok(())
+    })
+}
+
+/// Release a tuning list. Does nothing for null.
+///
+/// # Safety
+///
+/// `list` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_tuning_list_free(list: *mut TernaryTuningList) {
+    if !list.is_null() {
+        drop(unsafe { Box::from_raw(list) });
+    }
+}
+
+/// The number of tunings in a list, or 0 for null.
+///
+/// # Safety
+///
+/// `list` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_tuning_list_len(list: *const TernaryTuningList) -> usize {
+    unsafe { list.as_ref() }.map_or(0, |list| list.0.len())
+}
+
+/// A copy of tuning `index` of a list, to be released with [`ternary_tuning_free`].
+///
+/// # Safety
+///
+/// `list` must be a live handle and `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_tuning_list_get(
+    list: *const TernaryTuningList,
+    index: usize,
+    out: *mut *mut TernaryTuning,
+) -> TernaryStatus {
+    guard(|| {
+        check_out(out)?;
+        let list = unsafe { borrow(list) }?;
+        let tuning = list.0.get(index).ok_or(TernaryStatus::OutOfRange)?.clone();
+        unsafe { out.write(Box::into_raw(Box::new(TernaryTuning(tuning)))) };
+        Ok(())
+    })
+}
+
+/// The contents of a Scala `.scl` file for a scale word under a tuning.
+///
+/// # Safety
+///
+/// `word` and `tuning` must be live handles and `out` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_scl(
+    word: *const TernaryWord,
+    tuning: *const TernaryTuning,
+    out: *mut *mut c_char,
+) -> TernaryStatus {
+    guard(|| {
+        check_out(out)?;
+        let word = unsafe { borrow(word) }?;
+        let tuning = unsafe { borrow(tuning) }?;
+        tuning.0.check_word(&word.0).map_err(Error::from)?;
+        let scl = into_c_string(word_to_scl(&word.0, &tuning.0))?;
+        unsafe { out.write(scl) };
+        Ok(())
+    })
+}
+
+/// The default keyboard mapping: the tonic on middle C at its 12edo frequency.
+#[unsafe(no_mangle)]
+pub extern "C" fn ternary_keyboard_mapping_default() -> TernaryKeyboardMapping {
+    let mapping = KeyboardMapping::default();
+    TernaryKeyboardMapping {
+        mode: mapping.mode,
+        root_note: mapping.root_note,
+        reference_note: mapping.reference_note,
+        reference_frequency: mapping.reference_frequency,
+    }
+}
+
+/// A MIDI Tuning Standard bulk tuning dump of a scale word under a tuning. A null `mapping` uses
+/// [`ternary_keyboard_mapping_default`] and a null `name` uses the scale word. The message is
+/// released with [`ternary_bytes_free`].
+///
+/// # Safety
+///
+/// `word` and `tuning` must be live handles, `mapping` and `name` must be null or valid, and
+/// `out` and `out_len` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ternary_mts_bulk_dump(
+    word: *const TernaryWord,
+    tuning: *const TernaryTuning,
+    mapping: *const TernaryKeyboardMapping,
+    device_id: u8,
+    program: u8,
+    name: *const c_char,
+    out: *mut *mut u8,
+    out_len: *mut usize,
+) -> TernaryStatus {
+    guard(|| {
+        check_out(out)?;
+        check_out(out_len)?;
+        let word = unsafe { borrow(word) }?;
+        let tuning = unsafe { borrow(tuning) }?;
+        tuning.0.check_word(&word.0).map_err(Error::from)?;
+        let mapping = match unsafe { mapping.as_ref() } {
+            Some(&mapping) => KeyboardMapping::try_from(mapping)?,
+            None => KeyboardMapping::default(),
+        };
+        let name = if name.is_null() {
+            numbers_to_string(&word.0)
+        } else {
+            unsafe { read_str(name) }?.to_string()
+        };
+        let message = midi::mts_bulk_dump(&word.0, &tuning.0, &mapping, device_id, program, &name)
+            .into_boxed_slice();
+        let len = message.len();
+        unsafe {
+            out.write(Box::into_raw(message).cast());
+            out_len.write(len);
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_word(s: &CStr) -> *mut TernaryWord {
+        let mut word = ptr::null_mut();
+        assert_eq!(
+            unsafe { ternary_word_new(s.as_ptr(), &mut word) },
+            TernaryStatus::Ok
+        );
+        word
+    }
+
+    #[test]
+    fn test_word_profile() {
+        let word = new_word(c"LmLsLmLsL");
+        let mut profile = TernaryProfile::default();
+        assert_eq!(
+            unsafe { ternary_word_profile(word, &mut profile) },
+            TernaryStatus::Ok
+        );
+        assert_eq!(profile.step_sig, [5, 2, 2]);
+        assert_eq!(profile.max_variety, 3);
+        assert_eq!(profile.chirality, TernaryChirality::Right);
+        assert_eq!(profile.guide_complexity, 2);
+
+        let mut mode = ptr::null_mut();
+        assert_eq!(
+            unsafe { ternary_word_brightest_mode(word, &mut mode) },
+            TernaryStatus::Ok
+        );
+        assert_eq!(unsafe { CStr::from_ptr(mode) }, c"LLmLsLmLs");
+        unsafe {
+            ternary_string_free(mode);
+            ternary_word_free(word);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut word = ptr::null_mut();
+        assert_eq!(
+            unsafe { ternary_word_new(c"LxL".as_ptr(), &mut word) },
+            TernaryStatus::InvalidWord
+        );
+        assert!(word.is_null());
+        assert_eq!(
+            unsafe { ternary_word_new(ptr::null(), &mut word) },
+            TernaryStatus::NullPointer
+        );
+        let binary = new_word(c"LLsLLLs");
+        let mut profile = TernaryProfile::default();
+        assert_eq!(
+            unsafe { ternary_word_profile(binary, &mut profile) },
+            TernaryStatus::InvalidWord
+        );
+        assert_eq!(
+            unsafe { CStr::from_ptr(ternary_status_message(TernaryStatus::InvalidWord)) },
+            c"invalid scale word"
+        );
+        unsafe { ternary_word_free(binary) };
+        assert_eq!(
+            TernaryStatus::from(Error::InvalidStepSig(vec![5, 2])),
+            TernaryStatus::InvalidArgument
+        );
+    }
+
+    #[test]
+    fn test_tunings_and_export() {
+        let word = new_word(c"LmLsLmLsL");
+        let mut list = ptr::null_mut();
+        assert_eq!(
+            unsafe { ternary_ed_tunings(word, 2, 1, 30, 20.0, 250.0, &mut list) },
+            TernaryStatus::Ok
+        );
+        assert_eq!(unsafe { ternary_tuning_list_len(list) }, 4);
+        let mut tuning = ptr::null_mut();
+        assert_eq!(
+            unsafe { ternary_tuning_list_get(list, 4, &mut tuning) },
+            TernaryStatus::OutOfRange
+        );
+        assert_eq!(
+            unsafe { ternary_tuning_list_get(list, 1, &mut tuning) },
+            TernaryStatus::Ok
+        );
+        let mut step = ptr::null_mut();
+        assert_eq!(
+            unsafe { ternary_tuning_step_string(tuning, 0, &mut step) },
+            TernaryStatus::Ok
+        );
+        assert_eq!(unsafe { CStr::from_ptr(step) }, c"4\\26");
+
+        let mut scl = ptr::null_mut();
+        assert_eq!(
+            unsafe { ternary_scl(word, tuning, &mut scl) },
+            TernaryStatus::Ok
+        );
+        let scl_text = unsafe { CStr::from_ptr(scl) }.to_str().unwrap();
+        assert!(scl_text.contains("\n 9\n"));
+
+        let mut bytes = ptr::null_mut();
+        let mut len = 0;
+        assert_eq!(
+            unsafe {
+                ternary_mts_bulk_dump(
+                    word,
+                    tuning,
+                    ptr::null(),
+                    0x7F,
+                    0,
+                    ptr::null(),
+                    &mut bytes,
+                    &mut len,
+                )
+            },
+            TernaryStatus::Ok
+        );
+        assert_eq!(len, 408);
+        assert_eq!(unsafe { *bytes }, 0xF0);
+        unsafe {
+            ternary_bytes_free(bytes, len);
+            ternary_string_free(scl);
+            ternary_string_free(step);
+            ternary_tuning_free(tuning);
+            ternary_tuning_list_free(list);
+            ternary_word_free(word);
+        }
+    }
+}
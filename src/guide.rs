@@ -89,7 +89,7 @@ where
 /// The last element is not counted and is required to differ from every element of the Guided GS.
 pub fn guided_gs_list(scale: &[usize]) -> Vec<Vec<CountVector<usize>>> {
     let len = scale.len();
-    (1..=len - 1) // Do include 1-step GSes
+    (1..len) // Do include 1-step GSes
         .filter(|&step_class| gcd(step_class as u32, len as u32) == 1)
         .flat_map(|step_class| step_class_guided_gs_list(step_class, scale))
         .collect()
@@ -548,6 +548,12 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_empty_scale() {
+        assert!(guided_gs_list(&[]).is_empty());
+        assert!(guided_gs_list(&[0]).is_empty());
+    }
+
     #[test]
     fn test_stacked_step_class() {
         let diasem: [usize; 9] = [0, 1, 0, 2, 0, 1, 0, 2, 0];
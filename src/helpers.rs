@@ -2,43 +2,24 @@ use std::collections::BTreeSet;
 use std::iter::Iterator;
 use {std::cmp::Ord, std::cmp::Ordering};
 
-#[derive(Default, Debug, PartialEq, Hash)]
 /// "Top-level errors" for scale construction and analysis.
+#[derive(Clone, Default, Debug, PartialEq, Hash, thiserror::Error)]
 pub enum ScaleError {
     /// When the required generator step class for an analysis is not coprime with scale length
+    #[error("generator class used is not coprime")]
     NonCoprimeGenError,
     /// When a subset's size is required to divide the size of the whole scale but does not
+    #[error("chose a subset size that does not divide the scale size")]
     NonDivisibleSubsetError,
     /// When an offset fails to meet the interleavability condition, resulting in non-interleaved scales
+    #[error("the chosen interval does not interleave the chosen strand scale")]
     NotInterleavable,
     /// Default error value for generic scale construction failures
     #[default]
+    #[error("error making scale")]
     CannotMakeScale,
 }
 
-impl std::fmt::Display for ScaleError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::NonCoprimeGenError => {
-                write!(f, "generator class used is not coprime")
-            }
-            &Self::NonDivisibleSubsetError => {
-                write!(f, "chose a subset size that does not divide the scale size")
-            }
-            &Self::NotInterleavable => {
-                write!(
-                    f,
-                    "the chosen interval does not interleave the chosen strand scale"
-                )
-            }
-            &Self::CannotMakeScale => {
-                write!(f, "erorr making scale")
-            }
-        }
-    }
-}
-impl std::error::Error for ScaleError {}
-
 /// The vector of all pairs with the first component from `v1` and second from `v2`.
 /// Produces the Cartesian product v1 × v2.
 pub fn pairs<T, U>(v1: &[T], v2: &[U]) -> Vec<(T, U)>
@@ -83,6 +64,39 @@ where
     true
 }
 
+/// Apply `f` to each item of `items`, keeping their order.
+/// With the `parallel` feature, the items are processed on all cores (except on WASM).
+pub fn par_map<T, U, F>(items: &[T], f: F) -> Vec<U>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> U + Sync + Send,
+{
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        use rayon::prelude::*;
+        items.par_iter().map(f).collect()
+    }
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    {
+        items.iter().map(f).collect()
+    }
+}
+
+/// How many items to pass to [`par_map`] at a time to keep every core busy:
+/// `per_thread` items for each thread of the rayon pool with the `parallel` feature
+/// (except on WASM), otherwise just `per_thread`.
+pub fn par_chunk_len(per_thread: usize) -> usize {
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        per_thread * rayon::current_num_threads()
+    }
+    #[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+    {
+        per_thread
+    }
+}
+
 /// Given a `&[Vec]`, convert to borrowed slice references `Vec<&[T]>`.
 pub fn slicify_each<T>(vecs: &[Vec<T>]) -> Vec<&[T]> {
     vecs.iter().map(|x| x.as_slice()).collect()
@@ -163,12 +177,12 @@ fn modulo(n: i32, m: i32) -> i32 {
 
 /// Return modular inverse: find x such that (x*a) mod b = 1.
 /// Returns error if a and b are not coprime.
-pub fn modinv(a: i32, b: i32) -> Result<i32, String> {
+pub fn modinv(a: i32, b: i32) -> Result<i32, ScaleError> {
     let (gcd, x, _) = extended_gcd(a, b);
     if gcd == 1 {
         Ok(modulo(x, b))
     } else {
-        Err("Non-coprime generator error".to_string())
+        Err(ScaleError::NonCoprimeGenError)
     }
 }
 
@@ -280,6 +294,19 @@ mod tests {
     #[test]
     fn test_modinv() {
         assert_eq!(modinv(1, 2), Ok(1));
+        assert_eq!(modinv(3, 7), Ok(5));
+        assert_eq!(modinv(2, 4), Err(ScaleError::NonCoprimeGenError));
+    }
+    #[test]
+    fn test_par_map() {
+        let items: Vec<u32> = (0..1000).collect();
+        assert_eq!(
+            par_map(&items, |x| x * x),
+            items.iter().map(|x| x * x).collect::<Vec<_>>()
+        );
+        assert!(par_map(&[] as &[u32], |x| *x).is_empty());
+        assert!(par_chunk_len(4) >= 4);
+        assert_eq!(par_chunk_len(4) % 4, 0);
     }
     #[test]
     fn test_bezout() {
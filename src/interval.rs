@@ -1,5 +1,7 @@
 use std::cmp::Ordering;
 
+use crate::ji_ratio::BadJiArith;
+
 /// Trait for any type representing concrete interval sizes.
 /// The trait provides the abstraction of an abelian group under interval stacking.
 /// Operations treat intervals as elements that can be combined additively.
@@ -57,6 +59,17 @@ pub trait Dyad: Copy + Eq + PartialEq + std::fmt::Debug + Send + Sync {
             }
         }
     }
+    /// [`rd`](Dyad::rd), returning an error instead of panicking if `modulo == unison()`.
+    fn try_rd(self, modulo: Self) -> Result<Self, BadJiArith>
+    where
+        Self: Sized,
+    {
+        if modulo == Self::unison() {
+            Err(BadJiArith::LogDivByUnison)
+        } else {
+            Ok(self.rd(modulo))
+        }
+    }
     /// Comparison for dyad sizes (based on logarithmic magnitude in cents).
     fn cmp_dyad(self, other: &Self) -> Ordering {
         self.cents().total_cmp(&other.cents())
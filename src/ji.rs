@@ -53,7 +53,7 @@ use num_integer::{gcd, lcm};
 use std::collections::BTreeSet;
 
 use crate::equal::is_in_tuning_range;
-use crate::helpers::{ScaleError, is_sorted_strictly_desc, pairs};
+use crate::helpers::{ScaleError, is_sorted_strictly_desc, pairs, par_chunk_len, par_map};
 use crate::interpretations::INTERPRETATIONS_270ET;
 use crate::interval::{Dyad, JiRatio};
 use crate::ji_ratio::{BadJiArith, RawJiRatio};
@@ -62,6 +62,7 @@ use crate::matrix::{det3, unimodular_inv};
 use crate::monzo;
 use crate::monzo::Monzo;
 use crate::primes::SMALL_PRIMES_COUNT;
+use crate::progress::{Cancelled, Monitor, Progress, Stage};
 use crate::words::{CountVector, rotate};
 
 /// Given a list of odd numbers, return the octave-reduced intervals in the corresponding odd-limit,
@@ -167,44 +168,151 @@ pub fn solve_step_sig_fast(
     result
 }
 
+/// Slower solver for JI solutions to a step signature (with decreasing step sizes),
+/// matching two interval classes of the scale to 27-odd-limit intervals.
+/// The smallest step is required to be between `cents_lower_bound` and `cents_upper_bound`.
 pub fn solve_step_sig_slow(
     step_sig: &[usize],
     equave: Monzo,
     cents_lower_bound: f64,
     cents_upper_bound: f64,
 ) -> Vec<Vec<Monzo>> {
-    let mut result = vec![];
-    let sig_i32: Vec<_> = step_sig.iter().map(|x| *x as i32).collect();
-    let equave_ratio = equave.try_to_ratio().unwrap_or(RawJiRatio::OCTAVE);
-    let targets: Vec<_> = odd_limit(27)
-        .into_iter()
-        .map(|x| Monzo::try_from_ratio(x).unwrap())
-        .collect();
+    SlowSolver::new(step_sig, equave, cents_lower_bound, cents_upper_bound).finish()
+}
+
+/// The search of [`solve_step_sig_slow`], as a resumable computation.
+///
+/// The solver tries each choice of step counts for the first interval class in turn;
+/// [`step`](SlowSolver::step) tries a given number of them and returns.
+/// With the `parallel` feature, the choices in each step are tried on all cores
+/// and their solutions are collected in order.
+///
+/// # Examples
+///
+/// ```
+/// use ternary::ji::{SlowSolver, solve_step_sig_slow};
+/// use ternary::monzo::Monzo;
+///
+/// let mut solver = SlowSolver::new(&[5, 2, 2], Monzo::OCTAVE, 20.0, 100.0);
+/// while !solver.step(10).is_done() {}
+/// assert_eq!(
+///     solver.finish(),
+///     solve_step_sig_slow(&[5, 2, 2], Monzo::OCTAVE, 20.0, 100.0)
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct SlowSolver {
+    sig: Vec<i32>,
+    equave: Monzo,
+    equave_ratio: RawJiRatio,
+    cents_lower_bound: f64,
+    cents_upper_bound: f64,
+    targets: Vec<Monzo>,
+    first_columns: Vec<[i32; 3]>,
+    next: usize,
+    solutions: Vec<Vec<Monzo>>,
+}
 
-    // Generate valid first step counts
-    let step_counts_1 =
-        iproduct!(0..=sig_i32[0], 0..=sig_i32[1], 0..=sig_i32[2]).filter(|&(l, m, s)| {
-            (l < sig_i32[0] || m < sig_i32[1] || s < sig_i32[2]) && gcd(l, gcd(m, s)) == 1
+impl SlowSolver {
+    /// A solver for the ternary step signature `step_sig` that hasn't started yet.
+    pub fn new(
+        step_sig: &[usize],
+        equave: Monzo,
+        cents_lower_bound: f64,
+        cents_upper_bound: f64,
+    ) -> Self {
+        let sig: Vec<_> = step_sig.iter().map(|x| *x as i32).collect();
+        let targets = odd_limit(27)
+            .into_iter()
+            .map(|x| Monzo::try_from_ratio(x).unwrap())
+            .collect();
+        // Generate valid first step counts
+        let first_columns = iproduct!(0..=sig[0], 0..=sig[1], 0..=sig[2])
+            .filter(|&(l, m, s)| (l < sig[0] || m < sig[1] || s < sig[2]) && gcd(l, gcd(m, s)) == 1)
+            .map(|(l, m, s)| [l, m, s])
+            .collect();
+        Self {
+            sig,
+            equave,
+            equave_ratio: equave.try_to_ratio().unwrap_or(RawJiRatio::OCTAVE),
+            cents_lower_bound,
+            cents_upper_bound,
+            targets,
+            first_columns,
+            next: 0,
+            solutions: vec![],
+        }
+    }
+
+    /// How many choices of the first interval class have been tried, out of how many.
+    pub fn progress(&self) -> Progress {
+        if self.next < self.first_columns.len() {
+            Progress {
+                stage: Stage::Solve,
+                done: self.next,
+                total: Some(self.first_columns.len()),
+            }
+        } else {
+            Progress::DONE
+        }
+    }
+
+    /// The solutions found so far.
+    pub fn solutions(&self) -> &[Vec<Monzo>] {
+        &self.solutions
+    }
+
+    /// Try at most `budget` more choices of the first interval class and return the progress.
+    pub fn step(&mut self, budget: usize) -> Progress {
+        let end = self
+            .first_columns
+            .len()
+            .min(self.next.saturating_add(budget));
+        let solutions = par_map(&self.first_columns[self.next..end], |&col1| {
+            self.solve_first_column(col1)
         });
-    for (l_count_1, m_count_1, s_count_1) in step_counts_1 {
-        let col1 = [l_count_1, m_count_1, s_count_1];
-        for target1 in &targets {
-            let target1_rd = target1.rd(equave);
-            if !is_in_tuning_range(target1_rd.cents(), &sig_i32, &col1, equave_ratio) {
+        self.solutions.extend(solutions.into_iter().flatten());
+        self.next = end;
+        self.progress()
+    }
+
+    /// Run the rest of the search, reporting to `monitor` after each chunk of choices of
+    /// the first interval class, one choice per thread with the `parallel` feature.
+    pub fn run(mut self, monitor: &mut Monitor) -> Result<Vec<Vec<Monzo>>, Cancelled> {
+        monitor.report(self.progress())?;
+        let chunk_len = par_chunk_len(1);
+        while !self.progress().is_done() {
+            monitor.report(self.step(chunk_len))?;
+        }
+        Ok(self.solutions)
+    }
+
+    /// Run the rest of the search and return all the solutions.
+    pub fn finish(mut self) -> Vec<Vec<Monzo>> {
+        self.step(usize::MAX);
+        self.solutions
+    }
+
+    fn solve_first_column(&self, col1: [i32; 3]) -> Vec<Vec<Monzo>> {
+        let mut solutions = vec![];
+        let [l_count_1, m_count_1, s_count_1] = col1;
+        for target1 in &self.targets {
+            let target1_rd = target1.rd(self.equave);
+            if !is_in_tuning_range(target1_rd.cents(), &self.sig, &col1, self.equave_ratio) {
                 continue;
             }
 
             // Generate valid second step counts
-            let step_counts_2 =
-                iproduct!(0..=sig_i32[0], 0..=sig_i32[1], 0..=sig_i32[2]).filter(|&(l, m, s)| {
-                    (l < sig_i32[0] || m < sig_i32[1] || s < sig_i32[2])
+            let step_counts_2 = iproduct!(0..=self.sig[0], 0..=self.sig[1], 0..=self.sig[2])
+                .filter(|&(l, m, s)| {
+                    (l < self.sig[0] || m < self.sig[1] || s < self.sig[2])
                         && (l != l_count_1 || m != m_count_1 || s != s_count_1)
                         && gcd(l, gcd(m, s)) == 1
                 });
 
             for (l_count_2, m_count_2, s_count_2) in step_counts_2 {
                 let col2 = [l_count_2, m_count_2, s_count_2];
-                if det3(&sig_i32, &col1, &col2).abs() == 1 {
+                if det3(&self.sig, &col1, &col2).abs() == 1 {
                     // [L_i m_i s_i] [sig col1 col2] = [equave_i target1_i target2_i]
                     // e.g. for 5-limit blackdye
                     //      [ 1  4 -4] [5 3 2] = [1 -1 -2]
@@ -212,16 +320,21 @@ pub fn solve_step_sig_slow(
                     //      [ 1 -1 -1] [3 2 1]   [0  0  1]
                     // The RHS columns are the *reduced* targets!
                     // => [L_i m_i s_i] = [equave_i target1_i target2_i] * inv for monzo index i
-                    let inv = unimodular_inv(&sig_i32, &col1, &col2);
-                    for target2 in &targets {
-                        let target2_rd = target2.rd(equave);
+                    let inv = unimodular_inv(&self.sig, &col1, &col2);
+                    for target2 in &self.targets {
+                        let target2_rd = target2.rd(self.equave);
                         if *target2 != *target1
-                            && is_in_tuning_range(target2_rd.cents(), &sig_i32, &col2, equave_ratio)
+                            && is_in_tuning_range(
+                                target2_rd.cents(),
+                                &self.sig,
+                                &col2,
+                                self.equave_ratio,
+                            )
                         {
                             let coeffs: Vec<_> = (0..SMALL_PRIMES_COUNT)
                                 .map(|i| {
                                     covector_times_matrix(
-                                        &[equave[i], target1_rd[i], target2_rd[i]],
+                                        &[self.equave[i], target1_rd[i], target2_rd[i]],
                                         &inv[0],
                                         &inv[1],
                                         &inv[2],
@@ -262,20 +375,20 @@ pub fn solve_step_sig_slow(
                                 coeffs[8][2],
                             ];
                             if s.is_positive()
-                                    && l > m // Compare size using the Dyad trait implemented by Monzo
-                                    && m > s
-                                    && cents_lower_bound < s.cents()
-                                    && s.cents() < cents_upper_bound
+                                && l > m // Compare size using the Dyad trait implemented by Monzo
+                                && m > s
+                                && self.cents_lower_bound < s.cents()
+                                && s.cents() < self.cents_upper_bound
                             {
-                                result.push(vec![l, m, s]);
+                                solutions.push(vec![l, m, s]);
                             }
                         }
                     }
                 }
             }
         }
+        solutions
     }
-    result
 }
 
 /// Multiset of `subword_length`-step intervals in a JI scale.
@@ -580,6 +693,29 @@ mod tests {
         assert_eq!(seconds.keys_count(), 2);
     }
 
+    #[test]
+    fn test_slow_solver_progress() {
+        use crate::progress::{CancelToken, Cancelled};
+
+        let solver = SlowSolver::new(&[5, 2, 2], Monzo::OCTAVE, 20.0, 100.0);
+        let total = solver.progress().total.unwrap();
+        let mut reports = vec![];
+        let mut monitor = Monitor::new().on_progress(|progress| reports.push(progress));
+        let solutions = solver.clone().run(&mut monitor).unwrap();
+        drop(monitor);
+        assert_eq!(
+            solutions,
+            solve_step_sig_slow(&[5, 2, 2], Monzo::OCTAVE, 20.0, 100.0)
+        );
+        assert_eq!(reports.len(), total.div_ceil(par_chunk_len(1)) + 1);
+        assert_eq!(reports.last(), Some(&Progress::DONE));
+
+        let token = CancelToken::new();
+        token.cancel();
+        let mut monitor = Monitor::new().cancel_token(token);
+        assert_eq!(solver.run(&mut monitor), Err(Cancelled));
+    }
+
     #[test]
     fn test_ji_scale_modes() {
         let diasem_modes = ji_scale_modes(&RawJiRatio::TAS_9);
@@ -36,52 +36,40 @@
 use std::cmp::Ordering;
 use std::fmt;
 use std::ops::{Div, DivAssign, Mul, MulAssign};
+use std::str::FromStr;
 
 use num_traits::{CheckedDiv, CheckedMul};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 
 use crate::helpers::gcd;
 use crate::interval::{Dyad, JiRatio};
 
 // ERRORS
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 /// Error type for attempts to construct a RawJiRatio from a non-positive ratio.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, thiserror::Error)]
+#[error("tried to create invalid JI ratio {numer}/{denom}")]
 pub struct IllegalJiRatio {
     // Show what the attempt was
     numer: u32,
     denom: u32,
 }
 
-impl fmt::Display for IllegalJiRatio {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "tried to create invalid JI ratio {}/{}",
-            self.numer, self.denom
-        )
-    }
-}
-
-impl std::error::Error for IllegalJiRatio {}
-
 /// Error type for invalid outputs of JI interval arithmetic.
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug, thiserror::Error)]
 pub enum BadJiArith {
     /// logarithmic division by 0
+    #[error("tried to log divide a JI ratio by the unison")]
     LogDivByUnison,
+    /// division of a monzo's exponents by 0
+    #[error("tried to divide a monzo by zero")]
+    DivByZero,
 }
 
-impl std::fmt::Display for BadJiArith {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            &Self::LogDivByUnison => {
-                write!(f, "tried to log divide a JI ratio by the unison")
-            }
-        }
-    }
-}
-
-impl std::error::Error for BadJiArith {}
+/// Error type for strings that can't be read as a [`RawJiRatio`].
+#[derive(Clone, PartialEq, Eq, Debug, thiserror::Error)]
+#[error("`{0}` is not a JI ratio such as `3/2` or `3`")]
+pub struct ParseJiRatioError(String);
 
 // STRUCTS
 
@@ -522,6 +510,71 @@ impl fmt::Display for RawJiRatio {
     }
 }
 
+/// Reads `"n/d"`, or `"n"` for a whole number, as [`Display`](fmt::Display) writes it.
+///
+/// ```
+/// use ternary::ji_ratio::RawJiRatio;
+///
+/// assert_eq!("6/4".parse(), Ok(RawJiRatio::PYTH_5TH));
+/// assert_eq!("3".parse(), Ok(RawJiRatio::TRITAVE));
+/// assert!("0/1".parse::<RawJiRatio>().is_err());
+/// assert!("3:2".parse::<RawJiRatio>().is_err());
+/// ```
+impl FromStr for RawJiRatio {
+    type Err = ParseJiRatioError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (numer, denom) = s.split_once('/').unwrap_or((s, "1"));
+        match (numer.trim().parse::<u32>(), denom.trim().parse::<u32>()) {
+            (Ok(numer), Ok(denom)) => {
+                RawJiRatio::try_new(numer, denom).map_err(|_| ParseJiRatioError(s.to_string()))
+            }
+            _ => Err(ParseJiRatioError(s.to_string())),
+        }
+    }
+}
+
+/// Serialized as a string such as `"3/2"`.
+impl Serialize for RawJiRatio {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Deserialized from a string such as `"3/2"` or `"3"`, or from a positive integer.
+impl<'de> Deserialize<'de> for RawJiRatio {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RatioVisitor;
+
+        impl de::Visitor<'_> for RatioVisitor {
+            type Value = RawJiRatio;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a JI ratio such as \"3/2\" or a positive integer")
+            }
+
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<RawJiRatio, E> {
+                s.parse().map_err(E::custom)
+            }
+
+            fn visit_u64<E: de::Error>(self, n: u64) -> Result<RawJiRatio, E> {
+                u32::try_from(n)
+                    .ok()
+                    .and_then(|n| RawJiRatio::try_new(n, 1).ok())
+                    .ok_or_else(|| E::custom(format!("{n} is not a JI ratio")))
+            }
+
+            fn visit_i64<E: de::Error>(self, n: i64) -> Result<RawJiRatio, E> {
+                u64::try_from(n)
+                    .map_err(|_| E::custom(format!("{n} is not a JI ratio")))
+                    .and_then(|n| self.visit_u64(n))
+            }
+        }
+
+        deserializer.deserialize_any(RatioVisitor)
+    }
+}
+
 impl Mul for RawJiRatio {
     type Output = Self;
     fn mul(self, other: Self) -> Self {
@@ -15,6 +15,21 @@
 //! 2. Project each pitch onto the 2D plane spanned by the basis vectors
 //! 3. The result shows the geometric structure of the scale
 //!
+//! # Choosing a basis
+//!
+//! A scale usually has several bases showing it as a parallelogram substring.
+//! [`parallelogram_bases`] finds them all and [`ranked_lattice_bases`] sorts them by a
+//! [`BasisScore`], such as the shortest vectors or the vector nearest a JI interval.
+//! [`reduce_basis`] gives the shortest basis of the lattice, parallelogram or not, which
+//! [`BasisScore::Shortest`] measures the other bases against.
+//!
+//! # Scales without a 2D lattice
+//!
+//! Scales that repeat more than once per equave have torsion, and some scales have no guide
+//! frame with vectors completing the equave to a unimodular basis. For these,
+//! [`fallback_lattice`] gives 3D coordinates in a basis made of the period and two vectors
+//! completing it, with the reason there is no 2D lattice and a [`Projection`] to draw it with.
+//!
 //! # Examples
 //!
 //! ```
@@ -32,16 +47,18 @@
 
 use std::iter::IntoIterator;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use crate::GuideResult;
 use crate::countvector_to_u16_vec;
 use crate::equal::direct_approx;
 use crate::guide::*;
 use crate::guide_frame_to_result;
+use crate::interval::Dyad;
 use crate::ji_ratio::RawJiRatio;
 use crate::matrix;
 use crate::matrix::unimodular_inv;
+use crate::tuning::{BadTuning, StepTuning};
 use crate::word_to_sig;
 use crate::words::CountVector;
 
@@ -61,7 +78,7 @@ use crate::words::CountVector;
 /// assert_eq!(basis.vx(), &[1, 1, 0]);
 /// assert_eq!(basis.vy(), &[0, 1, 1]);
 /// ```
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PitchClassLatticeBasis {
     vx: Vec<i32>,
     vy: Vec<i32>,
@@ -114,7 +131,7 @@ impl IntoIterator for PitchClassLatticeBasis {
 }
 
 /// A struct representing a substring of a row-by-row traversal of a lattice parallelogram.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ParallelogramSubstring {
     row_count: i32,
     full_row_len: i32,
@@ -236,6 +253,30 @@ pub fn pitch_classes(
     (pitch_classes, basis.clone())
 }
 
+/// The coordinates `[x, y]` of each pitch of `query` in `basis`, such that the pitch is
+/// `x * basis.vx() + y * basis.vy()` up to equaves. Unlike [`pitch_classes`], which gives
+/// their negatives if `[equave, vx, vy]` has determinant -1.
+///
+/// # Examples
+///
+/// ```
+/// use ternary::lattice::{PitchClassLatticeBasis, lattice_coordinates};
+///
+/// // LLmLLms with the basis L, LLm
+/// let basis = PitchClassLatticeBasis::from_slices(&[1, 0, 0], &[2, 1, 0]);
+/// let coordinates = lattice_coordinates(&[0, 0, 1, 0, 0, 1, 2], &basis);
+/// assert_eq!(coordinates[..3], [vec![1, 0], vec![2, 0], vec![0, 1]]);
+/// ```
+pub fn lattice_coordinates(query: &[usize], basis: &PitchClassLatticeBasis) -> Vec<Vec<i32>> {
+    let sig: Vec<i32> = word_to_sig(query).iter().map(|&x| x as i32).collect();
+    let (mut coordinates, _) = pitch_classes(query, basis);
+    // `pitch_classes` multiplies by the adjugate, which is minus the inverse here
+    if matrix::det3(&sig, basis.vx(), basis.vy()) == -1 {
+        coordinates.iter_mut().flatten().for_each(|x| *x = -*x);
+    }
+    coordinates
+}
+
 /// Compute 2D lattice coordinates for each pitch in a scale.
 ///
 /// Finds a unimodular basis from the scale's guide frames and projects
@@ -307,6 +348,187 @@ pub fn try_pitch_class_lattice(query: &[usize]) -> Option<(Vec<Vec<i32>>, PitchC
     })
 }
 
+/// Why a scale has no 2D pitch class lattice from [`try_pitch_class_lattice`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NoLatticeReason {
+    /// The step signature is `periods` times a smaller vector, the period, so the scale repeats
+    /// `periods` times per equave. Pitch classes then have torsion (`periods` periods make a
+    /// unison) and no two vectors complete the equave to a unimodular basis.
+    Torsion { periods: i32 },
+    /// The scale has no guide frame to take basis vectors from.
+    NoGuideFrame,
+    /// No pair of vectors from the guide frames completes the equave to a unimodular basis.
+    NoUnimodularPair,
+}
+
+impl std::fmt::Display for NoLatticeReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Torsion { periods } => write!(
+                f,
+                "the scale repeats {periods} times per equave, so its pitch classes have torsion"
+            ),
+            Self::NoGuideFrame => write!(f, "the scale has no guide frame"),
+            Self::NoUnimodularPair => write!(
+                f,
+                "no pair of guide frame vectors completes the equave to a unimodular basis"
+            ),
+        }
+    }
+}
+
+/// How to draw a [`Lattice3d`] in two dimensions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Projection {
+    /// Plot `[x, y]`; every pitch class is on layer 0.
+    Plane,
+    /// Plot `[x, y]` and tell the `count` layers apart,
+    /// e.g. by color or by shifting layer `k` by `k / count` along a diagonal.
+    Layers { count: i32 },
+}
+
+/// A 3D pitch class lattice, for scales without a 2D one.
+///
+/// The basis is `[p, v, w]`, where `p` is the period of the scale (the equave divided by the
+/// number of times the scale repeats in it) and `v` and `w` complete `p` to a unimodular basis.
+/// Every pitch is `k p + x v + y w`, and its pitch class is `[k mod periods, x, y]`.
+///
+/// # Examples
+///
+/// ```
+/// use ternary::lattice::{NoLatticeReason, Projection, fallback_lattice};
+///
+/// // LmsLms repeats twice per octave
+/// let lattice = fallback_lattice(&[0, 1, 2, 0, 1, 2]).unwrap();
+/// assert_eq!(lattice.reason, NoLatticeReason::Torsion { periods: 2 });
+/// assert_eq!(lattice.projection, Projection::Layers { count: 2 });
+/// assert_eq!(lattice.basis[0], [1, 1, 1]);
+/// assert_eq!(lattice.steps[2], [1, 1, 1]);
+/// // the period is on the other layer from the octave
+/// assert_eq!(lattice.coordinates[2], [1, 0, 0]);
+/// assert_eq!(lattice.coordinates[5], [0, 0, 0]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lattice3d {
+    /// the step counts `[L, m, s]` of each pitch, i.e. its coordinates in the (L, m, s) basis
+    pub steps: Vec<[i32; 3]>,
+    /// the `[layer, x, y]` coordinates of each pitch class in `basis`
+    pub coordinates: Vec<[i32; 3]>,
+    /// the period and the two vectors completing it, as step counts
+    pub basis: [[i32; 3]; 3],
+    /// why the scale has no 2D lattice
+    pub reason: NoLatticeReason,
+    /// how to draw the coordinates
+    pub projection: Projection,
+}
+
+/// Vectors `v` and `w` such that `[p, v, w]` has determinant ±1, for a primitive vector `p`.
+///
+/// Runs the Euclidean algorithm on the entries of `p` with column operations on the identity,
+/// keeping `p` equal to the basis times the remaining entries.
+fn complete_to_unimodular(p: [i32; 3]) -> ([i32; 3], [i32; 3]) {
+    let mut basis = [[1, 0, 0], [0, 1, 0], [0, 0, 1]];
+    let mut entries = p;
+    loop {
+        let nonzero: Vec<usize> = (0..3).filter(|&i| entries[i] != 0).collect();
+        let Some(&i) = nonzero.iter().min_by_key(|&&i| entries[i].abs()) else {
+            unreachable!("a primitive vector is nonzero");
+        };
+        if nonzero.len() == 1 {
+            // now p = ±basis[i]
+            let [j, k] = [(i + 1) % 3, (i + 2) % 3];
+            return (basis[j], basis[k]);
+        }
+        for &j in &nonzero {
+            if j != i {
+                let q = entries[j] / entries[i];
+                entries[j] -= q * entries[i];
+                basis[i] = std::array::from_fn(|r| basis[i][r] + q * basis[j][r]);
+            }
+        }
+    }
+}
+
+/// A 3D pitch class lattice for `query` if [`try_pitch_class_lattice`] finds no 2D one.
+///
+/// Returns `None` if the scale has a 2D lattice or is empty.
+/// The basis vectors after the period come from the guide frames where possible.
+pub fn fallback_lattice(query: &[usize]) -> Option<Lattice3d> {
+    let sig: Vec<i32> = word_to_sig(query).iter().map(|&x| x as i32).collect();
+    let periods = sig
+        .iter()
+        .fold(0, |acc, &x| crate::helpers::gcd(acc, x as u32)) as i32;
+    if periods == 0 {
+        return None;
+    }
+    let gfs = guide_frames(query);
+    let reason = if periods > 1 {
+        NoLatticeReason::Torsion { periods }
+    } else if gfs.is_empty() {
+        NoLatticeReason::NoGuideFrame
+    } else if get_unimodular_basis(&gfs, &sig).is_some() {
+        return None;
+    } else {
+        NoLatticeReason::NoUnimodularPair
+    };
+    let period: [i32; 3] = std::array::from_fn(|i| sig[i] / periods);
+
+    // Prefer generators and offsets of the guide frames to an arbitrary completion.
+    let candidates: Vec<Vec<i32>> = gfs
+        .iter()
+        .flat_map(|gf| {
+            let result = guide_frame_to_result(gf);
+            result.gs.into_iter().chain(result.offset_chord)
+        })
+        .map(|v| v.into_iter().map(|x| x as i32).collect())
+        .collect();
+    let pair = candidates.iter().enumerate().find_map(|(i, v)| {
+        candidates[i + 1..]
+            .iter()
+            .find(|w| matrix::det3(&period, v, w).abs() == 1)
+            .map(|w| (v, w))
+    });
+    let (v, w) = match pair {
+        Some((v, w)) => (std::array::from_fn(|i| v[i]), std::array::from_fn(|i| w[i])),
+        None => complete_to_unimodular(period),
+    };
+    // `unimodular_inv` gives the adjugate, which is the inverse only for determinant 1
+    let (v, w) = if matrix::det3(&period, &v, &w) == 1 {
+        (v, w)
+    } else {
+        (w, v)
+    };
+
+    let basis_change = unimodular_inv(&period, &v, &w);
+    let mut steps = vec![];
+    let mut coordinates = vec![];
+    let mut count_vector = CountVector::ZERO;
+    for step in query {
+        count_vector = count_vector.add(&CountVector::from_slice(&[*step]));
+        let u: Vec<i32> = countvector_to_u16_vec(&count_vector)
+            .iter()
+            .map(|x| *x as i32)
+            .collect();
+        let t =
+            matrix::matrix_times_vector(&basis_change[0], &basis_change[1], &basis_change[2], &u);
+        steps.push([u[0], u[1], u[2]]);
+        coordinates.push([t[0].rem_euclid(periods), t[1], t[2]]);
+    }
+    Some(Lattice3d {
+        steps,
+        coordinates,
+        basis: [period, v, w],
+        reason,
+        projection: if periods == 1 {
+            Projection::Plane
+        } else {
+            Projection::Layers { count: periods }
+        },
+    })
+}
+
 /// Whether the result is `Some` or `None` depends on
 /// whether the pitch classes form a substring of a parallelogram traversal,
 /// i.e. its pitch classes forming a substring of a traversal
@@ -361,385 +583,683 @@ pub fn parallelogram_substring_info(
     // to write the basis in step size coordinates.
     for (i, vx) in pairwise_differences.iter().enumerate() {
         for vy in pairwise_differences.iter().skip(i + 1) {
-            if (vx[0] * vy[1] - vx[1] * vy[0]).abs() == 1 {
-                // Change coordinates to basis (v1, v2)
-                let basis_change: Vec<Vec<i32>> = vec![vec![vy[1], -vx[1]], vec![-vy[0], vx[0]]];
-                let mut pitch_classes_transformed = pitch_classes
-                    .iter()
-                    .map(|v| {
-                        vec![
-                            basis_change[0][0] * v[0] + basis_change[1][0] * v[1],
-                            basis_change[0][1] * v[0] + basis_change[1][1] * v[1],
-                        ]
-                    })
+            if (vx[0] * vy[1] - vx[1] * vy[0]).abs() == 1
+                && let Some(found) = parallelogram_witness(pitch_classes, vx, vy, old_basis)
+            {
+                return Some(found);
+            }
+        }
+    }
+    None
+}
+
+/// The parallelogram substring traversal that `pitch_classes` form with rows along `vx` or `vy`,
+/// and the basis `(vx, vy)` in scale step coordinates with the row generator first, if any.
+/// `vx` and `vy` are in the coordinates of `old_basis` and must have determinant ±1.
+pub(crate) fn parallelogram_witness(
+    pitch_classes: &[&[i32]],
+    vx: &[i32],
+    vy: &[i32],
+    old_basis: &PitchClassLatticeBasis,
+) -> Option<(ParallelogramSubstring, PitchClassLatticeBasis)> {
+    // Change coordinates to basis (v1, v2)
+    let basis_change: Vec<Vec<i32>> = vec![vec![vy[1], -vx[1]], vec![-vy[0], vx[0]]];
+    let mut pitch_classes_transformed = pitch_classes
+        .iter()
+        .map(|v| {
+            vec![
+                basis_change[0][0] * v[0] + basis_change[1][0] * v[1],
+                basis_change[0][1] * v[0] + basis_change[1][1] * v[1],
+            ]
+        })
+        .collect::<Vec<_>>();
+    // Get window dimensions: x_min, x_max, y_min, y_max
+    let mut xs: Vec<_> = pitch_classes_transformed.iter().map(|v| v[0]).collect();
+    let mut ys: Vec<_> = pitch_classes_transformed.iter().map(|v| v[1]).collect();
+    xs.sort();
+    ys.sort();
+    let x_min = xs[0];
+    let x_max = xs[xs.len() - 1];
+    let y_min = ys[0];
+    let y_max = ys[ys.len() - 1];
+    // Check all 4 possible traversals:
+    // 1. each row LTR (increases in x), rows go BTT (increases in y) (equivalently each row RTL, rows go TTB)
+    // 2. each row RTL (decreases in x), rows go BTT (increases in y) (equivalently each row LTR, rows go TTB)
+    // 3. each row BTT (increases in y), rows go LTR (increases in x) (equivalently each row TTB, rows go RTL)
+    // 4. each row TTB (decreases in y), rows go LTR (increases in x) (equivalently each row BTT, rows go RTL)
+    'traversal12: {
+        // Sort pitch_classes_transformed in lex order for traversal 1
+        pitch_classes_transformed.sort_by(|v1, v2| {
+            // Sort by *ascending* y values, if y values are equal sort by *ascending* x values
+            v1[1].cmp(&v2[1]).then(v1[0].cmp(&v2[0]))
+        });
+        let mut index = 0; // index into pitch_classes_transformed
+        // Check if middle rows are fully occupied; if not break out of block early
+        let ys_middle = (y_min + 1)..=(y_max - 1);
+        let full_row_len = x_max - x_min + 1; // Required length of each middle row
+        for y in ys_middle {
+            let mut row_counter = 0; // Count pitches with this y value
+            while pitch_classes_transformed[index][1] < y {
+                index += 1;
+            }
+            while pitch_classes_transformed[index][1] == y {
+                row_counter += 1;
+                index += 1;
+            }
+            if row_counter != full_row_len {
+                break 'traversal12;
+            }
+        }
+        'traversal1: {
+            // Check outer rows for traversal 1
+            // Last row must be a prefix of a row traversal
+            let mut last_row = vec![];
+            while pitch_classes_transformed[index][1] < y_max {
+                index += 1;
+            }
+            while index < pitch_classes_transformed.len() {
+                last_row.push(pitch_classes_transformed[index].clone());
+                index += 1;
+            }
+            // First x value in last_row == x_min AND
+            // |last x value in row - first x value in row| + 1 == last_row.len()
+            // (last_row should be sorted by *ascending* x values)
+            let last_row_is_prefix = !last_row.is_empty()
+                && last_row[0][0] == x_min
+                && (last_row[last_row.len() - 1][0] - x_min + 1) as usize == last_row.len();
+            if !last_row_is_prefix {
+                break 'traversal1;
+            }
+            // First row must be a suffix of a row traversal
+            index = 0;
+            let mut first_row = vec![];
+            while pitch_classes_transformed[index][1] == y_min {
+                first_row.push(pitch_classes_transformed[index].clone());
+                index += 1;
+            }
+            // Last x value in first_row == x_max AND
+            // |last x value in row - first x value in row| + 1 == first_row.len()
+            // (first_row should be sorted by *ascending* x values)
+            let first_row_is_suffix = !first_row.is_empty()
+                && first_row[first_row.len() - 1][0] == x_max
+                && (x_max - first_row[0][0] + 1) as usize == first_row.len();
+
+            // IMPORTANT: When there are no middle rows,
+            // verify that first and last rows span the same x-range.
+            let row_count = y_max - y_min + 1;
+            let rows_compatible = if row_count == 2 || full_row_len == 2 {
+                // At least one row must span the full range [x_min, x_max]
+                (first_row.len() as i32 == full_row_len) || (last_row.len() as i32 == full_row_len)
+            } else {
+                true // Middle rows already enforce consistency
+            };
+
+            if first_row_is_suffix && rows_compatible {
+                let row_count = y_max - y_min + 1;
+                let first_row_len = first_row.len() as i32;
+                let last_row_len = last_row.len() as i32;
+                let vx_lms = (0..3) // for each of L, m, s
+                    .map(|i| vx[0] * old_basis.vx[i] + vx[1] * old_basis.vy[i])
                     .collect::<Vec<_>>();
-                // Get window dimensions: x_min, x_max, y_min, y_max
-                let mut xs: Vec<_> = pitch_classes_transformed.iter().map(|v| v[0]).collect();
-                let mut ys: Vec<_> = pitch_classes_transformed.iter().map(|v| v[1]).collect();
-                xs.sort();
-                ys.sort();
-                let x_min = xs[0];
-                let x_max = xs[xs.len() - 1];
-                let y_min = ys[0];
-                let y_max = ys[ys.len() - 1];
-                // Check all 4 possible traversals:
-                // 1. each row LTR (increases in x), rows go BTT (increases in y) (equivalently each row RTL, rows go TTB)
-                // 2. each row RTL (decreases in x), rows go BTT (increases in y) (equivalently each row LTR, rows go TTB)
-                // 3. each row BTT (increases in y), rows go LTR (increases in x) (equivalently each row TTB, rows go RTL)
-                // 4. each row TTB (decreases in y), rows go LTR (increases in x) (equivalently each row BTT, rows go RTL)
-                'traversal12: {
-                    // Sort pitch_classes_transformed in lex order for traversal 1
-                    pitch_classes_transformed.sort_by(|v1, v2| {
-                        // Sort by *ascending* y values, if y values are equal sort by *ascending* x values
-                        v1[1].cmp(&v2[1]).then(v1[0].cmp(&v2[0]))
-                    });
-                    let mut index = 0; // index into pitch_classes_transformed
-                    // Check if middle rows are fully occupied; if not break out of block early
-                    let ys_middle = (y_min + 1)..=(y_max - 1);
-                    let full_row_len = x_max - x_min + 1; // Required length of each middle row
-                    for y in ys_middle {
-                        let mut row_counter = 0; // Count pitches with this y value
-                        while pitch_classes_transformed[index][1] < y {
-                            index += 1;
-                        }
-                        while pitch_classes_transformed[index][1] == y {
-                            row_counter += 1;
-                            index += 1;
-                        }
-                        if row_counter != full_row_len {
-                            break 'traversal12;
-                        }
-                    }
-                    'traversal1: {
-                        // Check outer rows for traversal 1
-                        // Last row must be a prefix of a row traversal
-                        let mut last_row = vec![];
-                        while pitch_classes_transformed[index][1] < y_max {
-                            index += 1;
-                        }
-                        while index < pitch_classes_transformed.len() {
-                            last_row.push(pitch_classes_transformed[index].clone());
-                            index += 1;
-                        }
-                        // First x value in last_row == x_min AND
-                        // |last x value in row - first x value in row| + 1 == last_row.len()
-                        // (last_row should be sorted by *ascending* x values)
-                        let last_row_is_prefix = !last_row.is_empty()
-                            && last_row[0][0] == x_min
-                            && (last_row[last_row.len() - 1][0] - x_min + 1) as usize
-                                == last_row.len();
-                        if !last_row_is_prefix {
-                            break 'traversal1;
-                        }
-                        // First row must be a suffix of a row traversal
-                        index = 0;
-                        let mut first_row = vec![];
-                        while pitch_classes_transformed[index][1] == y_min {
-                            first_row.push(pitch_classes_transformed[index].clone());
-                            index += 1;
-                        }
-                        // Last x value in first_row == x_max AND
-                        // |last x value in row - first x value in row| + 1 == first_row.len()
-                        // (first_row should be sorted by *ascending* x values)
-                        let first_row_is_suffix = !first_row.is_empty()
-                            && first_row[first_row.len() - 1][0] == x_max
-                            && (x_max - first_row[0][0] + 1) as usize == first_row.len();
-
-                        // IMPORTANT: When there are no middle rows,
-                        // verify that first and last rows span the same x-range.
-                        let row_count = y_max - y_min + 1;
-                        let rows_compatible = if row_count == 2 || full_row_len == 2 {
-                            // At least one row must span the full range [x_min, x_max]
-                            (first_row.len() as i32 == full_row_len)
-                                || (last_row.len() as i32 == full_row_len)
-                        } else {
-                            true // Middle rows already enforce consistency
-                        };
-
-                        if first_row_is_suffix && rows_compatible {
-                            let row_count = y_max - y_min + 1;
-                            let first_row_len = first_row.len() as i32;
-                            let last_row_len = last_row.len() as i32;
-                            let vx_lms = (0..3) // for each of L, m, s
-                                .map(|i| vx[0] * old_basis.vx[i] + vx[1] * old_basis.vy[i])
-                                .collect::<Vec<_>>();
-                            let vy_lms = (0..3)
-                                .map(|i| vy[0] * old_basis.vx[i] + vy[1] * old_basis.vy[i])
-                                .collect::<Vec<_>>();
-                            return Some((
-                                ParallelogramSubstring::new(
-                                    row_count,
-                                    full_row_len,
-                                    first_row_len,
-                                    last_row_len,
-                                ),
-                                PitchClassLatticeBasis::from_slices(&vx_lms, &vy_lms), // put row generator first
-                            ));
-                        }
-                    }
-                    // Sort pitch_classes_transformed in lex order for traversal 2
-                    pitch_classes_transformed.sort_by(|v1, v2| {
-                        // Sort by *ascending* y values, if y values are equal sort by *descending* x values
-                        v1[1].cmp(&v2[1]).then(v1[0].cmp(&v2[0]).reverse())
-                    });
-                    'traversal2: {
-                        // Check outer rows for traversal 2
-                        // Last row must be a prefix of a row traversal
-                        let mut last_row = vec![];
-                        // Only need to check at most `full_row_len` elements from end
-                        index = pitch_classes_transformed
-                            .len()
-                            .saturating_sub(full_row_len as usize);
-                        while index < pitch_classes_transformed.len()
-                            && pitch_classes_transformed[index][1] > y_min
-                        {
-                            index += 1;
-                        }
-                        while index < pitch_classes_transformed.len() {
-                            last_row.push(pitch_classes_transformed[index].clone());
-                            index += 1;
-                        }
-                        // First x value in last_row == x_max AND
-                        // |last x value in row - first x value in row| + 1 == last_row.len()
-                        // (last_row should be sorted by *descending* x values)
-                        let last_row_is_prefix = !last_row.is_empty()
-                            && last_row[0][0] == x_max
-                            && (x_max - last_row[last_row.len() - 1][0] + 1) as usize
-                                == last_row.len();
-                        if !last_row_is_prefix {
-                            break 'traversal2;
-                        }
-                        // First row must be a suffix of a row traversal
-                        index = 0;
-                        let mut first_row = vec![];
-                        while pitch_classes_transformed[index][1] == y_max {
-                            first_row.push(pitch_classes_transformed[index].clone());
-                            index += 1;
-                        }
-                        // Last x value in first_row == x_min AND
-                        // |last x value in row - first x value in row| + 1 == first_row.len()
-                        // (first_row should be sorted by *descending* x values)
-                        let first_row_is_suffix = !first_row.is_empty()
-                            && first_row[first_row.len() - 1][0] == x_min
-                            && (first_row[0][0] - x_min + 1) as usize == first_row.len();
-
-                        // IMPORTANT: When there are no middle rows,
-                        // verify that first and last rows span the same x-range.
-                        let row_count = y_max - y_min + 1;
-                        let rows_compatible = if row_count == 2 || full_row_len == 2 {
-                            // At least one row must span the full range [x_min, x_max]
-                            (first_row.len() as i32 == full_row_len)
-                                || (last_row.len() as i32 == full_row_len)
-                        } else {
-                            true // Middle rows already enforce consistency
-                        };
-
-                        if first_row_is_suffix && rows_compatible {
-                            let row_count = y_max - y_min + 1;
-                            let first_row_len = first_row.len() as i32;
-                            let last_row_len = last_row.len() as i32;
-                            let vx_lms = (0..3)
-                                .map(|i| vx[0] * old_basis.vx[i] + vx[1] * old_basis.vy[i])
-                                .collect::<Vec<_>>();
-                            let vy_lms = (0..3)
-                                .map(|i| vy[0] * old_basis.vx[i] + vy[1] * old_basis.vy[i])
-                                .collect::<Vec<_>>();
-                            return Some((
-                                ParallelogramSubstring::new(
-                                    row_count,
-                                    full_row_len,
-                                    first_row_len,
-                                    last_row_len,
-                                ),
-                                PitchClassLatticeBasis::from_slices(&vx_lms, &vy_lms), // put row generator first
-                            ));
-                        }
-                    }
-                }
-                'traversal34: {
-                    // Sort pitch_classes_transformed in lex order for traversal 3
-                    pitch_classes_transformed.sort_by(|v1, v2| {
-                        // Sort by *ascending* x values, if x values are equal sort by *ascending* y values
-                        v1[0].cmp(&v2[0]).then(v1[1].cmp(&v2[1]))
-                    });
-                    let mut index = 0; // index into pitch_classes_transformed
-                    // Check if middle rows are fully occupied; if not break out of block early
-                    let xs_middle = (x_min + 1)..=(x_max - 1);
-                    let full_row_len = y_max - y_min + 1; // Required length of each middle row
-                    for x in xs_middle {
-                        let mut row_counter = 0; // Count pitches with this y value
-                        while pitch_classes_transformed[index][0] < x {
-                            index += 1;
-                        }
-                        while pitch_classes_transformed[index][0] == x {
-                            row_counter += 1;
-                            index += 1;
-                        }
-                        if row_counter != full_row_len {
-                            break 'traversal34;
-                        }
-                    }
-                    'traversal3: {
-                        // Check outer rows for traversal 3
-                        // Last row must be a prefix of a row traversal
-                        let mut last_row = vec![];
-                        while pitch_classes_transformed[index][0] < x_max {
-                            index += 1;
-                        }
-                        while index < pitch_classes_transformed.len() {
-                            last_row.push(pitch_classes_transformed[index].clone());
-                            index += 1;
-                        }
-                        // First y value in last_row == y_min AND
-                        // |last y value in row - first y value in row| + 1 == last_row.len()
-                        // (last_row should be sorted by *ascending* y values)
-                        let last_row_is_prefix = !last_row.is_empty()
-                            && last_row[0][1] == y_min
-                            && (last_row[last_row.len() - 1][1] - y_min + 1) as usize
-                                == last_row.len();
-                        if !last_row_is_prefix {
-                            break 'traversal3;
-                        }
-                        // First row must be a suffix of a row traversal
-                        index = 0;
-                        let mut first_row = vec![];
-                        while pitch_classes_transformed[index][0] == x_min {
-                            first_row.push(pitch_classes_transformed[index].clone());
-                            index += 1;
-                        }
-                        // Last y value in first_row == y_max AND
-                        // |last y value in row - first y value in row| + 1 == first_row.len()
-                        // (first_row should be sorted by *ascending* y values)
-                        let first_row_is_suffix = !first_row.is_empty()
-                            && first_row[first_row.len() - 1][1] == y_max
-                            && (y_max - first_row[0][1] + 1) as usize == first_row.len();
-
-                        // IMPORTANT: When there are no middle rows,
-                        // verify that first and last rows span the same y-range.
-                        // Otherwise we might accept two rows with overlapping but different ranges.
-                        let row_count = x_max - x_min + 1;
-                        let rows_compatible = if row_count == 2 || full_row_len == 2 {
-                            // At least one row must span the full range [y_min, y_max]
-                            (first_row.len() as i32 == full_row_len)
-                                || (last_row.len() as i32 == full_row_len)
-                        } else {
-                            true // Middle rows already enforce consistency
-                        };
-
-                        if first_row_is_suffix && rows_compatible {
-                            let row_count = x_max - x_min + 1;
-                            let first_row_len = first_row.len() as i32;
-                            let last_row_len = last_row.len() as i32;
-                            let vx_lms = (0..3)
-                                .map(|i| vx[0] * old_basis.vx[i] + vx[1] * old_basis.vy[i])
-                                .collect::<Vec<_>>();
-                            let vy_lms = (0..3)
-                                .map(|i| vy[0] * old_basis.vx[i] + vy[1] * old_basis.vy[i])
-                                .collect::<Vec<_>>();
-                            return Some((
-                                ParallelogramSubstring::new(
-                                    row_count,
-                                    full_row_len,
-                                    first_row_len,
-                                    last_row_len,
-                                ),
-                                PitchClassLatticeBasis::from_slices(&vy_lms, &vx_lms), // put row generator first
-                            ));
-                        }
-                    }
-                    // Sort pitch_classes_transformed in lex order for traversal 4
-                    pitch_classes_transformed.sort_by(|v1, v2| {
-                        // Sort by *ascending* x values, if y values are equal sort by *descending* y values
-                        v1[0].cmp(&v2[0]).then(v1[1].cmp(&v2[1]).reverse())
-                    });
-                    'traversal4: {
-                        // Check outer rows for traversal 4
-                        // Last row must be a prefix of a row traversal
-                        let mut last_row = vec![];
-                        // Only need to check at most `full_row_len` elements from end
-                        index = pitch_classes_transformed
-                            .len()
-                            .saturating_sub(full_row_len as usize);
-                        while index < pitch_classes_transformed.len()
-                            && pitch_classes_transformed[index][0] > x_min
-                        {
-                            index += 1;
-                        }
-                        while index < pitch_classes_transformed.len() {
-                            last_row.push(pitch_classes_transformed[index].clone());
-                            index += 1;
-                        }
-                        // First y value in last_row == y_max AND
-                        // |last y value in row - first y value in row| + 1 == last_row.len()
-                        // (last_row should be sorted by descending y values)
-                        let last_row_is_prefix = !last_row.is_empty()
-                            && last_row[0][1] == y_max
-                            && (y_max - last_row[last_row.len() - 1][1] + 1) as usize
-                                == last_row.len();
-                        if !last_row_is_prefix {
-                            break 'traversal4;
-                        }
-                        // First row must be a suffix of a row traversal
-                        index = 0;
-                        let mut first_row = vec![];
-                        while pitch_classes_transformed[index][1] == y_max {
-                            first_row.push(pitch_classes_transformed[index].clone());
-                            index += 1;
-                        }
-                        // Last y value in first_row == y_min AND
-                        // |last y value in row - first y value in row| + 1 == first_row.len()
-                        // (last_row should be sorted by *descending* x values)
-                        let first_row_is_suffix = !first_row.is_empty()
-                            && first_row[first_row.len() - 1][1] == y_min
-                            && (first_row[0][1] - y_min + 1) as usize == first_row.len();
-
-                        // IMPORTANT: When there are no middle rows,
-                        // verify that first and last rows span the same y-range.
-                        let row_count = x_max - x_min + 1;
-                        let rows_compatible = if row_count == 2 || full_row_len == 2 {
-                            // At least one row must span the full range [y_min, y_max]
-                            (first_row.len() as i32 == full_row_len)
-                                || (last_row.len() as i32 == full_row_len)
-                        } else {
-                            true // Middle rows already enforce consistency
-                        };
-
-                        if first_row_is_suffix && rows_compatible {
-                            let row_count = x_max - x_min + 1;
-                            let first_row_len = first_row.len() as i32;
-                            let last_row_len = last_row.len() as i32;
-                            let vx_lms = (0..3)
-                                .map(|i| vx[0] * old_basis.vx[i] + vx[1] * old_basis.vy[i])
-                                .collect::<Vec<_>>();
-                            let vy_lms = (0..3)
-                                .map(|i| vy[0] * old_basis.vx[i] + vy[1] * old_basis.vy[i])
-                                .collect::<Vec<_>>();
-                            return Some((
-                                ParallelogramSubstring::new(
-                                    row_count,
-                                    full_row_len,
-                                    first_row_len,
-                                    last_row_len,
-                                ),
-                                PitchClassLatticeBasis::from_slices(&vy_lms, &vx_lms), // put row generator first
-                            ));
-                        }
-                    }
-                }
+                let vy_lms = (0..3)
+                    .map(|i| vy[0] * old_basis.vx[i] + vy[1] * old_basis.vy[i])
+                    .collect::<Vec<_>>();
+                return Some((
+                    ParallelogramSubstring::new(
+                        row_count,
+                        full_row_len,
+                        first_row_len,
+                        last_row_len,
+                    ),
+                    PitchClassLatticeBasis::from_slices(&vx_lms, &vy_lms), // put row generator first
+                ));
+            }
+        }
+        // Sort pitch_classes_transformed in lex order for traversal 2
+        pitch_classes_transformed.sort_by(|v1, v2| {
+            // Sort by *ascending* y values, if y values are equal sort by *descending* x values
+            v1[1].cmp(&v2[1]).then(v1[0].cmp(&v2[0]).reverse())
+        });
+        'traversal2: {
+            // Check outer rows for traversal 2
+            // Last row must be a prefix of a row traversal
+            let mut last_row = vec![];
+            // Only need to check at most `full_row_len` elements from end
+            index = pitch_classes_transformed
+                .len()
+                .saturating_sub(full_row_len as usize);
+            while index < pitch_classes_transformed.len()
+                && pitch_classes_transformed[index][1] > y_min
+            {
+                index += 1;
+            }
+            while index < pitch_classes_transformed.len() {
+                last_row.push(pitch_classes_transformed[index].clone());
+                index += 1;
+            }
+            // First x value in last_row == x_max AND
+            // |last x value in row - first x value in row| + 1 == last_row.len()
+            // (last_row should be sorted by *descending* x values)
+            let last_row_is_prefix = !last_row.is_empty()
+                && last_row[0][0] == x_max
+                && (x_max - last_row[last_row.len() - 1][0] + 1) as usize == last_row.len();
+            if !last_row_is_prefix {
+                break 'traversal2;
+            }
+            // First row must be a suffix of a row traversal
+            index = 0;
+            let mut first_row = vec![];
+            while pitch_classes_transformed[index][1] == y_max {
+                first_row.push(pitch_classes_transformed[index].clone());
+                index += 1;
+            }
+            // Last x value in first_row == x_min AND
+            // |last x value in row - first x value in row| + 1 == first_row.len()
+            // (first_row should be sorted by *descending* x values)
+            let first_row_is_suffix = !first_row.is_empty()
+                && first_row[first_row.len() - 1][0] == x_min
+                && (first_row[0][0] - x_min + 1) as usize == first_row.len();
+
+            // IMPORTANT: When there are no middle rows,
+            // verify that first and last rows span the same x-range.
+            let row_count = y_max - y_min + 1;
+            let rows_compatible = if row_count == 2 || full_row_len == 2 {
+                // At least one row must span the full range [x_min, x_max]
+                (first_row.len() as i32 == full_row_len) || (last_row.len() as i32 == full_row_len)
+            } else {
+                true // Middle rows already enforce consistency
+            };
+
+            if first_row_is_suffix && rows_compatible {
+                let row_count = y_max - y_min + 1;
+                let first_row_len = first_row.len() as i32;
+                let last_row_len = last_row.len() as i32;
+                let vx_lms = (0..3)
+                    .map(|i| vx[0] * old_basis.vx[i] + vx[1] * old_basis.vy[i])
+                    .collect::<Vec<_>>();
+                let vy_lms = (0..3)
+                    .map(|i| vy[0] * old_basis.vx[i] + vy[1] * old_basis.vy[i])
+                    .collect::<Vec<_>>();
+                return Some((
+                    ParallelogramSubstring::new(
+                        row_count,
+                        full_row_len,
+                        first_row_len,
+                        last_row_len,
+                    ),
+                    PitchClassLatticeBasis::from_slices(&vx_lms, &vy_lms), // put row generator first
+                ));
+            }
+        }
+    }
+    'traversal34: {
+        // Sort pitch_classes_transformed in lex order for traversal 3
+        pitch_classes_transformed.sort_by(|v1, v2| {
+            // Sort by *ascending* x values, if x values are equal sort by *ascending* y values
+            v1[0].cmp(&v2[0]).then(v1[1].cmp(&v2[1]))
+        });
+        let mut index = 0; // index into pitch_classes_transformed
+        // Check if middle rows are fully occupied; if not break out of block early
+        let xs_middle = (x_min + 1)..=(x_max - 1);
+        let full_row_len = y_max - y_min + 1; // Required length of each middle row
+        for x in xs_middle {
+            let mut row_counter = 0; // Count pitches with this y value
+            while pitch_classes_transformed[index][0] < x {
+                index += 1;
+            }
+            while pitch_classes_transformed[index][0] == x {
+                row_counter += 1;
+                index += 1;
+            }
+            if row_counter != full_row_len {
+                break 'traversal34;
+            }
+        }
+        'traversal3: {
+            // Check outer rows for traversal 3
+            // Last row must be a prefix of a row traversal
+            let mut last_row = vec![];
+            while pitch_classes_transformed[index][0] < x_max {
+                index += 1;
+            }
+            while index < pitch_classes_transformed.len() {
+                last_row.push(pitch_classes_transformed[index].clone());
+                index += 1;
+            }
+            // First y value in last_row == y_min AND
+            // |last y value in row - first y value in row| + 1 == last_row.len()
+            // (last_row should be sorted by *ascending* y values)
+            let last_row_is_prefix = !last_row.is_empty()
+                && last_row[0][1] == y_min
+                && (last_row[last_row.len() - 1][1] - y_min + 1) as usize == last_row.len();
+            if !last_row_is_prefix {
+                break 'traversal3;
+            }
+            // First row must be a suffix of a row traversal
+            index = 0;
+            let mut first_row = vec![];
+            while pitch_classes_transformed[index][0] == x_min {
+                first_row.push(pitch_classes_transformed[index].clone());
+                index += 1;
+            }
+            // Last y value in first_row == y_max AND
+            // |last y value in row - first y value in row| + 1 == first_row.len()
+            // (first_row should be sorted by *ascending* y values)
+            let first_row_is_suffix = !first_row.is_empty()
+                && first_row[first_row.len() - 1][1] == y_max
+                && (y_max - first_row[0][1] + 1) as usize == first_row.len();
+
+            // IMPORTANT: When there are no middle rows,
+            // verify that first and last rows span the same y-range.
+            // Otherwise we might accept two rows with overlapping but different ranges.
+            let row_count = x_max - x_min + 1;
+            let rows_compatible = if row_count == 2 || full_row_len == 2 {
+                // At least one row must span the full range [y_min, y_max]
+                (first_row.len() as i32 == full_row_len) || (last_row.len() as i32 == full_row_len)
+            } else {
+                true // Middle rows already enforce consistency
+            };
+
+            if first_row_is_suffix && rows_compatible {
+                let row_count = x_max - x_min + 1;
+                let first_row_len = first_row.len() as i32;
+                let last_row_len = last_row.len() as i32;
+                let vx_lms = (0..3)
+                    .map(|i| vx[0] * old_basis.vx[i] + vx[1] * old_basis.vy[i])
+                    .collect::<Vec<_>>();
+                let vy_lms = (0..3)
+                    .map(|i| vy[0] * old_basis.vx[i] + vy[1] * old_basis.vy[i])
+                    .collect::<Vec<_>>();
+                return Some((
+                    ParallelogramSubstring::new(
+                        row_count,
+                        full_row_len,
+                        first_row_len,
+                        last_row_len,
+                    ),
+                    PitchClassLatticeBasis::from_slices(&vy_lms, &vx_lms), // put row generator first
+                ));
+            }
+        }
+        // Sort pitch_classes_transformed in lex order for traversal 4
+        pitch_classes_transformed.sort_by(|v1, v2| {
+            // Sort by *ascending* x values, if y values are equal sort by *descending* y values
+            v1[0].cmp(&v2[0]).then(v1[1].cmp(&v2[1]).reverse())
+        });
+        'traversal4: {
+            // Check outer rows for traversal 4
+            // Last row must be a prefix of a row traversal
+            let mut last_row = vec![];
+            // Only need to check at most `full_row_len` elements from end
+            index = pitch_classes_transformed
+                .len()
+                .saturating_sub(full_row_len as usize);
+            while index < pitch_classes_transformed.len()
+                && pitch_classes_transformed[index][0] > x_min
+            {
+                index += 1;
+            }
+            while index < pitch_classes_transformed.len() {
+                last_row.push(pitch_classes_transformed[index].clone());
+                index += 1;
+            }
+            // First y value in last_row == y_max AND
+            // |last y value in row - first y value in row| + 1 == last_row.len()
+            // (last_row should be sorted by descending y values)
+            let last_row_is_prefix = !last_row.is_empty()
+                && last_row[0][1] == y_max
+                && (y_max - last_row[last_row.len() - 1][1] + 1) as usize == last_row.len();
+            if !last_row_is_prefix {
+                break 'traversal4;
+            }
+            // First row must be a suffix of a row traversal
+            index = 0;
+            let mut first_row = vec![];
+            while pitch_classes_transformed[index][1] == y_max {
+                first_row.push(pitch_classes_transformed[index].clone());
+                index += 1;
+            }
+            // Last y value in first_row == y_min AND
+            // |last y value in row - first y value in row| + 1 == first_row.len()
+            // (last_row should be sorted by *descending* x values)
+            let first_row_is_suffix = !first_row.is_empty()
+                && first_row[first_row.len() - 1][1] == y_min
+                && (first_row[0][1] - y_min + 1) as usize == first_row.len();
+
+            // IMPORTANT: When there are no middle rows,
+            // verify that first and last rows span the same y-range.
+            let row_count = x_max - x_min + 1;
+            let rows_compatible = if row_count == 2 || full_row_len == 2 {
+                // At least one row must span the full range [y_min, y_max]
+                (first_row.len() as i32 == full_row_len) || (last_row.len() as i32 == full_row_len)
+            } else {
+                true // Middle rows already enforce consistency
+            };
+
+            if first_row_is_suffix && rows_compatible {
+                let row_count = x_max - x_min + 1;
+                let first_row_len = first_row.len() as i32;
+                let last_row_len = last_row.len() as i32;
+                let vx_lms = (0..3)
+                    .map(|i| vx[0] * old_basis.vx[i] + vx[1] * old_basis.vy[i])
+                    .collect::<Vec<_>>();
+                let vy_lms = (0..3)
+                    .map(|i| vy[0] * old_basis.vx[i] + vy[1] * old_basis.vy[i])
+                    .collect::<Vec<_>>();
+                return Some((
+                    ParallelogramSubstring::new(
+                        row_count,
+                        full_row_len,
+                        first_row_len,
+                        last_row_len,
+                    ),
+                    PitchClassLatticeBasis::from_slices(&vy_lms, &vx_lms), // put row generator first
+                ));
             }
         }
     }
     None
 }
 
+/// Every basis witnessing that `pitch_classes` form a parallelogram substring,
+/// as [`parallelogram_substring_info`] finds the first of, with each pair of vectors once up to sign.
+pub fn parallelogram_bases(
+    pitch_classes: &[&[i32]],
+    old_basis: &PitchClassLatticeBasis,
+) -> Vec<(ParallelogramSubstring, PitchClassLatticeBasis)> {
+    let mut differences: Vec<[i32; 2]> = vec![];
+    for (i, p) in pitch_classes.iter().enumerate() {
+        for q in &pitch_classes[i + 1..] {
+            differences.push([q[0] - p[0], q[1] - p[1]]);
+        }
+    }
+    differences.sort();
+    differences.dedup();
+    let mut bases: Vec<(ParallelogramSubstring, PitchClassLatticeBasis)> = vec![];
+    for (i, vx) in differences.iter().enumerate() {
+        for vy in &differences[i + 1..] {
+            if (vx[0] * vy[1] - vx[1] * vy[0]).abs() == 1
+                && let Some((shape, basis)) =
+                    parallelogram_witness(pitch_classes, vx, vy, old_basis)
+                && !bases
+                    .iter()
+                    .any(|(_, known)| same_up_to_sign(known, &basis))
+            {
+                bases.push((shape, basis));
+            }
+        }
+    }
+    bases
+}
+
+/// Whether `a` and `b` are made of the same vectors up to sign, in either order.
+fn same_up_to_sign(a: &PitchClassLatticeBasis, b: &PitchClassLatticeBasis) -> bool {
+    let up_to_sign = |u: &[i32], v: &[i32]| u == v || u.iter().zip(v).all(|(x, y)| *x == -y);
+    (up_to_sign(&a.vx, &b.vx) && up_to_sign(&a.vy, &b.vy))
+        || (up_to_sign(&a.vx, &b.vy) && up_to_sign(&a.vy, &b.vx))
+}
+
+/// The inner product of `u` and `v` projected orthogonally to `equave`, times `equave · equave`.
+fn pitch_class_inner(u: &[i32], v: &[i32], equave: &[i32]) -> i64 {
+    let dot = |a: &[i32], b: &[i32]| (0..3).map(|i| a[i] as i64 * b[i] as i64).sum::<i64>();
+    dot(u, v) * dot(equave, equave) - dot(u, equave) * dot(v, equave)
+}
+
+/// The Lagrange-Gauss reduction of `basis`, a basis of the same pitch class lattice with the
+/// shortest possible vectors, with lengths measured orthogonally to the equave `step_sig`.
+///
+/// # Examples
+///
+/// ```
+/// use ternary::lattice::{PitchClassLatticeBasis, reduce_basis};
+///
+/// // L and LLm + 3L in LLmLLms reduce to L and LLm
+/// let basis = PitchClassLatticeBasis::from_slices(&[1, 0, 0], &[5, 1, 0]);
+/// let reduced = reduce_basis(&basis, &[4, 2, 1]);
+/// assert_eq!(reduced, PitchClassLatticeBasis::from_slices(&[1, 0, 0], &[2, 1, 0]));
+/// ```
+pub fn reduce_basis(basis: &PitchClassLatticeBasis, step_sig: &[i32]) -> PitchClassLatticeBasis {
+    let norm = |v: &[i32]| pitch_class_inner(v, v, step_sig);
+    let (mut a, mut b) = (basis.vx.clone(), basis.vy.clone());
+    loop {
+        if norm(&a) > norm(&b) {
+            std::mem::swap(&mut a, &mut b);
+        }
+        // the nearest integer to <a, b> / <a, a>
+        let mu = (2 * pitch_class_inner(&a, &b, step_sig) + norm(&a)).div_euclid(2 * norm(&a));
+        if mu == 0 {
+            return PitchClassLatticeBasis::from_slices(&a, &b);
+        }
+        (0..3).for_each(|i| b[i] -= mu as i32 * a[i]);
+    }
+}
+
+/// The smaller of the interval `v` and its inversion, reduced modulo the equave `step_sig`
+/// to have between 0 and `step_sig[i]` steps of each size `i`, if there is such an interval.
+fn interval_class(v: &[i32], step_sig: &[i32]) -> Vec<i32> {
+    if step_sig.contains(&0) {
+        return v.to_vec();
+    }
+    [1, -1]
+        .iter()
+        .filter_map(|sign| {
+            // the fewest equaves to add to make every step count nonnegative
+            let k = (0..3)
+                .map(|i| (sign * v[i]).div_euclid(step_sig[i]))
+                .min()
+                .unwrap_or(0);
+            let w: Vec<i32> = (0..3).map(|i| sign * v[i] - k * step_sig[i]).collect();
+            (0..3).all(|i| w[i] <= step_sig[i]).then_some(w)
+        })
+        .min_by_key(|w| w.iter().sum::<i32>())
+        .unwrap_or_else(|| v.to_vec())
+}
+
+/// The accepted values of a [`BasisScore`] as a string.
+pub const BASIS_SCORES: &[&str] = &["fifth", "shortest", "near_interval", "guide_frames"];
+
+/// How to score the lattice bases of a scale in [`ranked_lattice_bases`], lower scores first.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum BasisScore {
+    /// 0 if a basis vector is a fifth or fourth in the patent val of the EDO with as many notes
+    /// as the scale, otherwise 1, as [`parallelogram_substring_info`] prefers
+    #[default]
+    Fifth,
+    /// the summed squared lengths of the basis vectors, measured orthogonally to the equave,
+    /// over those of the [`reduce_basis`] of the lattice, so at least 1
+    Shortest,
+    /// the distance in cents of the basis vector nearest to `interval` under `tuning`,
+    /// modulo the equave and up to inversion
+    NearInterval {
+        interval: RawJiRatio,
+        tuning: StepTuning,
+    },
+    /// the number of basis vectors that aren't a guide frame generator, up to sign and equaves
+    GuideFrames,
+}
+
+impl BasisScore {
+    /// The score named `name`, one of [`BASIS_SCORES`], where `near_interval` is the closeness
+    /// to `interval` under `tuning`. Fails with [`BadTuning::Missing`] for `near_interval`
+    /// without a tuning.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ternary::ji_ratio::RawJiRatio;
+    /// use ternary::lattice::BasisScore;
+    /// use ternary::tuning::{BadTuning, StepTuning};
+    ///
+    /// let tuning = StepTuning::from_strings(&["9/8", "28/27", "64/63"]).unwrap();
+    /// let score = BasisScore::with_name("near_interval", RawJiRatio::PYTH_5TH, Some(tuning.clone()));
+    /// assert_eq!(
+    ///     score,
+    ///     Ok(BasisScore::NearInterval { interval: RawJiRatio::PYTH_5TH, tuning })
+    /// );
+    /// assert_eq!(
+    ///     BasisScore::with_name("near_interval", RawJiRatio::PYTH_5TH, None),
+    ///     Err(BadTuning::Missing("near_interval").into())
+    /// );
+    /// ```
+    pub fn with_name(
+        name: &str,
+        interval: RawJiRatio,
+        tuning: Option<StepTuning>,
+    ) -> Result<Self, crate::Error> {
+        match name {
+            "fifth" => Ok(Self::Fifth),
+            "shortest" => Ok(Self::Shortest),
+            "near_interval" => match tuning {
+                Some(tuning) => Ok(Self::NearInterval { interval, tuning }),
+                None => Err(BadTuning::Missing("near_interval").into()),
+            },
+            "guide_frames" => Ok(Self::GuideFrames),
+            _ => Err(crate::Error::InvalidOption {
+                value: name.to_string(),
+                expected: BASIS_SCORES,
+            }),
+        }
+    }
+}
+
+impl std::str::FromStr for BasisScore {
+    type Err = crate::Error;
+
+    /// Parse one of [`BASIS_SCORES`] without a tuning, as in [`BasisScore::with_name`],
+    /// so `near_interval` fails.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::with_name(s, RawJiRatio::PYTH_5TH, None)
+    }
+}
+
+/// A lattice basis of a scale witnessing the parallelogram substring property, with its score.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScoredBasis {
+    /// the basis in scale step coordinates, row generator first
+    pub basis: PitchClassLatticeBasis,
+    /// the shape of the traversal the pitch classes form
+    pub shape: ParallelogramSubstring,
+    /// lattice coordinates of each pitch class in this basis
+    pub coordinates: Vec<Vec<i32>>,
+    /// the score, lower is better
+    pub score: f64,
+}
+
+/// Every lattice basis of `query` witnessing the parallelogram substring property,
+/// sorted by `score`. Empty if the scale has no 2D lattice or forms no parallelogram substring.
+/// Fails if the tuning of a [`BasisScore::NearInterval`] has no step size for some letter of `query`.
+///
+/// # Examples
+///
+/// ```
+/// use ternary::lattice::{BasisScore, ranked_lattice_bases};
+///
+/// let diasem = [0, 1, 0, 2, 0, 1, 0, 2, 0];
+/// let bases = ranked_lattice_bases(&diasem, &BasisScore::Shortest).unwrap();
+/// assert!(bases.len() > 1);
+/// assert!(bases.windows(2).all(|pair| pair[0].score <= pair[1].score));
+/// assert_eq!(bases[0].coordinates.len(), 9);
+/// ```
+pub fn ranked_lattice_bases(
+    query: &[usize],
+    score: &BasisScore,
+) -> Result<Vec<ScoredBasis>, BadTuning> {
+    if let BasisScore::NearInterval { tuning, .. } = score {
+        tuning.check_word(query)?;
+    }
+    let Some((lattice, old_basis)) = try_pitch_class_lattice(query) else {
+        return Ok(vec![]);
+    };
+    let sig: Vec<i32> = word_to_sig(query).iter().map(|&x| x as i32).collect();
+    let reduced = reduce_basis(&old_basis, &sig);
+    let reduced_norm = pitch_class_inner(reduced.vx(), reduced.vx(), &sig)
+        + pitch_class_inner(reduced.vy(), reduced.vy(), &sig);
+    let step_cents = match score {
+        BasisScore::NearInterval { tuning, .. } => tuning.step_cents(),
+        _ => vec![],
+    };
+    let generators: Vec<Vec<i32>> = match score {
+        BasisScore::GuideFrames => guide_frames(query)
+            .iter()
+            .flat_map(|gf| guide_frame_to_result(gf).gs)
+            .map(|g| g.into_iter().map(|x| x as i32).collect())
+            .collect(),
+        _ => vec![],
+    };
+    let score_vector = |v: &[i32]| -> f64 {
+        match score {
+            BasisScore::Fifth => {
+                let scale_size = query.len() as i32;
+                let fifth =
+                    direct_approx(RawJiRatio::PYTH_5TH, query.len() as f64, RawJiRatio::OCTAVE);
+                let taxicab_len: i32 = v.iter().map(|x| x.abs()).sum();
+                let steps = taxicab_len % scale_size;
+                if steps == fifth || steps == scale_size - fifth {
+                    0.0
+                } else {
+                    1.0
+                }
+            }
+            BasisScore::Shortest => pitch_class_inner(v, v, &sig) as f64 / reduced_norm as f64,
+            BasisScore::NearInterval { interval, .. } => {
+                let size = |v: &[i32]| (0..3).map(|i| v[i] as f64 * step_cents[i]).sum::<f64>();
+                let (cents, equave) = (interval.cents(), size(&sig));
+                let distance = |c: f64| {
+                    let d = (c - cents).rem_euclid(equave);
+                    d.min(equave - d)
+                };
+                distance(size(v)).min(distance(-size(v)))
+            }
+            BasisScore::GuideFrames => {
+                let is_generator = generators.iter().any(|g| {
+                    [1, -1].iter().any(|sign| {
+                        let d: Vec<i32> = (0..3).map(|i| v[i] - sign * g[i]).collect();
+                        // d is a multiple of the equave
+                        (0..3).all(|i| d[i] * sig[(i + 1) % 3] == d[(i + 1) % 3] * sig[i])
+                    })
+                });
+                if is_generator { 0.0 } else { 1.0 }
+            }
+        }
+    };
+    let pitch_class_refs: Vec<&[i32]> = lattice.iter().map(|v| v.as_slice()).collect();
+    let mut bases: Vec<ScoredBasis> = parallelogram_bases(&pitch_class_refs, &old_basis)
+        .into_iter()
+        .map(|(shape, basis)| {
+            let basis = PitchClassLatticeBasis::from_slices(
+                &interval_class(basis.vx(), &sig),
+                &interval_class(basis.vy(), &sig),
+            );
+            let (vx, vy) = (score_vector(basis.vx()), score_vector(basis.vy()));
+            let score = match score {
+                BasisScore::Fifth | BasisScore::NearInterval { .. } => vx.min(vy),
+                BasisScore::Shortest | BasisScore::GuideFrames => vx + vy,
+            };
+            let coordinates = lattice_coordinates(query, &basis);
+            ScoredBasis {
+                basis,
+                shape,
+                coordinates,
+                score,
+            }
+        })
+        .collect();
+    bases.sort_by(|a, b| a.score.total_cmp(&b.score));
+    Ok(bases)
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::ji_ratio::RawJiRatio;
+    use crate::tuning::{BadTuning, StepTuning};
     use crate::{
         // comb::partitions_exact_part_count,
         lattice::{
             /*ParallelogramSubstring, PitchClassLatticeBasis,*/
             // ParallelogramSubstring, get_unimodular_basis, parallelogram_info,
-            parallelogram_substring_info, try_pitch_class_lattice,
+            BasisScore, NoLatticeReason, Projection, fallback_lattice, parallelogram_bases,
+            parallelogram_substring_info, ranked_lattice_bases, reduce_basis, same_up_to_sign,
+            try_pitch_class_lattice,
         },
     };
 
@@ -969,4 +1489,157 @@ mod tests {
             .is_none()
         );
     }
+
+    #[test]
+    fn test_fallback_lattice() {
+        let mut reasons = vec![];
+        for sig in [[4, 2, 2], [3, 2, 2], [5, 3, 1], [5, 2, 3], [3, 3, 3]] {
+            for word in crate::comb::necklaces_fixed_content(&sig) {
+                let Some(lattice) = fallback_lattice(&word) else {
+                    assert!(try_pitch_class_lattice(&word).is_some());
+                    continue;
+                };
+                assert!(try_pitch_class_lattice(&word).is_none());
+                let [p, v, w] = lattice.basis;
+                assert_eq!(crate::matrix::det3(&p, &v, &w).abs(), 1);
+                let periods = match lattice.projection {
+                    Projection::Plane => 1,
+                    Projection::Layers { count } => count,
+                };
+                assert_eq!(p.map(|x| x * periods), sig.map(|x| x as i32));
+                assert_eq!(lattice.coordinates.last(), Some(&[0, 0, 0]));
+                for (steps, [layer, x, y]) in lattice.steps.iter().zip(&lattice.coordinates) {
+                    assert!((0..periods).contains(layer));
+                    // steps = k p + x v + y w with k = layer modulo the number of periods
+                    let rest: [i32; 3] = std::array::from_fn(|i| steps[i] - x * v[i] - y * w[i]);
+                    let k = rest[0] / p[0];
+                    assert_eq!(rest, p.map(|x| k * x));
+                    assert_eq!(k.rem_euclid(periods), *layer);
+                }
+                reasons.push(lattice.reason);
+            }
+        }
+        assert!(reasons.contains(&NoLatticeReason::Torsion { periods: 2 }));
+        assert!(reasons.contains(&NoLatticeReason::Torsion { periods: 3 }));
+
+        let no_guide_frame = crate::string_to_numbers("LLmLsLmLss");
+        let lattice = fallback_lattice(&no_guide_frame).unwrap();
+        assert_eq!(lattice.reason, NoLatticeReason::NoGuideFrame);
+        assert_eq!(lattice.projection, Projection::Plane);
+        let no_pair = crate::string_to_numbers("LLLsLLmmm");
+        let lattice = fallback_lattice(&no_pair).unwrap();
+        assert_eq!(lattice.reason, NoLatticeReason::NoUnimodularPair);
+        assert!(lattice.coordinates.iter().all(|&[layer, _, _]| layer == 0));
+        assert_eq!(fallback_lattice(&[]), None);
+    }
+
+    #[test]
+    fn test_ranked_lattice_bases() {
+        for word in [
+            "LmLsLmLsL",
+            "LLmLLms",
+            "LLsmLsLmLs",
+            "LLLLsLLmLLs",
+            "LLLLsLmLLsLLLms",
+        ] {
+            let word = crate::string_to_numbers(word);
+            let sig: Vec<i32> = crate::word_to_sig(&word)
+                .iter()
+                .map(|&x| x as i32)
+                .collect();
+            let (lattice, old_basis) = try_pitch_class_lattice(&word).unwrap();
+            let lattice = crate::helpers::slicify_each(&lattice);
+            let bases = parallelogram_bases(&lattice, &old_basis);
+            // the basis `parallelogram_substring_info` picks is one of them
+            match parallelogram_substring_info(&lattice, &old_basis) {
+                Some((_, first)) => {
+                    assert!(
+                        bases
+                            .iter()
+                            .any(|(_, basis)| same_up_to_sign(basis, &first))
+                    )
+                }
+                None => assert!(bases.is_empty()),
+            }
+            for (_, basis) in &bases {
+                assert_eq!(crate::matrix::det3(&sig, basis.vx(), basis.vy()).abs(), 1);
+            }
+
+            // no basis is shorter than the reduced one, which scores 1 if it is among them
+            let reduced = reduce_basis(&old_basis, &sig);
+            let shortest = ranked_lattice_bases(&word, &BasisScore::Shortest).unwrap();
+            assert_eq!(shortest.len(), bases.len());
+            if let Some(best) = shortest.first() {
+                assert!(best.score >= 1.0 - 1e-9);
+                if bases
+                    .iter()
+                    .any(|(_, basis)| same_up_to_sign(basis, &reduced))
+                {
+                    assert!(best.score <= 1.0 + 1e-9);
+                }
+            }
+            assert!(
+                shortest
+                    .windows(2)
+                    .all(|pair| pair[0].score <= pair[1].score)
+            );
+            for scored in &shortest {
+                let (vx, vy) = (scored.basis.vx(), scored.basis.vy());
+                let mut steps = [0; 3];
+                for (&step, xy) in word.iter().zip(&scored.coordinates) {
+                    steps[step] += 1;
+                    // the pitch is x vx + y vy up to equaves
+                    let rest: Vec<i32> = (0..3)
+                        .map(|i| steps[i] - xy[0] * vx[i] - xy[1] * vy[i])
+                        .collect();
+                    assert!(
+                        (0..3).all(|i| rest[i] * sig[(i + 1) % 3] == rest[(i + 1) % 3] * sig[i])
+                    );
+                }
+            }
+        }
+
+        // diasem tuned with 9/8, 28/27 and 64/63 has the fifth 3L + m + s
+        let diasem = crate::string_to_numbers("LmLsLmLsL");
+        let tuning = StepTuning::from_strings(&["9/8", "28/27", "64/63"]).unwrap();
+        let near_fifth = ranked_lattice_bases(
+            &diasem,
+            &BasisScore::NearInterval {
+                interval: RawJiRatio::PYTH_5TH,
+                tuning: tuning.clone(),
+            },
+        )
+        .unwrap();
+        assert!(near_fifth[0].score < 1e-6);
+        // and 7/4 as 4L + 2m + s
+        let near_seventh = ranked_lattice_bases(
+            &diasem,
+            &BasisScore::NearInterval {
+                interval: RawJiRatio::try_new(7, 4).unwrap(),
+                tuning,
+            },
+        )
+        .unwrap();
+        assert!(near_seventh[0].score < 1e-6);
+        let guide_frames = ranked_lattice_bases(&diasem, &BasisScore::GuideFrames).unwrap();
+        assert_eq!(guide_frames[0].score, 0.0);
+        assert_eq!(
+            "shortest".parse::<BasisScore>().unwrap(),
+            BasisScore::Shortest
+        );
+        assert!("fourth".parse::<BasisScore>().is_err());
+        assert!("near_interval".parse::<BasisScore>().is_err());
+        // s isn't tuned as 0¢
+        let two_steps = BasisScore::NearInterval {
+            interval: RawJiRatio::PYTH_5TH,
+            tuning: StepTuning::from_strings(&["9/8", "28/27"]).unwrap(),
+        };
+        assert_eq!(
+            ranked_lattice_bases(&diasem, &two_steps),
+            Err(BadTuning::TooFewSteps {
+                steps: 2,
+                letters: 3
+            })
+        );
+    }
 }
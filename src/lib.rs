@@ -97,11 +97,40 @@
 //! - [`guide`]: Guided Generator Sequences
 //! - [`comb`]: Necklace enumeration
 //! - [`lattice`]: Pitch class lattice visualization
+//! - [`tuning`]: Step tunings of a scale word (JI, ED or cents)
+//! - [`error`]: The crate-wide error type [`Error`], with conversions from every module error
+//! - [`chord`]: Chord census and harmonic-series identities
+//! - [`bridge`]: Linking ED tunings to JI tunings via vals and tempered commas
+//! - [`temperament`]: Regular temperament mappings, comma bases and subgroups
+//! - [`optimal`]: Optimal tunings (TE, CTE, POTE, minimax) of temperaments
+//! - [`scala`]: Scala `.scl` export and import
+//! - [`sonicweave`]: SonicWeave source code for Scale Workshop 3
+//! - [`svg`]: Standalone SVG drawings of pitch class lattices
+//! - [`midi`]: Keyboard mappings (`.kbm`), AnaMark `.tun` files and MIDI Tuning Standard SysEx
+//! - [`progress`]: Progress callbacks, cancellation and resumable searches
+//! - [`named`]: Scales known by name, such as diasem and Zarlino, with reference JI tunings
+//! - [`cache`]: Memoized profiles and tunings, and catalogs of them that can be saved
+//!   (JSON reading and writing requires the `catalog` feature)
+//! - `python`: Python bindings (requires the `python` feature)
+//! - `ffi`: C API for embedding, with the header `include/ternary.h` (requires the `ffi` feature)
+//! - `batch`: Newline-delimited JSON batch queries with shared caches (requires the `batch` feature)
+//!
+//! The `parallel` feature runs the scale filtering of [`SigSearch`] and the slow JI solver
+//! [`ji::SlowSolver`] on all cores with [rayon](https://docs.rs/rayon) in native builds.
+//! Results are the same, in the same order, with or without it.
 
 // #![deny(warnings)]
+#[cfg(feature = "batch")]
+pub mod batch;
+pub mod bridge;
+pub mod cache;
+pub mod chord;
 pub mod comb;
 #[macro_use]
 pub mod equal;
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod guide;
 pub mod helpers;
 pub mod interval;
@@ -109,22 +138,31 @@ pub mod ji;
 pub mod ji_ratio;
 pub mod lattice;
 pub mod matrix;
+pub mod midi;
+pub mod named;
 #[macro_use]
 pub mod monzo;
 pub mod interpretations;
+pub mod optimal;
 pub mod primes;
+pub mod progress;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod scala;
+pub mod sonicweave;
+pub mod svg;
+pub mod temperament;
+pub mod tuning;
 pub mod vector;
 pub mod words;
 
+pub use error::Error;
+
 use interval::JiRatio;
-#[cfg(feature = "wasm")]
-use itertools::Itertools;
 use ji_ratio::RawJiRatio;
 #[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 use words::Chirality;
-#[cfg(feature = "wasm")]
-use words::Letter;
 use words::{chirality, is_mos_subst_one_perm};
 
 #[cfg(feature = "wasm")]
@@ -156,26 +194,84 @@ const STEP_LETTERS: [&str; 12] = [
     "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz", // >= 11
 ];
 
-use std::cmp::min;
+use std::cmp::{Ordering, min};
 use std::collections::HashSet;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 #[cfg(feature = "wasm")]
 use serde_wasm_bindgen::to_value;
 
 use guide::GuideFrame;
 use guide::guide_frames;
-#[cfg(feature = "wasm")]
 use words::maximum_variety_is;
 use words::{CountVector, least_mode, maximum_variety, monotone_lm, monotone_ms, monotone_s0};
 
+use crate::comb::NecklaceSearch;
+use crate::error::FieldError;
 use crate::lattice::get_unimodular_basis;
 use crate::monzo::Monzo;
+use crate::progress::{Cancelled, Monitor, Progress, Stage};
+
+/// The version of the serialized form of the result types, such as [`ScaleProfile`] and [`SigResult`].
+/// Bumped whenever a field is added, removed or changes meaning.
+pub const SCHEMA_VERSION: u32 = 4;
+
+/// A result stored with the [`SCHEMA_VERSION`] it was serialized under, so that saved results
+/// can be reloaded, or diffed against results from another version of the crate.
+/// The version is a `schema_version` field alongside the fields of the result.
+///
+/// ```
+/// use ternary::{SCHEMA_VERSION, ScaleProfile, Versioned, string_to_numbers, word_to_profile};
+///
+/// let profile = word_to_profile(&string_to_numbers("LmLsLmLsL"));
+/// let json = serde_json::to_string(&Versioned::new(profile.clone())).unwrap();
+/// assert!(json.starts_with(&format!(r#"{{"schema_version":{SCHEMA_VERSION},"word":"LLmLsLmLs""#)));
+///
+/// let stored: Versioned<ScaleProfile> = serde_json::from_str(&json).unwrap();
+/// assert_eq!(stored.into_current(), Ok(profile));
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Versioned<T> {
+    /// the schema version the result was serialized under
+    pub schema_version: u32,
+    /// the result
+    #[serde(flatten)]
+    pub result: T,
+}
+
+impl<T> Versioned<T> {
+    /// Tag `result` with the current schema version.
+    pub fn new(result: T) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            result,
+        }
+    }
+
+    /// The result, if it was stored under the current schema version.
+    pub fn into_current(self) -> Result<T, SchemaMismatch> {
+        if self.schema_version == SCHEMA_VERSION {
+            Ok(self.result)
+        } else {
+            Err(SchemaMismatch {
+                found: self.schema_version,
+            })
+        }
+    }
+}
+
+/// Error type for stored results from a different schema version.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("result has schema version {found} but this crate reads version {SCHEMA_VERSION}")]
+pub struct SchemaMismatch {
+    /// the schema version of the stored result
+    pub found: u32,
+}
 
 /// Compute the determinant of a 3x3 matrix formed by three row vectors.
 /// Used to check if vectors form a unimodular basis (determinant ±1).
 // A representation of a GuideFrame that should be WASM-readable
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct GuideResult {
     /// Either Guided GS or multiple interleaved Guided GSes
     /// `guided_gs` generates a guided generator sequence (detempered single-period MOS) subscale.
@@ -193,69 +289,418 @@ pub struct GuideResult {
 }
 
 /// A representation of a Scale Profile. Doesn't include tunings.
-#[derive(Debug, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ScaleProfile {
     /// brightest word
-    word: String,
+    pub word: String,
     /// unimodular basis for lattice is there is one
-    lattice_basis: Option<Vec<Vec<i32>>>,
+    pub lattice_basis: Option<Vec<Vec<i32>>>,
     /// chirality
-    chirality: Chirality,
+    pub chirality: Chirality,
     /// brightest mode of reversed word
-    reversed: String,
+    pub reversed: String,
     /// lowest-complexity guide frame structure provided there is one
-    structure: Option<GuideResult>,
+    pub structure: Option<GuideResult>,
     /// whether scale is L=m monotone MOS
-    lm: bool,
+    pub lm: bool,
     /// whether scale is m=s monotone MOS
-    ms: bool,
+    pub ms: bool,
     /// whether scale is s=0 monotone MOS
-    s0: bool,
+    pub s0: bool,
     /// whether scale is a subst aL(bmcs)
-    subst_l_ms: bool,
+    pub subst_l_ms: bool,
     /// whether scale is a subst bm(aLcs)
-    subst_m_ls: bool,
+    pub subst_m_ls: bool,
     /// whether scale is a subst cs(aLbm)
-    subst_s_lm: bool,
+    pub subst_s_lm: bool,
     /// Temperament-agnostic ed join
-    ed_join: (i32, i32, i32),
+    pub ed_join: (i32, i32, i32),
     /// maximum variety of scale
-    mv: u16,
+    pub mv: u16,
+    /// number of guide frames of the scale
+    #[serde(default)]
+    pub guide_frames: usize,
+    /// name of the scale, if it has one (see [`named`]); with the octave as the equave,
+    /// except in the results of queries taking an equave
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+impl ScaleProfile {
+    /// A one-line description of the scale for file headers, such as the description line of a `.scl` file.
+    pub fn description(&self) -> String {
+        let arity = self.word.chars().collect::<HashSet<_>>().len();
+        let step_sig: String = STEP_LETTERS[min(arity, 11)]
+            .chars()
+            .map(|letter| format!("{}{letter}", self.word.matches(letter).count()))
+            .collect();
+        let chirality = match self.chirality {
+            Chirality::Left => "left-handed",
+            Chirality::Achiral => "achiral",
+            Chirality::Right => "right-handed",
+        };
+        format!(
+            "{step_sig} scale, brightest mode {}, mv {}, {chirality}",
+            self.word, self.mv
+        )
+    }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct SigResult {
-    profiles: Vec<ScaleProfile>,
-    ji_tunings: Vec<Vec<String>>,
-    ed_tunings: Vec<Vec<String>>,
+    /// profiles of the scales with the step signature that pass the filters
+    pub profiles: Vec<ScaleProfile>,
+    /// JI tunings of the step signature as step ratios
+    pub ji_tunings: Vec<Vec<String>>,
+    /// ED tunings of the step signature as step counts such as `4\22`
+    pub ed_tunings: Vec<Vec<String>>,
+    /// the val each ED tuning is best read under, in wart notation for EDOs
+    pub ed_vals: Vec<String>,
+}
+
+impl SigResult {
+    /// The profiles sorted as `query` asks, and the page of them from `query.offset`.
+    /// Profiles that tie on the sort field are ordered by word, so the order is stable across queries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ternary::ji_ratio::RawJiRatio;
+    /// use ternary::{PageQuery, ProfileSort, SigFilter, sig_to_result};
+    ///
+    /// let result = sig_to_result(&[5, 2, 2], &SigFilter::default(), RawJiRatio::OCTAVE, 53, 20.0, 250.0);
+    /// let query = PageQuery {
+    ///     offset: 80,
+    ///     limit: 10,
+    ///     sort: ProfileSort::Mv,
+    ///     descending: false,
+    /// };
+    /// let page = result.page(&query);
+    /// assert_eq!((page.offset, page.total, page.profiles.len()), (80, 84, 4));
+    /// assert!(page.profiles.windows(2).all(|w| w[0].mv <= w[1].mv));
+    /// ```
+    pub fn page(&self, query: &PageQuery) -> ProfilePage {
+        let mut sorted: Vec<&ScaleProfile> = self.profiles.iter().collect();
+        sorted.sort_by(|a, b| query.sort.compare(a, b, query.descending));
+        ProfilePage {
+            profiles: sorted
+                .into_iter()
+                .skip(query.offset)
+                .take(query.limit)
+                .cloned()
+                .collect(),
+            offset: query.offset,
+            total: self.profiles.len(),
+        }
+    }
+}
+
+/// The accepted values of a [`ProfileSort`] as a string.
+pub const PROFILE_SORTS: &[&str] = &[
+    "complexity",
+    "mv",
+    "chirality",
+    "lattice",
+    "guide_frames",
+    "word",
+];
+
+/// A field of [`ScaleProfile`] to sort by.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileSort {
+    /// guide frame complexity, with scales without a guide frame last
+    #[default]
+    Complexity,
+    /// maximum variety
+    Mv,
+    /// chirality, in the order left-handed, achiral, right-handed
+    Chirality,
+    /// whether there is a lattice basis, with scales that have one first
+    Lattice,
+    /// number of guide frames
+    GuideFrames,
+    /// brightest word
+    Word,
+}
+
+impl ProfileSort {
+    /// Compare two profiles by this field, then by word.
+    /// `descending` reverses the comparison by this field but not the tie-break by word.
+    pub fn compare(self, a: &ScaleProfile, b: &ScaleProfile, descending: bool) -> Ordering {
+        let chirality_rank = |chirality| match chirality {
+            Chirality::Left => 0,
+            Chirality::Achiral => 1,
+            Chirality::Right => 2,
+        };
+        let complexity = |profile: &ScaleProfile| {
+            profile
+                .structure
+                .as_ref()
+                .map_or(u16::MAX, |g| g.complexity)
+        };
+        let by_field = match self {
+            Self::Complexity => complexity(a).cmp(&complexity(b)),
+            Self::Mv => a.mv.cmp(&b.mv),
+            Self::Chirality => chirality_rank(a.chirality).cmp(&chirality_rank(b.chirality)),
+            Self::Lattice => b.lattice_basis.is_some().cmp(&a.lattice_basis.is_some()),
+            Self::GuideFrames => a.guide_frames.cmp(&b.guide_frames),
+            Self::Word => Ordering::Equal,
+        };
+        let by_field = if descending {
+            by_field.reverse()
+        } else {
+            by_field
+        };
+        by_field.then_with(|| {
+            let by_word = a.word.cmp(&b.word);
+            if descending && self == Self::Word {
+                by_word.reverse()
+            } else {
+                by_word
+            }
+        })
+    }
+}
+
+impl std::str::FromStr for ProfileSort {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "complexity" => Ok(Self::Complexity),
+            "mv" => Ok(Self::Mv),
+            "chirality" => Ok(Self::Chirality),
+            "lattice" => Ok(Self::Lattice),
+            "guide_frames" => Ok(Self::GuideFrames),
+            "word" => Ok(Self::Word),
+            _ => Err(Error::InvalidOption {
+                value: s.to_string(),
+                expected: PROFILE_SORTS,
+            }),
+        }
+    }
 }
 
-#[derive(Debug, Serialize)]
+/// The default number of profiles in a [`ProfilePage`].
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Which page of sorted scale profiles to get, as used by [`SigResult::page`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PageQuery {
+    /// how many profiles to skip
+    pub offset: usize,
+    /// the most profiles to return
+    pub limit: usize,
+    /// the field to sort by
+    pub sort: ProfileSort,
+    /// whether to sort in descending order
+    pub descending: bool,
+}
+
+impl Default for PageQuery {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            limit: DEFAULT_PAGE_SIZE,
+            sort: ProfileSort::default(),
+            descending: false,
+        }
+    }
+}
+
+/// A page of sorted scale profiles.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ProfilePage {
+    /// the profiles on the page
+    pub profiles: Vec<ScaleProfile>,
+    /// the position of the first profile on the page among all the profiles
+    pub offset: usize,
+    /// the number of profiles on all pages
+    pub total: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct WordResult {
-    profile: ScaleProfile,
-    ji_tunings: Vec<Vec<String>>,
-    ed_tunings: Vec<Vec<String>>,
+    /// profile of the scale
+    pub profile: ScaleProfile,
+    /// JI tunings of the step signature as step ratios
+    pub ji_tunings: Vec<Vec<String>>,
+    /// ED tunings of the step signature as step counts such as `4\22`
+    pub ed_tunings: Vec<Vec<String>>,
+    /// the val each ED tuning is best read under, in wart notation for EDOs
+    pub ed_vals: Vec<String>,
+}
+
+/// A WASM-readable analysis of an imported `.scl` file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SclResult {
+    /// the description line of the file
+    pub description: String,
+    /// the scale word read from the file, starting on the tonic of the file
+    pub word: String,
+    /// the size in cents of each step letter
+    pub step_cents: Vec<f64>,
+    pub profile: ScaleProfile,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct LatticeResult {
-    coordinates: Vec<Vec<i32>>,
-    basis: Vec<Vec<i16>>,
+    /// lattice coordinates of each pitch class
+    pub coordinates: Vec<Vec<i32>>,
+    /// the two basis vectors as step counts
+    pub basis: Vec<Vec<i16>>,
+    /// the 3D lattice, if the scale has no 2D one;
+    /// `coordinates` and `basis` are then its `[x, y]` coordinates and its last two basis vectors
+    #[serde(default)]
+    pub fallback: Option<lattice::Lattice3d>,
 }
 
-#[cfg(feature = "wasm")]
-fn string_to_numbers(word: &str) -> Vec<usize> {
+/// Filters on the scales of a step signature, as used by [`sig_to_result`].
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct SigFilter {
+    /// only L=m monotone MOS scales
+    pub lm: bool,
+    /// only m=s monotone MOS scales
+    pub ms: bool,
+    /// only s=0 monotone MOS scales
+    pub s0: bool,
+    /// maximum (or exact) length of the shortest guided generator sequence; 0 for no constraint
+    pub ggs_len: u8,
+    /// whether `ggs_len` is exact rather than a maximum
+    pub ggs_len_exact: bool,
+    /// maximum (or exact) maximum variety; 0 for no constraint
+    pub mv: u8,
+    /// whether `mv` is exact rather than a maximum
+    pub mv_exact: bool,
+    /// only MOS substitution scales instead of all necklaces
+    pub mos_subst: bool,
+}
+
+impl SigFilter {
+    /// Whether `scale` passes the filter. Doesn't check `mos_subst`.
+    pub fn accepts(&self, scale: &[usize]) -> bool {
+        (!self.lm || monotone_lm(scale))
+            && (!self.ms || monotone_ms(scale))
+            && (!self.s0 || monotone_s0(scale))
+            && (match self.ggs_len {
+                0 => true,
+                l => {
+                    let guide_frames = guide_frames(scale);
+                    if self.ggs_len_exact {
+                        !guide_frames.is_empty() && guide_frames[0].gs.len() == l as usize
+                    } else {
+                        !guide_frames.is_empty() && guide_frames[0].gs.len() <= l as usize
+                    }
+                }
+            })
+            && (match self.mv {
+                0 => true,
+                mv => {
+                    if self.mv_exact {
+                        maximum_variety_is(scale, mv as usize)
+                    } else {
+                        maximum_variety(scale) <= mv as usize
+                    }
+                }
+            })
+    }
+}
+
+/// A WASM-readable tuning bridge between an ED tuning and a JI tuning of a scale.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BridgeResult {
+    /// the val used to map JI intervals to ED steps
+    pub val: Vec<i32>,
+    /// whether the JI interpretation of each step is consistent with the ED
+    pub steps_consistent: Vec<bool>,
+    /// for each k-step class, the JI interpretation, ED step count, and consistency of each size
+    pub interval_classes: Vec<Vec<(String, i32, bool)>>,
+    /// commas tempered out by the ED within the JI tuning's subgroup
+    pub tempered_commas: Vec<String>,
+}
+
+/// Read a scale word written with step letters (`"LmLsLmLsL"`) as letters `0, 1, 2, ...`.
+pub fn string_to_numbers(word: &str) -> Vec<usize> {
     let mut result = vec![];
     let arity = word.chars().collect::<HashSet<_>>().len();
     for c in word.chars() {
-        if let Some(letter) = STEP_LETTERS[min(arity, 12)].find(c) {
+        if let Some(letter) = STEP_LETTERS[min(arity, 11)].find(c) {
             result.push(letter);
         }
     }
     result
 }
 
-fn word_to_sig(input: &[usize]) -> Vec<usize> {
+/// Read a scale word like [`string_to_numbers`], rejecting empty words
+/// and characters that aren't step letters for the word's arity.
+pub fn try_string_to_numbers(word: &str) -> Result<Vec<usize>, Error> {
+    let result = string_to_numbers(word);
+    if result.is_empty() || result.len() != word.chars().count() {
+        Err(Error::InvalidWord(word.to_string()))
+    } else {
+        Ok(result)
+    }
+}
+
+/// Read a ternary scale word in the letters `L`, `m` and `s`, each of which must occur.
+pub fn parse_ternary_word(word: &str) -> Result<Vec<usize>, Error> {
+    let result = string_to_numbers(word);
+    if result.len() == word.chars().count() && word_to_sig(&result).iter().all(|&n| n > 0) {
+        Ok(result)
+    } else {
+        Err(Error::InvalidWord(word.to_string()))
+    }
+}
+
+/// Check that `step_sig` is a ternary step signature, with a positive count of each step size.
+pub fn check_ternary_sig(step_sig: &[usize]) -> Result<(), Error> {
+    if step_sig.len() == 3 && step_sig.iter().all(|&n| n > 0) {
+        Ok(())
+    } else {
+        Err(Error::InvalidStepSig(step_sig.to_vec()))
+    }
+}
+
+/// Read an equave written as `"n/d"`, or `"n"` for a whole number. It must be greater than 1/1.
+pub fn parse_equave(equave: &str) -> Result<RawJiRatio, Error> {
+    match equave.parse::<RawJiRatio>() {
+        Ok(ratio) if ratio > RawJiRatio::UNISON => Ok(ratio),
+        _ => Err(Error::InvalidEquave(equave.to_string())),
+    }
+}
+
+/// Check the search bounds of the tuning queries: a positive ED bound,
+/// and step sizes in cents with `0 <= s_lower <= s_upper`.
+pub fn check_tuning_bounds(ed_bound: i32, s_lower: f64, s_upper: f64) -> Result<(), FieldError> {
+    if ed_bound < 1 {
+        return Err(Error::OutOfRange {
+            value: ed_bound.to_string(),
+            expected: "a positive ED bound",
+        }
+        .in_field("ed_bound"));
+    }
+    if !(s_lower.is_finite() && s_lower >= 0.0) {
+        return Err(Error::OutOfRange {
+            value: s_lower.to_string(),
+            expected: "a nonnegative number of cents",
+        }
+        .in_field("s_lower"));
+    }
+    if !(s_upper.is_finite() && s_upper >= s_lower) {
+        return Err(Error::OutOfRange {
+            value: s_upper.to_string(),
+            expected: "a number of cents no less than s_lower",
+        }
+        .in_field("s_upper"));
+    }
+    Ok(())
+}
+
+/// The step signature of a ternary scale word, as counts of L, m and s.
+pub fn word_to_sig(input: &[usize]) -> Vec<usize> {
     let mut result = vec![0, 0, 0];
     for &i in input {
         if i < 3 {
@@ -265,12 +710,13 @@ fn word_to_sig(input: &[usize]) -> Vec<usize> {
     result
 }
 
-fn numbers_to_string(word: &[usize]) -> String {
+/// Write a scale word given as letters `0, 1, 2, ...` with step letters; the inverse of [`string_to_numbers`].
+pub fn numbers_to_string(word: &[usize]) -> String {
     let mut result = "".to_string();
     let arity = word.iter().collect::<HashSet<_>>().len();
     for i in word {
         if *i <= arity {
-            result.push(STEP_LETTERS[min(arity, 12)].chars().nth(*i).unwrap_or('?'));
+            result.push(STEP_LETTERS[min(arity, 11)].chars().nth(*i).unwrap_or('?'));
         }
     }
     result
@@ -323,7 +769,9 @@ pub fn word_to_profile(query: &[usize]) -> ScaleProfile {
     let subst_l_ms = is_mos_subst_one_perm(query, 0, 1, 2);
     let subst_m_ls = is_mos_subst_one_perm(query, 1, 0, 2);
     let subst_s_lm = is_mos_subst_one_perm(query, 2, 0, 1);
-    if let Some(pair) = get_unimodular_basis(&guide_frames(query), &step_sig) {
+    let frames = guide_frames(query);
+    let name = named::profile_name(&brightest, RawJiRatio::OCTAVE);
+    if let Some(pair) = get_unimodular_basis(&frames, &step_sig) {
         let (lattice_basis, structure) = pair;
         ScaleProfile {
             word: brightest,
@@ -340,6 +788,8 @@ pub fn word_to_profile(query: &[usize]) -> ScaleProfile {
             subst_s_lm,
             ed_join,
             mv,
+            guide_frames: frames.len(),
+            name,
         }
     } else {
         ScaleProfile {
@@ -357,10 +807,61 @@ pub fn word_to_profile(query: &[usize]) -> ScaleProfile {
             subst_s_lm,
             ed_join,
             mv,
+            guide_frames: frames.len(),
+            name,
         }
     }
 }
 
+/// The profile and tunings of a scale word, as returned by `word_result` in WASM.
+pub fn word_to_result(
+    word: &[usize],
+    equave: RawJiRatio,
+    ed_bound: i32,
+    s_lower: f64,
+    s_upper: f64,
+) -> WordResult {
+    let step_sig = word_to_sig(word);
+    let (ed_tunings, ed_vals) = sig_to_ed_tunings_with_vals(
+        &step_sig,
+        equave,
+        ed_bound,
+        s_lower,
+        s_upper,
+        ED_VAL_ODD_LIMIT,
+    );
+    WordResult {
+        profile: with_name(word_to_profile(word), equave),
+        ji_tunings: sig_to_ji_tunings(&step_sig, equave, s_lower, s_upper),
+        ed_tunings,
+        ed_vals,
+    }
+}
+
+/// Read an equave from WASM arguments; see [`parse_equave`].
+#[cfg(feature = "wasm")]
+fn wasm_equave(equave_num: u32, equave_den: u32) -> Result<RawJiRatio, FieldError> {
+    parse_equave(&format!("{equave_num}/{equave_den}")).map_err(|e| e.in_field("equave"))
+}
+
+/// The options of the GGS length and MV filters of `sig_result`.
+#[cfg(feature = "wasm")]
+const CONSTRAINTS: &[&str] = &["exactly", "at-most"];
+
+/// Check that an option string from WASM is one of `expected`.
+#[cfg(feature = "wasm")]
+fn wasm_option(
+    value: String,
+    field: &'static str,
+    expected: &'static [&'static str],
+) -> Result<String, FieldError> {
+    if expected.contains(&value.as_str()) {
+        Ok(value)
+    } else {
+        Err(Error::InvalidOption { value, expected }.in_field(field))
+    }
+}
+
 #[cfg(feature = "wasm")]
 #[wasm_bindgen]
 pub fn word_result(
@@ -371,46 +872,412 @@ pub fn word_result(
     s_lower: f64,
     s_upper: f64,
 ) -> Result<JsValue, JsValue> {
-    let equave = RawJiRatio::try_new(equave_num, equave_den).unwrap_or(RawJiRatio::OCTAVE);
-    let word_as_numbers = string_to_numbers(&query);
-    let step_sig = word_to_sig(&word_as_numbers);
+    let word_as_numbers = parse_ternary_word(&query).map_err(|e| e.in_field("query"))?;
+    let equave = wasm_equave(equave_num, equave_den)?;
+    check_tuning_bounds(ed_bound, s_lower, s_upper)?;
+    Ok(to_value(&word_to_result(
+        &word_as_numbers,
+        equave,
+        ed_bound,
+        s_lower,
+        s_upper,
+    ))?)
+}
 
-    Ok(to_value(&WordResult {
-        profile: word_to_profile(&word_as_numbers),
-        ji_tunings: sig_to_ji_tunings(&step_sig, equave, s_lower, s_upper),
-        ed_tunings: sig_to_ed_tunings(&step_sig, equave, ed_bound, s_lower, s_upper),
+/// The named scales whose names or aliases contain `query`, exact matches first, as objects
+/// `{name, aliases, word, chirality, equave, reference}`; see [`named::search_scales`].
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn find_scales(query: String) -> Result<JsValue, JsValue> {
+    Ok(to_value(&named::search_scales(&query))?)
+}
+
+/// The named scales with the step signature `query` and the equave `equave_num/equave_den`,
+/// as objects like those of `find_scales`; see [`named::scales_with_sig`].
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn scales_with_sig(
+    query: Vec<u8>,
+    equave_num: u32,
+    equave_den: u32,
+) -> Result<JsValue, JsValue> {
+    let step_sig = query.iter().map(|x| *x as usize).collect::<Vec<_>>();
+    check_ternary_sig(&step_sig).map_err(|e| e.in_field("query"))?;
+    let equave = wasm_equave(equave_num, equave_den)?;
+    Ok(to_value(&named::scales_with_sig(&step_sig, equave))?)
+}
+
+/// The contents of a Scala `.scl` file for `query` under `tuning`, with the description line
+/// taken from the scale's profile. The scale starts on the first step of `query`.
+pub fn word_to_scl(query: &[usize], tuning: &tuning::StepTuning) -> String {
+    let name = numbers_to_string(query);
+    let description = format!("{name}: {}", word_to_profile(query).description());
+    scala::scl_string(query, tuning, &name, &description)
+}
+
+/// Read a nonempty scale word and a tuning with a step size for each of its letters from WASM arguments.
+#[cfg(feature = "wasm")]
+fn word_and_tuning(
+    query: &str,
+    tuning: &[String],
+) -> Result<(Vec<usize>, tuning::StepTuning), FieldError> {
+    let word_as_numbers = try_string_to_numbers(query).map_err(|e| e.in_field("query"))?;
+    let tuning =
+        tuning::StepTuning::from_strings(tuning).map_err(|e| Error::from(e).in_field("tuning"))?;
+    tuning
+        .check_word(&word_as_numbers)
+        .map_err(|e| Error::from(e).in_field("tuning"))?;
+    Ok((word_as_numbers, tuning))
+}
+
+/// A Scala `.scl` file for a scale word with step sizes given as strings
+/// such as `["9/8", "28/27", "64/63"]`, `["4\\26", "2\\26", "1\\26"]` or cents.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn word_to_scl_file(query: String, tuning: Vec<String>) -> Result<String, JsValue> {
+    let (word_as_numbers, tuning) = word_and_tuning(&query, &tuning)?;
+    Ok(word_to_scl(&word_as_numbers, &tuning))
+}
+
+/// SonicWeave source code for a mode of the scale word with step sizes given as strings,
+/// as in [`word_to_scl_file`]; see [`sonicweave::sonicweave_code`].
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn word_to_sonicweave(
+    query: String,
+    tuning: Vec<String>,
+    mode: usize,
+) -> Result<String, JsValue> {
+    let (word_as_numbers, tuning) = word_and_tuning(&query, &tuning)?;
+    let options = sonicweave::SonicWeaveOptions {
+        mode,
+        ..Default::default()
+    };
+    Ok(sonicweave::sonicweave_code(
+        &word_as_numbers,
+        &tuning,
+        &options,
+    ))
+}
+
+/// Read a `.scl` file as a scale word, grouping steps within `tolerance` cents of each other
+/// into the same step size, and return the word with the size of each letter and its profile.
+pub fn scl_to_profile(
+    contents: &str,
+    tolerance: f64,
+) -> Result<(Vec<usize>, Vec<f64>, ScaleProfile), scala::BadScl> {
+    let (word, step_cents) = scala::scl_to_word(contents, tolerance)?;
+    let profile = word_to_profile(&word);
+    Ok((word, step_cents, profile))
+}
+
+/// Analyze the contents of a `.scl` file; see [`scl_to_profile`].
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn scl_result(contents: String, tolerance: f64) -> Result<JsValue, JsValue> {
+    if !(tolerance.is_finite() && tolerance >= 0.0) {
+        return Err(Error::OutOfRange {
+            value: tolerance.to_string(),
+            expected: "a nonnegative number of cents",
+        }
+        .in_field("tolerance")
+        .into());
+    }
+    let scl_error = |e| Error::Scl(e).in_field("contents");
+    let description = scala::parse_scl(&contents).map_err(scl_error)?.description;
+    let (word, step_cents, profile) = scl_to_profile(&contents, tolerance).map_err(scl_error)?;
+    Ok(to_value(&SclResult {
+        description,
+        word: numbers_to_string(&word),
+        step_cents,
+        profile,
     })?)
 }
 
+/// Read a keyboard mapping from WASM arguments, rejecting notes outside the MIDI range
+/// and non-positive reference frequencies.
+#[cfg(feature = "wasm")]
+fn keyboard_mapping(
+    mode: usize,
+    root_note: u8,
+    reference_note: u8,
+    reference_frequency: f64,
+) -> Result<midi::KeyboardMapping, FieldError> {
+    for (note, field) in [(root_note, "root_note"), (reference_note, "reference_note")] {
+        if note as usize >= midi::MIDI_NOTE_COUNT {
+            return Err(Error::OutOfRange {
+                value: note.to_string(),
+                expected: "a MIDI note between 0 and 127",
+            }
+            .in_field(field));
+        }
+    }
+    if !(reference_frequency.is_finite() && reference_frequency > 0.0) {
+        return Err(Error::OutOfRange {
+            value: reference_frequency.to_string(),
+            expected: "a positive frequency",
+        }
+        .in_field("reference_frequency"));
+    }
+    Ok(midi::KeyboardMapping {
+        mode,
+        root_note,
+        reference_note,
+        reference_frequency,
+    })
+}
+
+/// A Scala `.kbm` file placing a mode of the scale word with step sizes given as strings
+/// on the MIDI keyboard, for the `.scl` file from [`word_to_scl_file`]; see [`midi::kbm_string`].
 #[cfg(feature = "wasm")]
 #[wasm_bindgen]
-pub fn word_to_brightest(query: String) -> String {
-    let word_in_numbers = string_to_numbers(&query);
-    let brightest = least_mode(&word_in_numbers);
-    numbers_to_string(&brightest)
+pub fn word_to_kbm_file(
+    query: String,
+    tuning: Vec<String>,
+    mode: usize,
+    root_note: u8,
+    reference_note: u8,
+    reference_frequency: f64,
+) -> Result<String, JsValue> {
+    let (word_as_numbers, tuning) = word_and_tuning(&query, &tuning)?;
+    let mapping = keyboard_mapping(mode, root_note, reference_note, reference_frequency)?;
+    Ok(midi::kbm_string(&word_as_numbers, &tuning, &mapping))
 }
 
+/// An AnaMark `.tun` file for a mode of the scale word with step sizes given as strings,
+/// as in [`word_to_scl_file`]; see [`midi::tun_string`].
 #[cfg(feature = "wasm")]
 #[wasm_bindgen]
-pub fn word_to_mv(query: String) -> u16 {
-    let word_in_numbers = string_to_numbers(&query);
-    maximum_variety(&word_in_numbers) as u16
+pub fn word_to_tun_file(
+    query: String,
+    tuning: Vec<String>,
+    mode: usize,
+    root_note: u8,
+    reference_note: u8,
+    reference_frequency: f64,
+) -> Result<String, JsValue> {
+    let (word_as_numbers, tuning) = word_and_tuning(&query, &tuning)?;
+    let mapping = keyboard_mapping(mode, root_note, reference_note, reference_frequency)?;
+    let name = numbers_to_string(&words::rotate(&word_as_numbers, mode));
+    Ok(midi::tun_string(&word_as_numbers, &tuning, &mapping, &name))
 }
 
-/// Get lattice coordinates for pitch classes if a unimodular basis exists.
-/// Returns None if no unimodular basis can be found.
-/// The coordinates are 2D projections suitable for plotting.
-/// Prioritizes the basis from parallelogram_substring_info if one exists.
+/// A MIDI Tuning Standard SysEx message for a mode of the scale word with step sizes given as strings,
+/// as in [`word_to_scl_file`]. `kind` is one of:
+///
+/// - `"bulk"`: a bulk tuning dump of all 128 notes of tuning program `program`
+/// - `"single"`: a single-note tuning change of all 128 notes of tuning program `program`,
+///   split into one message per 64 notes
+/// - `"scale-octave"`: a two-byte scale/octave tuning on all 16 channels
 #[cfg(feature = "wasm")]
 #[wasm_bindgen]
-pub fn word_to_lattice(query: String) -> Result<JsValue, JsValue> {
-    let word_in_numbers = string_to_numbers(&query);
-    let step_sig = word_to_sig(&word_in_numbers)
+#[allow(clippy::too_many_arguments)]
+pub fn word_to_mts_sysex(
+    query: String,
+    tuning: Vec<String>,
+    kind: String,
+    mode: usize,
+    root_note: u8,
+    reference_note: u8,
+    reference_frequency: f64,
+    program: u8,
+) -> Result<Vec<u8>, JsValue> {
+    let (word_as_numbers, tuning) = word_and_tuning(&query, &tuning)?;
+    let mapping = keyboard_mapping(mode, root_note, reference_note, reference_frequency)?;
+    let device = midi::MTS_ALL_DEVICES;
+    let mts_error = |e| Error::Mts(e).in_field("tuning");
+    match kind.as_str() {
+        "bulk" => {
+            let name = numbers_to_string(&words::rotate(&word_as_numbers, mode));
+            Ok(midi::mts_bulk_dump(
+                &word_as_numbers,
+                &tuning,
+                &mapping,
+                device,
+                program,
+                &name,
+            ))
+        }
+        "single" => {
+            let notes: Vec<u8> = (0..midi::MIDI_NOTE_COUNT as u8).collect();
+            let mut result = vec![];
+            for chunk in notes.chunks(64) {
+                result.extend(
+                    midi::mts_single_note_change(
+                        &word_as_numbers,
+                        &tuning,
+                        &mapping,
+                        device,
+                        program,
+                        chunk,
+                    )
+                    .map_err(mts_error)?,
+                );
+            }
+            Ok(result)
+        }
+        "scale-octave" => midi::mts_scale_octave(
+            &word_as_numbers,
+            &tuning,
+            &mapping,
+            device,
+            0xFFFF,
+            midi::ScaleOctaveFormat::TwoByte,
+        )
+        .map_err(|e| mts_error(e).into()),
+        _ => Err(Error::InvalidOption {
+            value: kind,
+            expected: &["bulk", "single", "scale-octave"],
+        }
+        .in_field("kind")
+        .into()),
+    }
+}
+
+/// Link an ED tuning of a scale word to a JI tuning of it, given as step size strings
+/// such as `["4\26", "2\26", "1\26"]` and `["9/8", "28/27", "64/63"]`, under `val`,
+/// the val the ED tables report for the ED tuning (e.g. `"26p"`, see [`sig_to_ed_tunings_with_vals`]).
+/// Tempered commas are searched for among quotients of `odd`-odd-limit intervals up to `max_comma_cents`.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn tuning_bridge_result(
+    query: String,
+    ed_tuning: Vec<String>,
+    ji_tuning: Vec<String>,
+    val: String,
+    odd: u32,
+    max_comma_cents: f64,
+) -> Result<JsValue, JsValue> {
+    use crate::tuning::StepTuning;
+
+    let word_as_numbers = try_string_to_numbers(&query).map_err(|e| e.in_field("query"))?;
+    let arity = word_as_numbers.iter().max().map_or(0, |&letter| letter + 1);
+    let (ed_input, ji_input) = (ed_tuning.join(" "), ji_tuning.join(" "));
+    let ed_tuning =
+        StepTuning::from_strings(&ed_tuning).map_err(|e| Error::from(e).in_field("ed_tuning"))?;
+    let ji_tuning =
+        StepTuning::from_strings(&ji_tuning).map_err(|e| Error::from(e).in_field("ji_tuning"))?;
+    let (ed_steps, ed, equave) = match ed_tuning {
+        StepTuning::Ed { steps, ed, equave } if steps.len() >= arity => (steps, ed, equave),
+        _ => {
+            return Err(Error::OutOfRange {
+                value: ed_input,
+                expected: "an ED step size for each letter of the scale word",
+            }
+            .in_field("ed_tuning")
+            .into());
+        }
+    };
+    let ji_steps = match ji_tuning {
+        StepTuning::Ji(steps) if steps.len() >= arity => steps,
+        _ => {
+            return Err(Error::OutOfRange {
+                value: ji_input,
+                expected: "a JI step size for each letter of the scale word",
+            }
+            .in_field("ji_tuning")
+            .into());
+        }
+    };
+    if !(odd >= 1 && odd % 2 == 1) {
+        return Err(Error::OutOfRange {
+            value: odd.to_string(),
+            expected: "an odd limit",
+        }
+        .in_field("odd")
+        .into());
+    }
+    if !(max_comma_cents.is_finite() && max_comma_cents >= 0.0) {
+        return Err(Error::OutOfRange {
+            value: max_comma_cents.to_string(),
+            expected: "a nonnegative number of cents",
+        }
+        .in_field("max_comma_cents")
+        .into());
+    }
+    let val_input = val;
+    let val: equal::Val = val_input
+        .parse()
+        .map_err(|e: equal::BadWart| Error::from(e).in_field("val"))?;
+    if Monzo::try_from_ratio(equave).map_or(true, |equave| val.evaluate(equave) != ed) {
+        return Err(Error::OutOfRange {
+            value: val_input,
+            expected: "a val mapping the equave to the ED tuning's ED",
+        }
+        .in_field("val")
+        .into());
+    }
+    let commas = bridge::comma_search(&val, odd, max_comma_cents, &ji_steps);
+    let bridge = bridge::tuning_bridge(
+        &word_as_numbers,
+        &ed_steps,
+        ed,
+        equave,
+        &val,
+        &ji_steps,
+        &commas,
+    );
+    let monzo_to_string = |m: &Monzo| {
+        m.try_to_ratio()
+            .map(|r| r.to_string())
+            .unwrap_or_else(|| m.to_string())
+    };
+    Ok(to_value(&BridgeResult {
+        val: bridge.val.into_inner().as_array().to_vec(),
+        steps_consistent: bridge.steps.iter().map(|s| s.is_consistent()).collect(),
+        interval_classes: bridge
+            .interval_classes
+            .iter()
+            .map(|class| {
+                class
+                    .iter()
+                    .map(|i| (monzo_to_string(&i.ji), i.ed_steps, i.is_consistent()))
+                    .collect()
+            })
+            .collect(),
+        tempered_commas: bridge.tempered_commas.iter().map(monzo_to_string).collect(),
+    })?)
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn word_to_brightest(query: String) -> Result<String, JsValue> {
+    let word_in_numbers = try_string_to_numbers(&query).map_err(|e| e.in_field("query"))?;
+    let brightest = least_mode(&word_in_numbers);
+    Ok(numbers_to_string(&brightest))
+}
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn word_to_mv(query: String) -> Result<u16, JsValue> {
+    let word_in_numbers = try_string_to_numbers(&query).map_err(|e| e.in_field("query"))?;
+    Ok(maximum_variety(&word_in_numbers) as u16)
+}
+
+/// Lattice coordinates for the pitch classes of a scale word and the basis they are given in.
+/// Scales without a unimodular basis get a [`lattice::fallback_lattice`].
+/// Returns `None` only for the empty word. See `word_to_lattice` for the WASM version.
+///
+/// # Examples
+///
+/// ```
+/// use ternary::lattice::NoLatticeReason;
+/// use ternary::{string_to_numbers, word_to_lattice_result};
+///
+/// let diasem = word_to_lattice_result(&string_to_numbers("LmLsLmLsL")).unwrap();
+/// assert_eq!(diasem.fallback, None);
+/// let lattice = word_to_lattice_result(&string_to_numbers("LmLsLmLs")).unwrap();
+/// let fallback = lattice.fallback.unwrap();
+/// assert_eq!(fallback.reason, NoLatticeReason::Torsion { periods: 2 });
+/// assert_eq!(lattice.coordinates[0], fallback.coordinates[0][1..]);
+/// ```
+pub fn word_to_lattice_result(word_in_numbers: &[usize]) -> Option<LatticeResult> {
+    let step_sig = word_to_sig(word_in_numbers)
         .iter()
         .map(|x| *x as i32)
         .collect::<Vec<i32>>();
     // First get the initial lattice and basis
-    if let Some((pitch_classes, initial_basis)) = lattice::try_pitch_class_lattice(&word_in_numbers)
+    if let Some((pitch_classes, initial_basis)) = lattice::try_pitch_class_lattice(word_in_numbers)
     {
         // Try to find a better basis using parallelogram_substring_info
         let pitch_class_refs: Vec<&[i32]> = pitch_classes.iter().map(|v| v.as_slice()).collect();
@@ -419,7 +1286,7 @@ pub fn word_to_lattice(query: String) -> Result<JsValue, JsValue> {
             lattice::parallelogram_substring_info(&pitch_class_refs, &initial_basis)
         {
             // Re-project pitch classes using the better basis
-            let (coords, _) = lattice::pitch_classes(&word_in_numbers, &better_basis);
+            let (coords, _) = lattice::pitch_classes(word_in_numbers, &better_basis);
             // Equave reduce the basis
             let better_basis_rd = better_basis.equave_reduce(&step_sig);
             (coords, better_basis_rd)
@@ -442,16 +1309,68 @@ pub fn word_to_lattice(query: String) -> Result<JsValue, JsValue> {
             ],
         ];
 
-        Ok(to_value(&Some(LatticeResult {
+        Some(LatticeResult {
             coordinates: final_coordinates,
             basis: basis_as_vecs,
-        }))?)
+            fallback: None,
+        })
     } else {
-        // No unimodular basis found - return early
-        Ok(to_value(&None::<LatticeResult>)?)
+        // No unimodular basis found, so use the [x, y] plane of a 3D lattice
+        lattice::fallback_lattice(word_in_numbers).map(|fallback| LatticeResult {
+            coordinates: fallback
+                .coordinates
+                .iter()
+                .map(|&[_, x, y]| vec![x, y])
+                .collect(),
+            basis: fallback.basis[1..]
+                .iter()
+                .map(|v| v.iter().map(|&x| x as i16).collect())
+                .collect(),
+            fallback: Some(fallback),
+        })
     }
 }
 
+/// Get lattice coordinates for pitch classes.
+/// Scales without a unimodular basis get a 3D fallback lattice in the `fallback` field.
+/// The coordinates are 2D projections suitable for plotting.
+/// Prioritizes the basis from parallelogram_substring_info if one exists.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn word_to_lattice(query: String) -> Result<JsValue, JsValue> {
+    let word_in_numbers = try_string_to_numbers(&query).map_err(|e| e.in_field("query"))?;
+    Ok(to_value(&word_to_lattice_result(&word_in_numbers))?)
+}
+
+/// All lattice bases of a scale that show it as a parallelogram substring, best first by `score`:
+/// one of `"fifth"`, `"shortest"`, `"near_interval"` or `"guide_frames"`, where `"near_interval"`
+/// prefers a basis vector near the JI ratio `interval` under the step sizes `tuning`, given as
+/// strings like those of [`word_to_scl_file`]. `interval` and `tuning` are ignored by the other scores.
+/// Each basis is `{basis: {vx, vy}, shape, coordinates, score}`.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+pub fn word_to_lattice_bases(
+    query: String,
+    score: String,
+    interval: String,
+    tuning: Vec<String>,
+) -> Result<JsValue, JsValue> {
+    let word_in_numbers = try_string_to_numbers(&query).map_err(|e| e.in_field("query"))?;
+    let score = if score == "near_interval" {
+        let interval = interval
+            .parse::<RawJiRatio>()
+            .map_err(|e| Error::from(e).in_field("interval"))?;
+        let tuning = tuning::StepTuning::from_strings(&tuning)
+            .map_err(|e| Error::from(e).in_field("tuning"))?;
+        lattice::BasisScore::NearInterval { interval, tuning }
+    } else {
+        score.parse().map_err(|e: Error| e.in_field("score"))?
+    };
+    let bases = lattice::ranked_lattice_bases(&word_in_numbers, &score)
+        .map_err(|e| Error::from(e).in_field("tuning"))?;
+    Ok(to_value(&bases)?)
+}
+
 /// Get JI tunings for a step signature using 81-odd-limit intervals.
 /// This is not an exhaustive search - it only considers intervals < 300 cents
 /// and requires steps to be strictly descending in size.
@@ -463,24 +1382,34 @@ pub fn sig_to_ji_tunings(
 ) -> Vec<Vec<String>> {
     let equave_monzo = Monzo::try_from_ratio(equave).ok();
     if let Some(equave_monzo) = equave_monzo {
-        ji::solve_step_sig_fast(step_sig, equave_monzo, cents_lower_bound, cents_upper_bound)
-            .into_iter()
-            .map(|steps| {
-                steps
-                    .into_iter()
-                    .map(|m| {
-                        m.try_to_ratio()
-                            .map(|r| r.to_string())
-                            .unwrap_or_else(|| m.to_string())
-                    })
-                    .collect()
-            })
-            .collect()
+        ji_tunings_to_strings(ji::solve_step_sig_fast(
+            step_sig,
+            equave_monzo,
+            cents_lower_bound,
+            cents_upper_bound,
+        ))
     } else {
         vec![]
     }
 }
 
+/// Write each step of JI tunings as a ratio, or as a monzo if it's too complex for a ratio.
+fn ji_tunings_to_strings(tunings: Vec<Vec<Monzo>>) -> Vec<Vec<String>> {
+    tunings
+        .into_iter()
+        .map(|steps| {
+            steps
+                .into_iter()
+                .map(|m| {
+                    m.try_to_ratio()
+                        .map(|r| r.to_string())
+                        .unwrap_or_else(|| m.to_string())
+                })
+                .collect()
+        })
+        .collect()
+}
+
 /// Get more JI tunings using the slow solver (shifts by 270edo commas).
 /// Returns tunings that are NOT already in the fast solver results.
 pub fn sig_to_ji_tunings_slow(
@@ -491,19 +1420,12 @@ pub fn sig_to_ji_tunings_slow(
 ) -> Vec<Vec<String>> {
     let equave_monzo = Monzo::try_from_ratio(equave).ok();
     if let Some(equave_monzo) = equave_monzo {
-        ji::solve_step_sig_slow(step_sig, equave_monzo, cents_lower_bound, cents_upper_bound)
-            .into_iter()
-            .map(|steps| {
-                steps
-                    .into_iter()
-                    .map(|m| {
-                        m.try_to_ratio()
-                            .map(|r| r.to_string())
-                            .unwrap_or_else(|| m.to_string())
-                    })
-                    .collect()
-            })
-            .collect()
+        ji_tunings_to_strings(ji::solve_step_sig_slow(
+            step_sig,
+            equave_monzo,
+            cents_lower_bound,
+            cents_upper_bound,
+        ))
     } else {
         vec![]
     }
@@ -518,15 +1440,29 @@ pub fn sig_to_ed_tunings(
 ) -> Vec<Vec<String>> {
     let ed_tunings =
         crate::equal::ed_tunings_for_ternary(step_sig, equave, ed_bound, s_lower, s_upper);
+    ed_tunings_to_strings(step_sig, equave, &ed_tunings)
+}
+
+/// The number of EDO steps of an ED tuning of `step_sig`.
+fn ed_of(step_sig: &[usize], steps: &[i32]) -> i32 {
+    steps
+        .iter()
+        .zip(step_sig)
+        .map(|(&steps, &count)| count as i32 * steps)
+        .sum()
+}
+
+/// Write each step of ED tunings as `steps\ed`, followed by `<equave>` for non-octave equaves.
+fn ed_tunings_to_strings(
+    step_sig: &[usize],
+    equave: RawJiRatio,
+    ed_tunings: &[Vec<i32>],
+) -> Vec<Vec<String>> {
     let is_octave = equave.numer() == 2 && equave.denom() == 1;
     ed_tunings
-        .into_iter()
+        .iter()
         .map(|v| {
-            let ed: i32 = v
-                .iter()
-                .enumerate()
-                .map(|(i, steps)| step_sig[i] as i32 * steps)
-                .sum();
+            let ed = ed_of(step_sig, v);
             if is_octave {
                 v.iter().map(|i| format!("{i}\\{ed}")).collect::<Vec<_>>()
             } else {
@@ -538,6 +1474,95 @@ pub fn sig_to_ed_tunings(
         .collect::<Vec<_>>()
 }
 
+/// The odd limit used to choose the val each ED tuning is read under in WASM results.
+pub const ED_VAL_ODD_LIMIT: u32 = 15;
+
+/// The ED tunings of `step_sig` as in [`sig_to_ed_tunings`], with the val each is best read under.
+///
+/// For octave equaves the ED tuning is read as the simplest JI tuning of `step_sig`
+/// (as in [`sig_to_ji_tunings`], by the total Tenney height of its steps) that one of the vals
+/// of the EDO maps to it, among the vals mapping each prime up to `odd` within one step of
+/// its exact size. The val is the one with the least error over the `odd`-odd-limit diamond
+/// that maps this JI tuning to the ED tuning, written in wart notation (e.g. "17c").
+/// If no such val reads any JI tuning as the ED tuning, it is [`best_val`](equal::best_val).
+/// For other equaves it is the generalized patent val in bra notation.
+///
+/// # Examples
+///
+/// ```
+/// use ternary::ji_ratio::RawJiRatio;
+/// use ternary::sig_to_ed_tunings_with_vals;
+///
+/// let (tunings, vals) = sig_to_ed_tunings_with_vals(&[5, 2, 2], RawJiRatio::OCTAVE, 36, 20.0, 250.0, 15);
+/// let val_of = |tuning: [&str; 3]| &vals[tunings.iter().position(|t| *t == tuning).unwrap()];
+/// // Two tunings of 35edo, both read as 9/8, 28/27, 64/63 but under different vals
+/// assert_eq!(val_of(["5\\35", "3\\35", "2\\35"]), "35f");
+/// assert_eq!(val_of(["5\\35", "4\\35", "1\\35"]), "35df");
+/// assert_eq!(val_of(["4\\26", "2\\26", "1\\26"]), "26p");
+/// ```
+pub fn sig_to_ed_tunings_with_vals(
+    step_sig: &[usize],
+    equave: RawJiRatio,
+    ed_bound: i32,
+    s_lower: f64,
+    s_upper: f64,
+    odd: u32,
+) -> (Vec<Vec<String>>, Vec<String>) {
+    let ed_tunings =
+        crate::equal::ed_tunings_for_ternary(step_sig, equave, ed_bound, s_lower, s_upper);
+    let is_octave = equave.numer() == 2 && equave.denom() == 1;
+    let ji_tunings = if is_octave {
+        ji::solve_step_sig_fast(step_sig, Monzo::OCTAVE, s_lower, s_upper)
+    } else {
+        vec![]
+    };
+    let dim = primes::SMALL_PRIMES
+        .iter()
+        .take_while(|&&p| p <= odd.max(2))
+        .count();
+    // Only JI tunings in the prime limit of the vals can be read under them
+    let ji_tunings: Vec<&Vec<Monzo>> = ji_tunings
+        .iter()
+        .filter(|steps| {
+            steps
+                .iter()
+                .all(|step| (dim..primes::SMALL_PRIMES_COUNT).all(|i| step[i] == 0))
+        })
+        .collect();
+    let vals = ed_tunings
+        .iter()
+        .map(|steps| {
+            let ed = ed_of(step_sig, steps);
+            if !is_octave {
+                return bridge::ed_val(ed, equave).to_string();
+            }
+            // The simplest JI tuning each val reads as the ED tuning, by total Tenney height
+            let simplest_reading = |val: &equal::Val| {
+                ji_tunings
+                    .iter()
+                    .filter(|ji| {
+                        ji.iter()
+                            .zip(steps)
+                            .all(|(&ji_step, &ed_step)| val.evaluate(ji_step) == ed_step)
+                    })
+                    .map(|ji| {
+                        ji.iter()
+                            .map(|&step| bridge::tenney_height(step))
+                            .sum::<f64>()
+                    })
+                    .min_by(f64::total_cmp)
+            };
+            equal::vals_for_edo(ed, dim, 1.0)
+                .into_iter()
+                .filter_map(|val| Some((simplest_reading(&val)?, val.odd_limit_l2_error(odd), val)))
+                .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)))
+                .map_or_else(|| equal::best_val(ed, odd), |(_, _, val)| val)
+                .wart_name()
+        })
+        .collect();
+    (ed_tunings_to_strings(step_sig, equave, &ed_tunings), vals)
+}
+
 #[cfg(feature = "wasm")]
 #[wasm_bindgen]
 #[allow(clippy::too_many_arguments)]
@@ -557,59 +1582,392 @@ pub fn sig_result(
     s_lower: f64,
     s_upper: f64,
 ) -> Result<JsValue, JsValue> {
-    let equave = RawJiRatio::try_new(equave_num, equave_den).unwrap_or(RawJiRatio::OCTAVE);
-    let step_sig = query;
-    let filtering_cond = |scale: &[Letter]| {
-        (!lm || monotone_lm(scale))
-            && (!ms || monotone_ms(scale))
-            && (!s0 || monotone_s0(scale))
-            && (match ggs_len {
-                0 => true,
-                l => {
-                    let guide_frames = guide_frames(scale);
-                    if ggs_len_constraint == "exactly" {
-                        !guide_frames.is_empty() && guide_frames[0].gs.len() == l as usize
-                    } else {
-                        !guide_frames.is_empty() && guide_frames[0].gs.len() <= l as usize
-                    }
+    let search = SigSearch::js_new(
+        query,
+        lm,
+        ms,
+        s0,
+        ggs_len,
+        ggs_len_constraint,
+        mv,
+        mv_constraint,
+        scale_type,
+        equave_num,
+        equave_den,
+        ed_bound,
+        s_lower,
+        s_upper,
+    )?;
+    Ok(to_value(&search.finish())?)
+}
+
+/// `profile` with the name of its scale with the equave `equave`.
+pub(crate) fn with_name(mut profile: ScaleProfile, equave: RawJiRatio) -> ScaleProfile {
+    profile.name = named::profile_name(&profile.word, equave);
+    profile
+}
+
+/// Sort profiles by guide frame complexity, with scales without a guide frame last,
+/// keeping the order of profiles of equal complexity.
+pub(crate) fn sort_by_complexity(profiles: &mut [ScaleProfile]) {
+    profiles.sort_by_key(|profile| match &profile.structure {
+        Some(guide) => guide.complexity,
+        None => u16::MAX,
+    });
+}
+
+/// The profiles of the scales of a step signature that pass `filter`, sorted by guide frame complexity,
+/// together with the tunings of the step signature, as returned by `sig_result` in WASM.
+pub fn sig_to_result(
+    step_sig: &[usize],
+    filter: &SigFilter,
+    equave: RawJiRatio,
+    ed_bound: i32,
+    s_lower: f64,
+    s_upper: f64,
+) -> SigResult {
+    SigSearch::new(step_sig, filter.clone(), equave, ed_bound, s_lower, s_upper).finish()
+}
+
+/// The computation of [`sig_to_result`], as a resumable search.
+///
+/// The search enumerates the scales of the step signature a given number at a time,
+/// then filters and profiles them one at a time, then computes the tunings. [`step`](SigSearch::step) does a given number of
+/// units of work and returns, and [`run`](SigSearch::run) reports to a
+/// [`Monitor`](progress::Monitor) that can cancel the search.
+/// With the `parallel` feature, the scales in each step are filtered and profiled on all cores,
+/// in the same order as without it.
+/// In WASM this is the `SigSearch` class, whose `step` method returns the progress as an object
+/// `{stage, done, total}` and whose `result` method returns the `sig_result` object once
+/// the stage is `"done"`.
+///
+/// # Examples
+///
+/// ```
+/// use ternary::ji_ratio::RawJiRatio;
+/// use ternary::progress::Stage;
+/// use ternary::{SigFilter, SigSearch, sig_to_result};
+///
+/// let filter = SigFilter::default();
+/// let mut search = SigSearch::new(&[5, 2, 2], filter.clone(), RawJiRatio::OCTAVE, 53, 20.0, 250.0);
+/// // each scale found while enumerating them is a unit of work
+/// let progress = search.step(50);
+/// assert_eq!((progress.stage, progress.done), (Stage::Enumerate, 50));
+/// // the last 34 scales, then 6 of them filtered
+/// let progress = search.step(40);
+/// assert_eq!((progress.stage, progress.done, progress.total), (Stage::Filter, 6, Some(84)));
+/// while !search.step(10).is_done() {}
+/// assert_eq!(
+///     search.finish(),
+///     sig_to_result(&[5, 2, 2], &filter, RawJiRatio::OCTAVE, 53, 20.0, 250.0)
+/// );
+/// ```
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Clone, Debug)]
+pub struct SigSearch {
+    step_sig: Vec<usize>,
+    filter: SigFilter,
+    equave: RawJiRatio,
+    ed_bound: i32,
+    s_lower: f64,
+    s_upper: f64,
+    necklaces: NecklaceSearch,
+    scales: Option<Vec<Vec<usize>>>,
+    next: usize,
+    result: SigResult,
+    done: bool,
+    cache: Option<cache::ProfileCache>,
+}
+
+/// How many scales [`SigSearch::run`] finds between progress reports while enumerating them.
+const ENUMERATE_CHUNK: usize = 1024;
+/// How many scales [`SigSearch::run`] filters per thread between progress reports.
+const FILTER_CHUNK_PER_THREAD: usize = 16;
+
+impl SigSearch {
+    /// A search for the ternary step signature `step_sig` that hasn't started yet.
+    pub fn new(
+        step_sig: &[usize],
+        filter: SigFilter,
+        equave: RawJiRatio,
+        ed_bound: i32,
+        s_lower: f64,
+        s_upper: f64,
+    ) -> Self {
+        Self {
+            step_sig: step_sig.to_vec(),
+            filter,
+            equave,
+            ed_bound,
+            s_lower,
+            s_upper,
+            necklaces: NecklaceSearch::new(step_sig),
+            scales: None,
+            next: 0,
+            result: SigResult {
+                profiles: vec![],
+                ji_tunings: vec![],
+                ed_tunings: vec![],
+                ed_vals: vec![],
+            },
+            done: false,
+            cache: None,
+        }
+    }
+
+    /// Take profiles and tunings from `cache` instead of computing them, adding the missing ones to it.
+    /// [`finish_with_cache`](SigSearch::finish_with_cache) gives the cache back.
+    pub(crate) fn with_cache(mut self, cache: cache::ProfileCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// The stage the search is at, and how many units of work of that stage are done.
+    pub fn progress(&self) -> Progress {
+        match &self.scales {
+            _ if self.done => Progress::DONE,
+            None => Progress {
+                stage: Stage::Enumerate,
+                done: self.necklaces.found().len(),
+                total: None,
+            },
+            Some(scales) if self.next < scales.len() => Progress {
+                stage: Stage::Filter,
+                done: self.next,
+                total: Some(scales.len()),
+            },
+            Some(_) => Progress {
+                stage: Stage::Tunings,
+                done: 0,
+                total: Some(1),
+            },
+        }
+    }
+
+    /// Do at most `budget` units of work and return the progress.
+    /// Finding a scale while enumerating them is a unit, and so is filtering and profiling a scale.
+    /// Enumerating the MOS substitution scales and computing the tunings each count as a single unit.
+    pub fn step(&mut self, budget: usize) -> Progress {
+        let mut budget = budget;
+        while budget > 0 && !self.done {
+            match &self.scales {
+                None if self.filter.mos_subst => {
+                    self.scales = Some(words::mos_substitution_scales(&self.step_sig));
+                    budget -= 1;
                 }
-            })
-            && (match mv {
-                0 => true,
-                mv => {
-                    if mv_constraint == "exactly" {
-                        maximum_variety_is(scale, mv as usize)
-                    } else {
-                        maximum_variety(scale) <= mv as usize
-                    }
+                None => budget -= self.enumerate(budget),
+                Some(scales) if self.next < scales.len() => {
+                    let end = scales.len().min(self.next.saturating_add(budget));
+                    budget -= end - self.next;
+                    self.filter_scales(end);
                 }
-            })
-    };
-    let step_sig = step_sig.iter().map(|x| *x as usize).collect::<Vec<_>>();
-    let scales = if scale_type == "mos-subst" {
-        words::mos_substitution_scales(&step_sig)
-    } else {
-        crate::comb::necklaces_fixed_content(&step_sig)
-    }; // Now filter
-    let scales = scales
-        .into_iter()
-        .filter(|scale| filtering_cond(scale))
-        .collect::<Vec<_>>();
-    Ok(to_value(&SigResult {
-        profiles: scales
-            .iter()
-            .map(|scale| word_to_profile(scale))
-            .sorted_by_key(|profile| {
-                if let Some(guide) = &profile.structure {
-                    guide.complexity
-                } else {
-                    u16::MAX
+                Some(_) => {
+                    self.compute_tunings();
+                    budget -= 1;
+                }
+            }
+        }
+        self.progress()
+    }
+
+    /// Run the rest of the search, reporting to `monitor` after every 1024 scales found
+    /// while enumerating them, every chunk of scales filtered (16 per thread with the
+    /// `parallel` feature) and after computing the tunings.
+    pub fn run(mut self, monitor: &mut Monitor) -> Result<SigResult, Cancelled> {
+        monitor.report(self.progress())?;
+        let chunk_len = helpers::par_chunk_len(FILTER_CHUNK_PER_THREAD);
+        while !self.done {
+            match &self.scales {
+                None if !self.filter.mos_subst => {
+                    self.enumerate(ENUMERATE_CHUNK);
                 }
+                Some(scales) if self.next < scales.len() => {
+                    let end = scales.len().min(self.next + chunk_len);
+                    self.filter_scales(end);
+                }
+                _ => {
+                    self.step(1);
+                }
+            }
+            monitor.report(self.progress())?;
+        }
+        Ok(self.result)
+    }
+
+    /// Run the rest of the search and return the result.
+    pub fn finish(mut self) -> SigResult {
+        self.step(usize::MAX);
+        self.result
+    }
+
+    /// Run the rest of the search and return the result with the cache given to
+    /// [`with_cache`](SigSearch::with_cache), or an empty cache if none was.
+    pub(crate) fn finish_with_cache(mut self) -> (SigResult, cache::ProfileCache) {
+        self.step(usize::MAX);
+        (self.result, self.cache.unwrap_or_default())
+    }
+
+    // Find at most `budget` more scales and return how many were found,
+    // keeping them once every scale has been found.
+    fn enumerate(&mut self, budget: usize) -> usize {
+        let found = self.necklaces.found().len();
+        let enumerated = self.necklaces.step(budget);
+        let spent = self.necklaces.found().len() - found;
+        if enumerated {
+            self.scales = Some(std::mem::take(&mut self.necklaces).into_necklaces());
+        }
+        spent
+    }
+
+    // Filter and profile the scales from `next` up to index `end`,
+    // sorting the profiles by guide frame complexity after the last scale.
+    fn filter_scales(&mut self, end: usize) {
+        let Some(scales) = &self.scales else {
+            return;
+        };
+        let (filter, equave) = (&self.filter, self.equave);
+        let profiles = match &mut self.cache {
+            Some(cache) => {
+                let accepted: Vec<Vec<usize>> =
+                    helpers::par_map(&scales[self.next..end], |scale| {
+                        filter.accepts(scale).then(|| scale.clone())
+                    })
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                cache.profiles(&accepted)
+            }
+            None => helpers::par_map(&scales[self.next..end], |scale| {
+                filter.accepts(scale).then(|| word_to_profile(scale))
             })
+            .into_iter()
+            .flatten()
             .collect(),
-        ji_tunings: sig_to_ji_tunings(&step_sig, equave, s_lower, s_upper),
-        ed_tunings: sig_to_ed_tunings(&step_sig, equave, ed_bound, s_lower, s_upper),
-    })?)
+        };
+        self.result.profiles.extend(
+            profiles
+                .into_iter()
+                .map(|profile| with_name(profile, equave)),
+        );
+        if end == scales.len() {
+            sort_by_complexity(&mut self.result.profiles);
+        }
+        self.next = end;
+    }
+
+    fn compute_tunings(&mut self) {
+        let (step_sig, equave) = (&self.step_sig, self.equave);
+        let (ed_bound, s_lower, s_upper) = (self.ed_bound, self.s_lower, self.s_upper);
+        if let Some(cache) = &mut self.cache {
+            self.result.ji_tunings = cache.ji_tunings(step_sig, equave, s_lower, s_upper, false);
+            let ed = cache.ed_tunings(step_sig, equave, ed_bound, s_lower, s_upper);
+            (self.result.ed_tunings, self.result.ed_vals) = (ed.ed_tunings, ed.ed_vals);
+        } else {
+            self.result.ji_tunings = sig_to_ji_tunings(step_sig, equave, s_lower, s_upper);
+            (self.result.ed_tunings, self.result.ed_vals) = sig_to_ed_tunings_with_vals(
+                step_sig,
+                equave,
+                ed_bound,
+                s_lower,
+                s_upper,
+                ED_VAL_ODD_LIMIT,
+            );
+        }
+        self.done = true;
+    }
+}
+
+/// `SigSearch` in WASM, validating its arguments like `sig_result`.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+impl SigSearch {
+    #[wasm_bindgen(constructor)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn js_new(
+        query: Vec<u8>,
+        lm: bool,
+        ms: bool,
+        s0: bool,
+        ggs_len: u8,
+        ggs_len_constraint: String,
+        mv: u8,
+        mv_constraint: String,
+        scale_type: String,
+        equave_num: u32,
+        equave_den: u32,
+        ed_bound: i32,
+        s_lower: f64,
+        s_upper: f64,
+    ) -> Result<SigSearch, JsValue> {
+        let step_sig = query.iter().map(|x| *x as usize).collect::<Vec<_>>();
+        check_ternary_sig(&step_sig).map_err(|e| e.in_field("query"))?;
+        let equave = wasm_equave(equave_num, equave_den)?;
+        check_tuning_bounds(ed_bound, s_lower, s_upper)?;
+        let filter = SigFilter {
+            lm,
+            ms,
+            s0,
+            ggs_len,
+            ggs_len_exact: wasm_option(ggs_len_constraint, "ggs_len_constraint", CONSTRAINTS)?
+                == "exactly",
+            mv,
+            mv_exact: wasm_option(mv_constraint, "mv_constraint", CONSTRAINTS)? == "exactly",
+            mos_subst: wasm_option(scale_type, "scale_type", &["mos-subst", "all-scales"])?
+                == "mos-subst",
+        };
+        Ok(Self::new(
+            &step_sig, filter, equave, ed_bound, s_lower, s_upper,
+        ))
+    }
+
+    /// Do at most `budget` units of work and return the progress as `{stage, done, total}`.
+    #[wasm_bindgen(js_name = step)]
+    pub fn js_step(&mut self, budget: usize) -> Result<JsValue, JsValue> {
+        Ok(to_value(&self.step(budget))?)
+    }
+
+    /// The result, in the format of `sig_result`, or `undefined` if the search hasn't finished.
+    #[wasm_bindgen(js_name = result)]
+    pub fn js_result(&self) -> Result<JsValue, JsValue> {
+        if self.done {
+            Ok(to_value(&self.result)?)
+        } else {
+            Ok(JsValue::UNDEFINED)
+        }
+    }
+
+    /// The tunings of the result without the profiles, or `undefined` if the search hasn't finished.
+    /// Use `page` to get the profiles a page at a time.
+    pub fn tunings(&self) -> Result<JsValue, JsValue> {
+        if self.done {
+            Ok(to_value(&SigResult {
+                profiles: vec![],
+                ji_tunings: self.result.ji_tunings.clone(),
+                ed_tunings: self.result.ed_tunings.clone(),
+                ed_vals: self.result.ed_vals.clone(),
+            })?)
+        } else {
+            Ok(JsValue::UNDEFINED)
+        }
+    }
+
+    /// A page `{profiles, offset, total}` of the profiles found so far, sorted by `sort`
+    /// (one of `"complexity"`, `"mv"`, `"chirality"`, `"lattice"`, `"guide_frames"` or `"word"`).
+    pub fn page(
+        &self,
+        offset: usize,
+        limit: usize,
+        sort: String,
+        descending: bool,
+    ) -> Result<JsValue, JsValue> {
+        let query = PageQuery {
+            offset,
+            limit,
+            sort: sort.parse().map_err(|e: Error| e.in_field("sort"))?,
+            descending,
+        };
+        Ok(to_value(&self.result.page(&query))?)
+    }
 }
 
 /// Get more JI tunings using the slow solver (shifts by 270edo commas).
@@ -623,9 +1981,54 @@ pub fn more_ji_tunings(
     s_lower: f64,
     s_upper: f64,
 ) -> Result<JsValue, JsValue> {
-    let equave = RawJiRatio::try_new(equave_num, equave_den).unwrap_or(RawJiRatio::OCTAVE);
     let step_sig = step_sig.iter().map(|x| *x as usize).collect::<Vec<_>>();
+    check_ternary_sig(&step_sig).map_err(|e| e.in_field("step_sig"))?;
+    let equave = wasm_equave(equave_num, equave_den)?;
+    check_tuning_bounds(1, s_lower, s_upper)?;
     Ok(to_value(&sig_to_ji_tunings_slow(
         &step_sig, equave, s_lower, s_upper,
     ))?)
 }
+
+/// The slow JI solver of `more_ji_tunings` as a resumable search, for the "more-sols" button.
+/// `step` returns the progress as `{stage, done, total}` and `result` returns
+/// the tunings found so far.
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct JiTuningSearch(ji::SlowSolver);
+
+#[cfg(feature = "wasm")]
+#[wasm_bindgen]
+impl JiTuningSearch {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        step_sig: Vec<u8>,
+        equave_num: u32,
+        equave_den: u32,
+        s_lower: f64,
+        s_upper: f64,
+    ) -> Result<JiTuningSearch, JsValue> {
+        let step_sig = step_sig.iter().map(|x| *x as usize).collect::<Vec<_>>();
+        check_ternary_sig(&step_sig).map_err(|e| e.in_field("step_sig"))?;
+        let equave = wasm_equave(equave_num, equave_den)?;
+        check_tuning_bounds(1, s_lower, s_upper)?;
+        let equave =
+            Monzo::try_from_ratio(equave).map_err(|e| Error::from(e).in_field("equave"))?;
+        Ok(Self(ji::SlowSolver::new(
+            &step_sig, equave, s_lower, s_upper,
+        )))
+    }
+
+    /// Try at most `budget` more choices of the first interval class and return the progress.
+    pub fn step(&mut self, budget: usize) -> Result<JsValue, JsValue> {
+        Ok(to_value(&self.0.step(budget))?)
+    }
+
+    /// The tunings found so far, in the format of `more_ji_tunings`.
+    pub fn result(&self) -> Result<JsValue, JsValue> {
+        Ok(to_value(&ji_tunings_to_strings(
+            self.0.solutions().to_vec(),
+        ))?)
+    }
+}
@@ -0,0 +1,643 @@
+//! Mapping tuned scale words onto the 128 MIDI notes.
+//!
+//! A [`KeyboardMapping`] places a mode of a scale word on a MIDI keyboard: consecutive keys
+//! play consecutive scale degrees, the root key plays the tonic of the chosen mode, and one
+//! reference key is tuned to a given frequency. From a word, a [`StepTuning`] and a mapping
+//! this module produces:
+//!
+//! - Scala keyboard mapping (`.kbm`) files, to be used together with a `.scl` file
+//! - AnaMark tuning (`.tun`) files, which give a pitch for each of the 128 MIDI notes
+//! - MIDI Tuning Standard (MTS) SysEx messages: bulk tuning dumps, single-note tuning changes
+//!   and scale/octave tunings, as raw bytes that can be saved as `.syx` files
+//!
+//! # Examples
+//!
+//! ```
+//! use ternary::midi::{KeyboardMapping, midi_note_frequencies};
+//! use ternary::tuning::StepTuning;
+//!
+//! // Diasem in 26edo with the tonic on middle C (note 60) at 440 Hz.
+//! let diasem = [0, 1, 0, 2, 0, 1, 0, 2, 0];
+//! let tuning = StepTuning::from_strings(&["4\\26", "2\\26", "1\\26"]).unwrap();
+//! let mapping = KeyboardMapping {
+//!     reference_note: 60,
+//!     reference_frequency: 440.0,
+//!     ..Default::default()
+//! };
+//! let frequencies = midi_note_frequencies(&diasem, &tuning, &mapping);
+//! // Nine keys up from the root is the octave.
+//! assert!((frequencies[69] - 880.0).abs() < 1e-9);
+//! ```
+
+use crate::tuning::StepTuning;
+use crate::words::{Letter, rotate};
+
+/// The number of MIDI notes.
+pub const MIDI_NOTE_COUNT: usize = 128;
+
+/// The frequency in Hz of MIDI note 0 in 12edo with A4 = 440 Hz.
+/// AnaMark `.tun` files give pitches in cents above this frequency.
+pub const MIDI_NOTE_0_HZ: f64 = 8.175_798_915_643_707;
+
+/// The frequency in Hz of middle C (MIDI note 60) in 12edo with A4 = 440 Hz.
+pub const MIDDLE_C_HZ: f64 = 261.625_565_300_598_6;
+
+/// A placement of a mode of a scale word on the MIDI keyboard.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyboardMapping {
+    /// The degree of the scale word the mode starts on, as in [`rotate`].
+    pub mode: usize,
+    /// The MIDI note playing the tonic of the mode.
+    pub root_note: u8,
+    /// The MIDI note with a given frequency.
+    pub reference_note: u8,
+    /// The frequency of `reference_note` in Hz.
+    pub reference_frequency: f64,
+}
+
+impl Default for KeyboardMapping {
+    /// The tonic on middle C (note 60) at its 12edo frequency with A4 = 440 Hz.
+    fn default() -> Self {
+        Self {
+            mode: 0,
+            root_note: 60,
+            reference_note: 60,
+            reference_frequency: MIDDLE_C_HZ,
+        }
+    }
+}
+
+/// The pitch of each MIDI note in cents above [`MIDI_NOTE_0_HZ`].
+pub fn midi_note_cents(
+    word: &[Letter],
+    tuning: &StepTuning,
+    mapping: &KeyboardMapping,
+) -> Vec<f64> {
+    let mode = if word.is_empty() {
+        vec![]
+    } else {
+        rotate(word, mapping.mode)
+    };
+    let degree_cents =
+        |note: u8| tuning.degree_cents(&mode, note as i32 - mapping.root_note as i32);
+    let reference_cents = 1200.0 * (mapping.reference_frequency / MIDI_NOTE_0_HZ).log2()
+        - degree_cents(mapping.reference_note);
+    (0..MIDI_NOTE_COUNT as u8)
+        .map(|note| reference_cents + degree_cents(note))
+        .collect()
+}
+
+/// The frequency of each MIDI note in Hz.
+pub fn midi_note_frequencies(
+    word: &[Letter],
+    tuning: &StepTuning,
+    mapping: &KeyboardMapping,
+) -> Vec<f64> {
+    midi_note_cents(word, tuning, mapping)
+        .into_iter()
+        .map(|cents| MIDI_NOTE_0_HZ * 2f64.powf(cents / 1200.0))
+        .collect()
+}
+
+/// The contents of a Scala `.kbm` file placing `word` under `tuning` on the keyboard as in
+/// [`tun_string`], to be used with the `.scl` file of `word` (see [`crate::scala::scl_string`]).
+///
+/// Consecutive keys are mapped to consecutive degrees of `word`, with the key of degree 0
+/// `mapping.mode` keys below the root note, so that the root note plays the tonic of the mode.
+/// The equave is mapped to the scale's size in keys.
+pub fn kbm_string(word: &[Letter], tuning: &StepTuning, mapping: &KeyboardMapping) -> String {
+    let size = word.len();
+    let mode = if size == 0 { 0 } else { mapping.mode % size };
+    let mut middle_note = mapping.root_note as i32 - mode as i32;
+    if middle_note < 0 {
+        middle_note += size as i32;
+    }
+    let mut result = format!(
+        "! Keyboard mapping for a {size}-note scale\n\
+         ! The root note plays degree {mode}, {:.6} cents above degree 0\n\
+         !\n\
+         ! Size of map:\n{size}\n\
+         ! First MIDI note number to retune:\n0\n\
+         ! Last MIDI note number to retune:\n{}\n\
+         ! Middle note where the first entry of the mapping is mapped to:\n{middle_note}\n\
+         ! Reference note for which frequency is given:\n{}\n\
+         ! Frequency to tune the above note to:\n{:.6}\n\
+         ! Scale degree to consider as formal octave:\n{size}\n\
+         ! Mapping.\n",
+        tuning.degree_cents(word, mode as i32),
+        MIDI_NOTE_COUNT - 1,
+        mapping.reference_note,
+        mapping.reference_frequency,
+    );
+    for degree in 0..size {
+        result.push_str(&format!("{degree}\n"));
+    }
+    result
+}
+
+/// The contents of an AnaMark `.tun` file (format version 200) giving the pitch of every MIDI note
+/// for `word` under `tuning`. `name` is written to the file's info section.
+pub fn tun_string(
+    word: &[Letter],
+    tuning: &StepTuning,
+    mapping: &KeyboardMapping,
+    name: &str,
+) -> String {
+    let cents = midi_note_cents(word, tuning, mapping);
+    let name = name.replace(['"', '\r', '\n'], " ");
+    let mut result = format!(
+        "; AnaMark tuning file\n\
+         [Scale Begin]\n\
+         Format= \"AnaMark-TUN\"\n\
+         FormatVersion= 200\n\
+         FormatSpecs= \"http://www.mark-henning.de/eternity/tuningspecs.html\"\n\
+         \n\
+         [Info]\n\
+         Name= \"{name}\"\n\
+         \n\
+         [Tuning]\n"
+    );
+    for (note, cents) in cents.iter().enumerate() {
+        result.push_str(&format!("note {note}= {}\n", cents.round() as i64));
+    }
+    result.push_str(&format!(
+        "\n[Exact Tuning]\nBaseFreq= {MIDI_NOTE_0_HZ:.10}\n"
+    ));
+    for (note, cents) in cents.iter().enumerate() {
+        result.push_str(&format!("note {note}= {cents:.6}\n"));
+    }
+    result.push_str("\n[Scale End]\n");
+    result
+}
+
+/// The device ID addressing every device in a SysEx message.
+pub const MTS_ALL_DEVICES: u8 = 0x7F;
+
+/// The MTS frequency data meaning "don't retune this note".
+pub const MTS_NO_CHANGE: [u8; 3] = [0x7F, 0x7F, 0x7F];
+
+/// Error type for tunings that can't be written as an MTS message.
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum BadMts {
+    /// A single-note tuning change can retune at most 127 notes.
+    #[error("can't retune {0} notes in one message (max 127)")]
+    TooManyNotes(usize),
+    /// A note number is not a MIDI note.
+    #[error("{0} is not a MIDI note")]
+    BadNote(u8),
+    /// The offset of a pitch class from 12edo is outside the range of a scale/octave tuning.
+    #[error(
+        "pitch class {pitch_class} is {cents:.3} cents from 12edo, outside the range of the message"
+    )]
+    OffsetOutOfRange { pitch_class: usize, cents: f64 },
+}
+
+/// The MTS frequency data for a pitch in cents above [`MIDI_NOTE_0_HZ`]: the 12edo note below it
+/// and the 14-bit fraction of a semitone above that note. Pitches outside the MIDI range are
+/// [`MTS_NO_CHANGE`].
+pub fn mts_frequency_bytes(cents: f64) -> [u8; 3] {
+    let units = (cents * 16384.0 / 100.0).round();
+    // The largest pitch is 7F 7F 7E, since 7F 7F 7F means no change.
+    if !(0.0..(128.0 * 16384.0 - 1.0)).contains(&units) {
+        return MTS_NO_CHANGE;
+    }
+    let units = units as u32;
+    let (semitone, fraction) = (units / 16384, units % 16384);
+    [
+        semitone as u8,
+        (fraction >> 7) as u8,
+        (fraction & 0x7F) as u8,
+    ]
+}
+
+/// The pitch in cents above [`MIDI_NOTE_0_HZ`] given by MTS frequency data,
+/// or `None` for [`MTS_NO_CHANGE`].
+pub fn decode_mts_frequency(bytes: [u8; 3]) -> Option<f64> {
+    if bytes == MTS_NO_CHANGE {
+        return None;
+    }
+    let fraction = ((bytes[1] as u32 & 0x7F) << 7) | (bytes[2] as u32 & 0x7F);
+    Some(100.0 * (bytes[0] & 0x7F) as f64 + fraction as f64 * 100.0 / 16384.0)
+}
+
+/// The MTS bulk tuning dump (non-real-time, `F0 7E <device> 08 01`) retuning all 128 notes of
+/// tuning program `program` to `word` under `tuning`. `name` is truncated or padded to 16
+/// ASCII characters. Notes whose pitch is outside the MIDI range are left unchanged.
+pub fn mts_bulk_dump(
+    word: &[Letter],
+    tuning: &StepTuning,
+    mapping: &KeyboardMapping,
+    device_id: u8,
+    program: u8,
+    name: &str,
+) -> Vec<u8> {
+    let mut message = vec![0xF0, 0x7E, device_id & 0x7F, 0x08, 0x01, program & 0x7F];
+    let name = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii() && !c.is_ascii_control() {
+                c as u8
+            } else {
+                b'?'
+            }
+        })
+        .chain(std::iter::repeat(b' '))
+        .take(16);
+    message.extend(name);
+    for cents in midi_note_cents(word, tuning, mapping) {
+        message.extend(mts_frequency_bytes(cents));
+    }
+    let checksum = message[1..].iter().fold(0, |acc, byte| acc ^ byte) & 0x7F;
+    message.push(checksum);
+    message.push(0xF7);
+    message
+}
+
+/// The MTS single-note tuning change (real-time, `F0 7F <device> 08 02`) retuning `notes` of
+/// tuning program `program` to their pitches under `word`, `tuning` and `mapping`.
+pub fn mts_single_note_change(
+    word: &[Letter],
+    tuning: &StepTuning,
+    mapping: &KeyboardMapping,
+    device_id: u8,
+    program: u8,
+    notes: &[u8],
+) -> Result<Vec<u8>, BadMts> {
+    if notes.len() > 127 {
+        return Err(BadMts::TooManyNotes(notes.len()));
+    }
+    let cents = midi_note_cents(word, tuning, mapping);
+    let mut message = vec![
+        0xF0,
+        0x7F,
+        device_id & 0x7F,
+        0x08,
+        0x02,
+        program & 0x7F,
+        notes.len() as u8,
+    ];
+    for &note in notes {
+        let pitch = cents.get(note as usize).ok_or(BadMts::BadNote(note))?;
+        message.push(note);
+        message.extend(mts_frequency_bytes(*pitch));
+    }
+    message.push(0xF7);
+    Ok(message)
+}
+
+/// The data resolution of an MTS scale/octave tuning message.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ScaleOctaveFormat {
+    /// One byte per pitch class, in whole cents from -64 to +63 (`08 08`).
+    OneByte,
+    /// Two bytes per pitch class, in units of 100/8192 cents from -100 to +100 (`08 09`).
+    TwoByte,
+}
+
+/// The MTS scale/octave tuning (non-real-time, `F0 7E <device> 08 08|09`) retuning each of the
+/// 12 pitch classes on the MIDI channels in `channels` (bit `i` is channel `i + 1`).
+///
+/// The offsets from 12edo are taken from the octave of notes 60 to 71, so the message is only
+/// exact for scales that repeat every 12 keys at the octave.
+pub fn mts_scale_octave(
+    word: &[Letter],
+    tuning: &StepTuning,
+    mapping: &KeyboardMapping,
+    device_id: u8,
+    channels: u16,
+    format: ScaleOctaveFormat,
+) -> Result<Vec<u8>, BadMts> {
+    let cents = midi_note_cents(word, tuning, mapping);
+    let sub_id = match format {
+        ScaleOctaveFormat::OneByte => 0x08,
+        ScaleOctaveFormat::TwoByte => 0x09,
+    };
+    let mut message = vec![
+        0xF0,
+        0x7E,
+        device_id & 0x7F,
+        0x08,
+        sub_id,
+        ((channels >> 14) & 0x03) as u8,
+        ((channels >> 7) & 0x7F) as u8,
+        (channels & 0x7F) as u8,
+    ];
+    for pitch_class in 0..12 {
+        let note = 60 + pitch_class;
+        let offset = cents[note] - 100.0 * note as f64;
+        let out_of_range = BadMts::OffsetOutOfRange {
+            pitch_class,
+            cents: offset,
+        };
+        match format {
+            ScaleOctaveFormat::OneByte => {
+                let value = offset.round() + 64.0;
+                if !(0.0..=127.0).contains(&value) {
+                    return Err(out_of_range);
+                }
+                message.push(value as u8);
+            }
+            ScaleOctaveFormat::TwoByte => {
+                let value = (offset * 8192.0 / 100.0).round() + 8192.0;
+                if !(0.0..=16383.0).contains(&value) {
+                    return Err(out_of_range);
+                }
+                let value = value as u16;
+                message.push((value >> 7) as u8);
+                message.push((value & 0x7F) as u8);
+            }
+        }
+    }
+    message.push(0xF7);
+    Ok(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interval::Dyad;
+    use crate::ji_ratio::RawJiRatio;
+
+    fn ionian_12edo() -> (Vec<Letter>, StepTuning) {
+        (
+            vec![0, 0, 1, 0, 0, 0, 1],
+            StepTuning::Ed {
+                steps: vec![2, 1],
+                ed: 12,
+                equave: RawJiRatio::OCTAVE,
+            },
+        )
+    }
+
+    fn assert_cents(cents: &[f64], expected: &[(usize, f64)]) {
+        for &(note, pitch) in expected {
+            assert!((cents[note] - pitch).abs() < 1e-6, "note {note}");
+        }
+    }
+
+    #[test]
+    fn test_midi_note_cents() {
+        // Ionian on C with A (degree 5) at 440 Hz gives the white keys of 12edo from note 60.
+        let (ionian, tuning) = ionian_12edo();
+        let mapping = KeyboardMapping {
+            reference_note: 65,
+            reference_frequency: 440.0,
+            ..Default::default()
+        };
+        let cents = midi_note_cents(&ionian, &tuning, &mapping);
+        assert_eq!(cents.len(), MIDI_NOTE_COUNT);
+        assert_cents(
+            &cents,
+            &[
+                (53, 4800.0),
+                (59, 5900.0),
+                (60, 6000.0),
+                (65, 6900.0),
+                (67, 7200.0),
+            ],
+        );
+        let frequencies = midi_note_frequencies(&ionian, &tuning, &mapping);
+        assert!((frequencies[60] - MIDDLE_C_HZ).abs() < 1e-9);
+
+        // Mode 3 of ionian is lydian; on F with A (degree 2) at 440 Hz.
+        let mapping = KeyboardMapping {
+            mode: 3,
+            reference_note: 62,
+            reference_frequency: 440.0,
+            ..Default::default()
+        };
+        let cents = midi_note_cents(&ionian, &tuning, &mapping);
+        assert_cents(
+            &cents,
+            &[(60, 6500.0), (63, 7100.0), (64, 7200.0), (67, 7700.0)],
+        );
+    }
+
+    #[test]
+    fn test_kbm_string() {
+        let (ionian, tuning) = ionian_12edo();
+        let kbm = kbm_string(&ionian, &tuning, &KeyboardMapping::default());
+        let lines: Vec<&str> = kbm.lines().filter(|line| !line.starts_with('!')).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "7",
+                "0",
+                "127",
+                "60",
+                "60",
+                "261.625565",
+                "7",
+                "0",
+                "1",
+                "2",
+                "3",
+                "4",
+                "5",
+                "6"
+            ]
+        );
+
+        // Read as Scala does, the .kbm with the .scl of the word tunes every key as the .tun does.
+        let diasem = [0, 1, 0, 2, 0, 1, 0, 2, 0];
+        let tuning = StepTuning::from_strings(&["4\\26", "2\\26", "1\\26"]).unwrap();
+        for (mode, root_note) in [(3, 60), (7, 2)] {
+            let mapping = KeyboardMapping {
+                mode,
+                root_note,
+                reference_note: 69,
+                reference_frequency: 440.0,
+            };
+            let kbm = kbm_string(&diasem, &tuning, &mapping);
+            let values: Vec<f64> = kbm
+                .lines()
+                .filter(|line| !line.starts_with('!'))
+                .map(|line| line.parse().unwrap())
+                .collect();
+            let (middle, reference, frequency) = (values[3] as i32, values[4] as i32, values[5]);
+            let reference_cents = 1200.0 * (frequency / MIDI_NOTE_0_HZ).log2()
+                - tuning.degree_cents(&diasem, reference - middle);
+            let tun = midi_note_cents(&diasem, &tuning, &mapping);
+            for (note, &pitch) in tun.iter().enumerate() {
+                let scala = reference_cents + tuning.degree_cents(&diasem, note as i32 - middle);
+                assert!((scala - pitch).abs() < 1e-6, "mode {mode}, note {note}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_tun_string() {
+        let (ionian, tuning) = ionian_12edo();
+        let tun = tun_string(&ionian, &tuning, &KeyboardMapping::default(), "ionian");
+        assert!(tun.contains("Name= \"ionian\""));
+        let tuning_section: Vec<&str> = tun
+            .lines()
+            .skip_while(|line| *line != "[Tuning]")
+            .skip(1)
+            .take_while(|line| !line.is_empty())
+            .collect();
+        assert_eq!(tuning_section.len(), MIDI_NOTE_COUNT);
+        // Middle C is 6000 cents above MIDI note 0, and the next key is D.
+        assert!(tun.contains("note 60= 6000\n"));
+        assert!(tun.contains("note 61= 6200\n"));
+        let exact = tun
+            .lines()
+            .skip_while(|line| *line != "[Exact Tuning]")
+            .filter(|line| line.starts_with("note "))
+            .count();
+        assert_eq!(exact, MIDI_NOTE_COUNT);
+        assert!(tun.trim_end().ends_with("[Scale End]"));
+    }
+
+    #[test]
+    fn test_mts_frequency_bytes() {
+        // Examples from the MIDI Tuning Standard.
+        assert_eq!(mts_frequency_bytes(0.0), [0x00, 0x00, 0x00]);
+        assert_eq!(mts_frequency_bytes(6900.0), [0x45, 0x00, 0x00]);
+        assert_eq!(
+            mts_frequency_bytes(6900.0 + 100.0 / 16384.0),
+            [0x45, 0x00, 0x01]
+        );
+        assert_eq!(mts_frequency_bytes(-1.0), MTS_NO_CHANGE);
+        assert_eq!(mts_frequency_bytes(12800.0), MTS_NO_CHANGE);
+        for cents in [0.0, 1234.5678, 6931.2, 12799.0] {
+            let decoded = decode_mts_frequency(mts_frequency_bytes(cents)).unwrap();
+            assert!((decoded - cents).abs() <= 100.0 / 16384.0);
+        }
+    }
+
+    /// Diasem in 26edo with the tonic on middle C.
+    fn diasem_26edo() -> (Vec<Letter>, StepTuning) {
+        (
+            vec![0, 1, 0, 2, 0, 1, 0, 2, 0],
+            StepTuning::Ed {
+                steps: vec![4, 2, 1],
+                ed: 26,
+                equave: RawJiRatio::OCTAVE,
+            },
+        )
+    }
+
+    #[test]
+    fn test_mts_bulk_dump() {
+        let (diasem, tuning) = diasem_26edo();
+        let mapping = KeyboardMapping::default();
+        let message = mts_bulk_dump(&diasem, &tuning, &mapping, MTS_ALL_DEVICES, 3, "diasem");
+        assert_eq!(message.len(), 6 + 16 + 3 * 128 + 2);
+        assert_eq!(&message[..6], &[0xF0, 0x7E, 0x7F, 0x08, 0x01, 0x03]);
+        assert_eq!(&message[6..22], b"diasem          ");
+        assert_eq!(*message.last().unwrap(), 0xF7);
+        assert!(
+            message[1..message.len() - 1]
+                .iter()
+                .all(|&byte| byte < 0x80)
+        );
+        let checksum = message[1..message.len() - 2]
+            .iter()
+            .fold(0, |acc, byte| acc ^ byte);
+        assert_eq!(message[message.len() - 2], checksum & 0x7F);
+
+        let cents = midi_note_cents(&diasem, &tuning, &mapping);
+        for (note, bytes) in message[22..22 + 3 * 128].chunks(3).enumerate() {
+            let decoded = decode_mts_frequency([bytes[0], bytes[1], bytes[2]]);
+            match decoded {
+                Some(decoded) => assert!((decoded - cents[note]).abs() <= 100.0 / 16384.0),
+                None => assert!(!(0.0..12800.0).contains(&cents[note])),
+            }
+        }
+        // Middle C is unchanged; the key above it is 4\26 higher.
+        let decode = |note: usize| {
+            let i = 22 + 3 * note;
+            decode_mts_frequency([message[i], message[i + 1], message[i + 2]]).unwrap()
+        };
+        assert!((decode(60) - 6000.0).abs() < 0.01);
+        assert!((decode(61) - 6000.0 - 4800.0 / 26.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_mts_single_note_change() {
+        let (diasem, tuning) = diasem_26edo();
+        let mapping = KeyboardMapping::default();
+        let message =
+            mts_single_note_change(&diasem, &tuning, &mapping, 0x10, 0, &[60, 69]).unwrap();
+        assert_eq!(&message[..7], &[0xF0, 0x7F, 0x10, 0x08, 0x02, 0x00, 0x02]);
+        assert_eq!(message.len(), 7 + 2 * 4 + 1);
+        assert_eq!(message[7], 60);
+        assert!(
+            (decode_mts_frequency([message[8], message[9], message[10]]).unwrap() - 6000.0).abs()
+                < 0.01
+        );
+        assert_eq!(message[11], 69);
+        // Nine keys above the tonic is the octave.
+        assert!(
+            (decode_mts_frequency([message[12], message[13], message[14]]).unwrap() - 7200.0).abs()
+                < 0.01
+        );
+        assert_eq!(
+            mts_single_note_change(&diasem, &tuning, &mapping, 0, 0, &[128]),
+            Err(BadMts::BadNote(128))
+        );
+        assert_eq!(
+            mts_single_note_change(&diasem, &tuning, &mapping, 0, 0, &[0; 128]),
+            Err(BadMts::TooManyNotes(128))
+        );
+    }
+
+    #[test]
+    fn test_mts_scale_octave() {
+        // A 5-limit JI chromatic scale on C, one letter per step.
+        let chromatic = [
+            (1, 1),
+            (16, 15),
+            (9, 8),
+            (6, 5),
+            (5, 4),
+            (4, 3),
+            (45, 32),
+            (3, 2),
+            (8, 5),
+            (5, 3),
+            (9, 5),
+            (15, 8),
+            (2, 1),
+        ]
+        .map(|(n, d)| RawJiRatio::try_new(n, d).unwrap().cents());
+        let word: Vec<Letter> = (0..12).collect();
+        let tuning = StepTuning::Cents((0..12).map(|i| chromatic[i + 1] - chromatic[i]).collect());
+        let mapping = KeyboardMapping::default();
+
+        let message = mts_scale_octave(
+            &word,
+            &tuning,
+            &mapping,
+            MTS_ALL_DEVICES,
+            0xFFFF,
+            ScaleOctaveFormat::TwoByte,
+        )
+        .unwrap();
+        assert_eq!(
+            &message[..8],
+            &[0xF0, 0x7E, 0x7F, 0x08, 0x09, 0x03, 0x7F, 0x7F]
+        );
+        assert_eq!(message.len(), 8 + 24 + 1);
+        for pc in 0..12 {
+            let value = ((message[8 + 2 * pc] as u16) << 7) | message[9 + 2 * pc] as u16;
+            let offset = (value as f64 - 8192.0) * 100.0 / 8192.0;
+            assert!((offset - (chromatic[pc] - 100.0 * pc as f64)).abs() <= 100.0 / 8192.0);
+        }
+        // On channel 1 only, with E = 5/4 about 14 cents flat of 12edo.
+        let message =
+            mts_scale_octave(&word, &tuning, &mapping, 0, 0b1, ScaleOctaveFormat::OneByte).unwrap();
+        assert_eq!(&message[5..8], &[0x00, 0x00, 0x01]);
+        assert_eq!(message.len(), 8 + 12 + 1);
+        assert_eq!(message[8] as i32 - 64, 0);
+        assert_eq!(message[8 + 4] as i32 - 64, -14);
+
+        // A 26edo scale strays too far from 12edo for the one-byte format.
+        let (diasem, tuning) = diasem_26edo();
+        assert!(matches!(
+            mts_scale_octave(&diasem, &tuning, &mapping, 0, 1, ScaleOctaveFormat::OneByte),
+            Err(BadMts::OffsetOutOfRange { .. })
+        ));
+    }
+}
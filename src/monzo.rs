@@ -47,10 +47,11 @@ use std::iter::Sum;
 use std::ops::Index;
 
 use itertools::Itertools;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 
 use crate::helpers::{bezout, is_sorted_strictly_desc};
 use crate::interval::{Dyad, JiRatio};
-use crate::ji_ratio::RawJiRatio;
+use crate::ji_ratio::{BadJiArith, RawJiRatio};
 use crate::primes::{SMALL_PRIMES, SMALL_PRIMES_COUNT, factorize, log_primes};
 use crate::vector::{Vector, Vectorf64};
 
@@ -100,15 +101,19 @@ macro_rules! const_monzo {
 }
 
 /// Error type for attempts to construct invalid monzos.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
 pub enum CantMakeMonzo {
     /// The numerator exceeded `SMALL_PRIMES`-prime limit (contains a prime factor > largest SMALL_PRIME).
+    #[error("numerator has prime factors beyond the prime limit: {0:?}")]
     NumerExceededPrimeLimit(Vec<u32>),
     /// The denominator exceeded `SMALL_PRIMES`-prime limit (contains a prime factor > largest SMALL_PRIME).
+    #[error("denominator has prime factors beyond the prime limit: {0:?}")]
     DenomExceededPrimeLimit(Vec<u32>),
     /// The numerator was 0 (invalid for JI ratios).
+    #[error("numerator can't be zero")]
     NumerCantBeZero,
     /// The denominator was 0 (invalid for JI ratios).
+    #[error("denominator can't be zero")]
     DenomCantBeZero,
 }
 
@@ -165,6 +170,11 @@ impl Monzo {
     pub fn from_slice(slice: &[i32]) -> Self {
         Self(Vector::from_slice(slice))
     }
+    /// Divide every exponent by `coeff`, rounding toward zero.
+    /// Returns an error instead of panicking if `coeff == 0`.
+    pub fn try_div(self, coeff: i32) -> Result<Monzo, BadJiArith> {
+        Ok(Monzo(self.0.try_div(coeff)?))
+    }
     /// Whether the monzo represents an interval with positive logarithmic size (ratio > 1/1).
     pub fn is_positive(self) -> bool {
         self.cents() > 0.0
@@ -266,9 +276,17 @@ impl Monzo {
         Monzo::try_new(r.numer(), r.denom())
     }
     /// Attempt to convert a monzo into a JI ratio.
+    /// Returns `None` if the numerator or denominator does not fit in a `u32`.
     pub fn try_to_ratio(&self) -> Option<RawJiRatio> {
-        let numer = self.numer();
-        let denom = self.denom();
+        let (numer, denom) =
+            (0..SMALL_PRIMES_COUNT).try_fold((1u32, 1u32), |(numer, denom), i| {
+                let power = SMALL_PRIMES[i].checked_pow(self[i].unsigned_abs())?;
+                if self[i] > 0 {
+                    Some((numer.checked_mul(power)?, denom))
+                } else {
+                    Some((numer, denom.checked_mul(power)?))
+                }
+            })?;
         RawJiRatio::try_new(numer, denom).ok()
     }
     /// Whether all entries of a monzo are divisible by `rhs`.
@@ -342,6 +360,57 @@ impl std::fmt::Display for Monzo {
     }
 }
 
+/// Serialized as its array of prime exponents up to the last nonzero one, so 3/2 is `[-1, 1]`.
+impl Serialize for Monzo {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let exponents = self.0.as_array();
+        let len = exponents
+            .iter()
+            .rposition(|&exp| exp != 0)
+            .map_or(0, |i| i + 1);
+        exponents[..len].serialize(serializer)
+    }
+}
+
+/// Deserialized from an array of at most [`SMALL_PRIMES_COUNT`] prime exponents,
+/// or from a ratio string such as `"3/2"`.
+impl<'de> Deserialize<'de> for Monzo {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MonzoVisitor;
+
+        impl<'de> de::Visitor<'de> for MonzoVisitor {
+            type Value = Monzo;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(
+                    f,
+                    "at most {SMALL_PRIMES_COUNT} prime exponents or a ratio such as \"3/2\""
+                )
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Monzo, A::Error> {
+                let mut exponents = [0; SMALL_PRIMES_COUNT];
+                let mut len = 0;
+                while let Some(exp) = seq.next_element()? {
+                    if len == SMALL_PRIMES_COUNT {
+                        return Err(de::Error::invalid_length(len + 1, &self));
+                    }
+                    exponents[len] = exp;
+                    len += 1;
+                }
+                Ok(Monzo::from_array(exponents))
+            }
+
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<Monzo, E> {
+                let ratio: RawJiRatio = s.parse().map_err(E::custom)?;
+                Monzo::try_from_ratio(ratio).map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(MonzoVisitor)
+    }
+}
+
 impl Ord for Monzo {
     fn cmp(&self, other: &Self) -> Ordering {
         (self.ln()).total_cmp(&other.ln()) // efficient definition
@@ -614,6 +683,18 @@ mod tests {
     use super::*;
     use crate::ji_ratio::RawJiRatio;
 
+    #[test]
+    fn test_serde() {
+        let comma = monzo![-4, 4, -1];
+        assert_eq!(serde_json::to_string(&comma).unwrap(), "[-4,4,-1]");
+        assert_eq!(serde_json::to_string(&Monzo::UNISON).unwrap(), "[]");
+        assert_eq!(serde_json::from_str::<Monzo>("[-4,4,-1]").unwrap(), comma);
+        assert_eq!(serde_json::from_str::<Monzo>(r#""81/80""#).unwrap(), comma);
+        // Too many primes, or a ratio beyond the prime limit
+        assert!(serde_json::from_str::<Monzo>("[0,0,0,0,0,0,0,0,0,1]").is_err());
+        assert!(serde_json::from_str::<Monzo>(r#""29/1""#).is_err());
+    }
+
     #[test]
     fn test_monzo_macro() {
         assert_eq!(monzo![], Monzo::UNISON);
@@ -671,6 +752,28 @@ mod tests {
         let monzo_81_80 = monzo![-4, 4, -1];
         let result_ratio = monzo_81_80.try_to_ratio();
         assert_eq!(result_ratio, Some(RawJiRatio::try_new(81, 80).unwrap()));
+        // Too large for a `u32` numerator or denominator.
+        assert_eq!(monzo![-50, 32].try_to_ratio(), None);
+        assert_eq!(monzo![0, 0, 0, 0, 0, 0, 0, 0, -8].try_to_ratio(), None);
+    }
+
+    #[test]
+    fn test_try_div_and_rd() {
+        let comma = Monzo::from_slice(&[-4, 4, -1]);
+        assert_eq!(comma.try_div(2), Ok(Monzo::from_slice(&[-2, 2, 0])));
+        assert_eq!(comma.try_div(0), Err(BadJiArith::DivByZero));
+        assert_eq!(
+            Monzo::PYTH_5TH.try_rd(Monzo::UNISON),
+            Err(BadJiArith::LogDivByUnison)
+        );
+        assert_eq!(
+            Monzo::PYTH_5TH.stack(Monzo::OCTAVE).try_rd(Monzo::OCTAVE),
+            Ok(Monzo::PYTH_5TH)
+        );
+        assert_eq!(
+            RawJiRatio::PYTH_5TH.try_rd(RawJiRatio::UNISON),
+            Err(BadJiArith::LogDivByUnison)
+        );
     }
 
     #[test]
@@ -0,0 +1,309 @@
+//! Ternary scales known by name.
+//!
+//! [`NAMED_SCALES`] lists scales with an established name, such as diasem and Zarlino, each
+//! keyed by its brightest word and its equave. The brightest word keeps the chirality of a scale,
+//! so the two mirror images of a chiral scale are separate entries. An entry may have aliases
+//! and a reference JI tuning, given as the pitches of one of its modes.
+//!
+//! [`scale_name`] finds the entry of any mode of a word, [`find_scale`] the entry with a given
+//! name or alias, [`search_scales`] all entries whose names or aliases contain a string, and
+//! [`scales_with_sig`] all entries with a given step signature.
+//! Profiles report the name of their scale in [`ScaleProfile::name`](crate::ScaleProfile::name).
+//!
+//! Only ternary scales are listed. Eurybia ([`RawJiRatio::EURYBIA`]) is deliberately left out:
+//! nearly all of its steps are different sizes, starting 23/22, 25/23, 26/25, so it has no word
+//! in three step sizes to key an entry by.
+//!
+//! # Examples
+//!
+//! ```
+//! use ternary::ji_ratio::RawJiRatio;
+//! use ternary::named::{find_scale, scale_name, scales_with_sig, search_scales};
+//! use ternary::string_to_numbers;
+//!
+//! let zarlino = scale_name(&string_to_numbers("LmsLmLs"), RawJiRatio::OCTAVE).unwrap();
+//! assert_eq!(zarlino.name, "zarlino");
+//! assert_eq!(zarlino.word, "LmLsLms");
+//! assert_eq!(find_scale("Intense Diatonic"), Some(zarlino));
+//!
+//! let reference = zarlino.reference.as_ref().unwrap();
+//! assert_eq!(reference.pitches[1], RawJiRatio::try_new(5, 4).unwrap());
+//!
+//! let names: Vec<&str> = search_scales("lino").iter().map(|scale| scale.name).collect();
+//! assert_eq!(names, ["zarlino", "archylino"]);
+//!
+//! let names: Vec<&str> = scales_with_sig(&[5, 2, 2], RawJiRatio::OCTAVE)
+//!     .iter()
+//!     .map(|scale| scale.name)
+//!     .collect();
+//! assert_eq!(names, ["diasem", "left-handed diasem"]);
+//! ```
+
+use serde::Serialize;
+use serde::ser::SerializeStruct;
+
+use crate::ji_ratio::RawJiRatio;
+use crate::words::{Chirality, Letter, least_mode};
+use crate::{numbers_to_string, string_to_numbers, word_to_sig};
+
+/// A JI tuning of a named scale, as the pitches of one of its modes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ReferenceTuning {
+    /// the mode the pitches are of, in the letters `L`, `m` and `s`
+    pub mode: &'static str,
+    /// the pitches above the tonic, ending with the equave
+    pub pitches: &'static [RawJiRatio],
+}
+
+/// A scale known by name.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NamedScale {
+    /// the name, in lowercase
+    pub name: &'static str,
+    /// other names of the scale, in lowercase
+    pub aliases: &'static [&'static str],
+    /// brightest word
+    pub word: &'static str,
+    /// chirality of the word
+    pub chirality: Chirality,
+    /// the equave
+    pub equave: RawJiRatio,
+    /// a JI tuning the scale is known for, if any
+    pub reference: Option<ReferenceTuning>,
+}
+
+impl NamedScale {
+    /// Whether `name` is the name or an alias of this scale, ignoring case.
+    pub fn is_called(&self, name: &str) -> bool {
+        std::iter::once(&self.name)
+            .chain(self.aliases)
+            .any(|known| known.eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// Named scales are serialized with the equave and the reference pitches as ratio strings.
+impl Serialize for NamedScale {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("NamedScale", 6)?;
+        state.serialize_field("name", self.name)?;
+        state.serialize_field("aliases", self.aliases)?;
+        state.serialize_field("word", self.word)?;
+        state.serialize_field("chirality", &self.chirality)?;
+        state.serialize_field("equave", &self.equave.to_string())?;
+        state.serialize_field(
+            "reference",
+            &self.reference.map(|reference| {
+                (
+                    reference.mode,
+                    reference
+                        .pitches
+                        .iter()
+                        .map(|pitch| pitch.to_string())
+                        .collect::<Vec<_>>(),
+                )
+            }),
+        )?;
+        state.end()
+    }
+}
+
+/// The named scales, by number of notes.
+pub const NAMED_SCALES: &[NamedScale] = &[
+    NamedScale {
+        name: "zarlino",
+        aliases: &["ptolemy's intense diatonic", "intense diatonic"],
+        word: "LmLsLms",
+        chirality: Chirality::Right,
+        equave: RawJiRatio::OCTAVE,
+        reference: Some(ReferenceTuning {
+            mode: "LmsLmLs",
+            pitches: &RawJiRatio::ZARLINO,
+        }),
+    },
+    NamedScale {
+        name: "archylino",
+        aliases: &["septal zarlino"],
+        word: "LmsmLsm",
+        chirality: Chirality::Left,
+        equave: RawJiRatio::OCTAVE,
+        reference: Some(ReferenceTuning {
+            mode: "mLsmLms",
+            pitches: &RawJiRatio::ARCHYLINO,
+        }),
+    },
+    NamedScale {
+        name: "diasem",
+        aliases: &["right-handed diasem"],
+        word: "LLmLsLmLs",
+        chirality: Chirality::Right,
+        equave: RawJiRatio::OCTAVE,
+        reference: Some(ReferenceTuning {
+            mode: "LmLsLmLsL",
+            pitches: &RawJiRatio::TAS_9,
+        }),
+    },
+    NamedScale {
+        name: "left-handed diasem",
+        aliases: &[],
+        word: "LLsLmLsLm",
+        chirality: Chirality::Left,
+        equave: RawJiRatio::OCTAVE,
+        reference: None,
+    },
+    NamedScale {
+        name: "blackdye",
+        aliases: &[],
+        word: "LLsmLsLmLs",
+        chirality: Chirality::Left,
+        equave: RawJiRatio::OCTAVE,
+        reference: Some(ReferenceTuning {
+            mode: "LsmLsLmLsL",
+            pitches: &RawJiRatio::BLACKDYE,
+        }),
+    },
+];
+
+/// The named scale that `word`, in any mode, is with the equave `equave`, if any.
+pub fn scale_name(word: &[Letter], equave: RawJiRatio) -> Option<&'static NamedScale> {
+    let brightest = numbers_to_string(&least_mode(word));
+    NAMED_SCALES
+        .iter()
+        .find(|scale| scale.word == brightest && scale.equave == equave)
+}
+
+/// The named scale with the name or alias `name`, ignoring case.
+pub fn find_scale(name: &str) -> Option<&'static NamedScale> {
+    NAMED_SCALES.iter().find(|scale| scale.is_called(name))
+}
+
+/// The named scales whose names or aliases contain `query`, ignoring case,
+/// with exact matches first and otherwise in the order of [`NAMED_SCALES`].
+pub fn search_scales(query: &str) -> Vec<&'static NamedScale> {
+    let query = query.trim().to_lowercase();
+    let mut matches: Vec<&NamedScale> = NAMED_SCALES
+        .iter()
+        .filter(|scale| {
+            std::iter::once(&scale.name)
+                .chain(scale.aliases)
+                .any(|known| known.contains(&query))
+        })
+        .collect();
+    matches.sort_by_key(|scale| !scale.is_called(&query));
+    matches
+}
+
+/// The named scales with the step signature `step_sig` and the equave `equave`,
+/// in the order of [`NAMED_SCALES`].
+pub fn scales_with_sig(step_sig: &[usize], equave: RawJiRatio) -> Vec<&'static NamedScale> {
+    NAMED_SCALES
+        .iter()
+        .filter(|scale| {
+            scale.equave == equave && word_to_sig(&string_to_numbers(scale.word)) == step_sig
+        })
+        .collect()
+}
+
+/// The name of the scale with brightest word `brightest` with the equave `equave`, if any,
+/// for [`ScaleProfile::name`](crate::ScaleProfile::name).
+pub(crate) fn profile_name(brightest: &str, equave: RawJiRatio) -> Option<String> {
+    scale_name(&string_to_numbers(brightest), equave).map(|scale| scale.name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interval::Dyad;
+    use crate::word_to_profile;
+    use itertools::Itertools;
+
+    #[test]
+    fn test_entries_are_consistent() {
+        for scale in NAMED_SCALES {
+            let word = string_to_numbers(scale.word);
+            assert_eq!(numbers_to_string(&least_mode(&word)), scale.word);
+            assert_eq!(word_to_profile(&word).chirality, scale.chirality);
+            assert_eq!(scale.name, scale.name.to_lowercase());
+            // no two entries share a key or a name
+            assert_eq!(scale_name(&word, scale.equave), Some(scale));
+            assert_eq!(find_scale(scale.name), Some(scale));
+            for alias in scale.aliases {
+                assert_eq!(find_scale(alias), Some(scale));
+            }
+
+            let Some(reference) = scale.reference else {
+                continue;
+            };
+            let mode = string_to_numbers(reference.mode);
+            assert_eq!(least_mode(&mode), word);
+            assert_eq!(reference.pitches.len(), mode.len());
+            assert_eq!(reference.pitches.last(), Some(&scale.equave));
+            // Each letter is a single step size, and L > m > s.
+            let mut sizes = [None; 3];
+            let mut previous = RawJiRatio::UNISON;
+            for (&letter, &pitch) in mode.iter().zip(reference.pitches) {
+                let step = pitch / previous;
+                assert_eq!(*sizes[letter].get_or_insert(step), step);
+                previous = pitch;
+            }
+            let [l, m, s] = sizes.map(|size| size.unwrap().cents());
+            assert!(l > m && m > s);
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        let diasem = string_to_numbers("LmLsLmLsL");
+        assert_eq!(
+            scale_name(&diasem, RawJiRatio::OCTAVE).map(|scale| scale.name),
+            Some("diasem")
+        );
+        assert_eq!(scale_name(&diasem, RawJiRatio::TRITAVE), None);
+        assert_eq!(word_to_profile(&diasem).name.as_deref(), Some("diasem"));
+        let tritave = crate::word_to_result(&diasem, RawJiRatio::TRITAVE, 20, 20.0, 250.0);
+        assert_eq!(tritave.profile.name, None);
+        let reversed: Vec<Letter> = diasem.iter().rev().copied().collect();
+        assert_eq!(
+            scale_name(&reversed, RawJiRatio::OCTAVE).map(|scale| scale.name),
+            Some("left-handed diasem")
+        );
+        assert_eq!(
+            find_scale(" Right-Handed Diasem").map(|scale| scale.word),
+            Some("LLmLsLmLs")
+        );
+        assert_eq!(find_scale("diatonic"), None);
+
+        let names: Vec<&str> = search_scales("DIASEM")
+            .iter()
+            .map(|scale| scale.name)
+            .collect();
+        assert_eq!(names, ["diasem", "left-handed diasem"]);
+        assert!(search_scales("xyz").is_empty());
+
+        let names: Vec<&str> = scales_with_sig(&[3, 2, 2], RawJiRatio::OCTAVE)
+            .iter()
+            .map(|scale| scale.name)
+            .collect();
+        assert_eq!(names, ["zarlino"]);
+        assert!(scales_with_sig(&[5, 2, 2], RawJiRatio::TRITAVE).is_empty());
+        assert!(scales_with_sig(&[2, 2, 3], RawJiRatio::OCTAVE).is_empty());
+        for scale in NAMED_SCALES {
+            let step_sig = word_to_sig(&string_to_numbers(scale.word));
+            assert!(scales_with_sig(&step_sig, scale.equave).contains(&scale));
+        }
+    }
+
+    #[test]
+    fn test_eurybia_is_not_ternary() {
+        let steps = crate::ji::step_form(&RawJiRatio::EURYBIA);
+        assert!(steps.iter().sorted().dedup().count() > 3);
+    }
+
+    #[test]
+    fn test_serialize() {
+        let json = serde_json::to_value(find_scale("archylino").unwrap()).unwrap();
+        assert_eq!(json["equave"], "2/1");
+        assert_eq!(json["chirality"], "Left");
+        assert_eq!(json["reference"][0], "mLsmLms");
+        assert_eq!(json["reference"][1][1], "9/7");
+    }
+}
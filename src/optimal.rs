@@ -0,0 +1,455 @@
+//! Optimal tunings of regular temperaments.
+//!
+//! Given a [`Temperament`], this module finds tunings of its generators in cents that
+//! minimize some measure of error against JI:
+//!
+//! - **TE** (Tenney-Euclidean): minimizes the Euclidean norm of the Tenney-weighted errors of the primes.
+//! - **CTE** (constrained TE): TE subject to a pure equave.
+//! - **POTE** (pure-octave TE): TE scaled so that the octave is pure.
+//! - **Minimax**: minimizes the largest error over an odd-limit tonality diamond, with a pure equave.
+//!
+//! The resulting [`OptimalTuning`] can then tune any interval of the temperament,
+//! in particular the steps of a scale, giving a cents [`StepTuning`] for the word.
+//! [`tempered_step_tuning`] goes all the way from a scale's JI tuning and commas to tempered step sizes.
+//!
+//! # Examples
+//!
+//! ```
+//! use ternary::monzo::Monzo;
+//! use ternary::optimal::{TuningMethod, optimal_tuning};
+//! use ternary::temperament::Temperament;
+//!
+//! let meantone = Temperament::from_commas(&[Monzo::try_new(81, 80).unwrap()], 3).unwrap();
+//! // Minimax over the 5-odd-limit is quarter-comma meantone.
+//! let tuning = optimal_tuning(&meantone, &TuningMethod::Minimax { odd: 5, equave: Monzo::OCTAVE }).unwrap();
+//! assert!((tuning.cents(Monzo::PYTH_5TH) - 696.578).abs() < 0.001);
+//! assert!((tuning.cents(Monzo::OCTAVE) - 1200.0).abs() < 1e-9);
+//! ```
+
+use itertools::Itertools;
+
+use crate::Error;
+use crate::interval::Dyad;
+use crate::ji::odd_limit;
+use crate::monzo::Monzo;
+use crate::primes::SMALL_PRIMES;
+use crate::temperament::Temperament;
+use crate::tuning::StepTuning;
+
+/// The criterion an optimal tuning minimizes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TuningMethod {
+    /// Tenney-Euclidean tuning.
+    Te,
+    /// Tenney-Euclidean tuning with `equave` pure.
+    Cte { equave: Monzo },
+    /// Tenney-Euclidean tuning scaled to make the octave pure.
+    Pote,
+    /// Minimax tuning over the `odd`-odd-limit tonality diamond with `equave` pure.
+    Minimax { odd: u32, equave: Monzo },
+}
+
+/// Error type for optimal tuning failures.
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum NoOptimalTuning {
+    /// The temperament has rank 0, so there is nothing to tune.
+    #[error("a rank-0 temperament has no generators to tune")]
+    RankZero,
+    /// The temperament tempers out the equave that should be pure.
+    #[error("the temperament tempers out the equave")]
+    EquaveTemperedOut,
+    /// No interval of the odd limit is in the temperament's prime limit.
+    #[error("no odd-limit interval is in the prime limit")]
+    EmptyDiamond,
+}
+
+/// A tuning of a temperament's generators.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OptimalTuning {
+    /// The sizes in cents of the generators of the temperament's canonical mapping.
+    pub generators: Vec<f64>,
+    /// The tempered sizes in cents of the primes in the temperament's prime limit.
+    pub tuning_map: Vec<f64>,
+}
+
+impl OptimalTuning {
+    fn new(temperament: &Temperament, generators: Vec<f64>) -> Self {
+        let mapping = temperament.mapping();
+        let tuning_map = (0..temperament.dim())
+            .map(|i| {
+                generators
+                    .iter()
+                    .zip(&mapping)
+                    .map(|(g, val)| g * val.into_inner()[i] as f64)
+                    .sum()
+            })
+            .collect();
+        Self {
+            generators,
+            tuning_map,
+        }
+    }
+
+    /// The tempered size of `monzo` in cents. Primes beyond the prime limit are ignored.
+    pub fn cents(&self, monzo: Monzo) -> f64 {
+        self.tuning_map
+            .iter()
+            .enumerate()
+            .map(|(i, c)| c * monzo[i] as f64)
+            .sum()
+    }
+
+    /// The tempered tuning of a scale whose JI step sizes are `steps`.
+    pub fn step_tuning(&self, steps: &[Monzo]) -> StepTuning {
+        StepTuning::Cents(steps.iter().map(|&step| self.cents(step)).collect())
+    }
+
+    /// The largest absolute error in cents over the `odd`-odd-limit tonality diamond,
+    /// counting only intervals in the tuning's prime limit.
+    pub fn odd_limit_max_error(&self, odd: u32) -> f64 {
+        diamond(odd, self.tuning_map.len())
+            .into_iter()
+            .map(|q| (self.cents(q) - q.cents()).abs())
+            .fold(0.0, f64::max)
+    }
+
+    /// The root-mean-square error in cents over the `odd`-odd-limit tonality diamond,
+    /// counting only intervals in the tuning's prime limit.
+    pub fn odd_limit_rms_error(&self, odd: u32) -> f64 {
+        let intervals = diamond(odd, self.tuning_map.len());
+        if intervals.is_empty() {
+            return 0.0;
+        }
+        let sum_sq: f64 = intervals
+            .iter()
+            .map(|&q| (self.cents(q) - q.cents()).powi(2))
+            .sum();
+        (sum_sq / intervals.len() as f64).sqrt()
+    }
+}
+
+/// The intervals of the `odd`-odd-limit tonality diamond (within the octave) in the `dim`-prime limit.
+fn diamond(odd: u32, dim: usize) -> Vec<Monzo> {
+    odd_limit(odd)
+        .into_iter()
+        .filter_map(|r| Monzo::try_from_ratio(r).ok())
+        .filter(|m| m.into_inner().iter().skip(dim).all(|&x| x == 0))
+        .collect()
+}
+
+/// Solve the linear system `a x = b` by Gaussian elimination with partial pivoting.
+/// Returns `None` if `a` is (numerically) singular.
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-9 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let (upper, lower) = a.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for (offset, row) in lower.iter_mut().enumerate() {
+            let factor = row[col] / pivot_row[col];
+            for (x, p) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                *x -= factor * p;
+            }
+            b[col + 1 + offset] -= factor * b[col];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+/// The mapping as `f64` rows, each restricted to the prime limit.
+fn mapping_rows(temperament: &Temperament) -> Vec<Vec<f64>> {
+    temperament
+        .mapping()
+        .iter()
+        .map(|val| {
+            val.into_inner()
+                .iter()
+                .take(temperament.dim())
+                .map(|&x| x as f64)
+                .collect()
+        })
+        .collect()
+}
+
+/// The generator counts `temperament` maps `monzo` to, as `f64`s.
+fn mapped(temperament: &Temperament, monzo: Monzo) -> Vec<f64> {
+    temperament.map(monzo).into_iter().map(f64::from).collect()
+}
+
+/// The normal equations of the Tenney-weighted least squares problem, `(V V^T, V j)`,
+/// where `V` is the Tenney-weighted mapping and `j` the weighted JI point (1200 for every prime).
+fn te_normal_equations(temperament: &Temperament) -> (Vec<Vec<f64>>, Vec<f64>) {
+    let weighted: Vec<Vec<f64>> = mapping_rows(temperament)
+        .into_iter()
+        .map(|row| {
+            row.iter()
+                .zip(SMALL_PRIMES)
+                .map(|(x, p)| x / (p as f64).log2())
+                .collect()
+        })
+        .collect();
+    let gram = weighted
+        .iter()
+        .map(|u| {
+            weighted
+                .iter()
+                .map(|v| u.iter().zip(v).map(|(a, b)| a * b).sum())
+                .collect()
+        })
+        .collect();
+    let rhs = weighted
+        .iter()
+        .map(|u| 1200.0 * u.iter().sum::<f64>())
+        .collect();
+    (gram, rhs)
+}
+
+fn te_generators(temperament: &Temperament) -> Option<Vec<f64>> {
+    let (gram, rhs) = te_normal_equations(temperament);
+    solve(gram, rhs)
+}
+
+fn cte_generators(temperament: &Temperament, equave: Monzo) -> Option<Vec<f64>> {
+    // Lagrange multipliers: [[V V^T, c], [c^T, 0]] [g; λ] = [V j; cents(equave)]
+    let (mut gram, mut rhs) = te_normal_equations(temperament);
+    let c = mapped(temperament, equave);
+    for (row, ci) in gram.iter_mut().zip(&c) {
+        row.push(*ci);
+    }
+    gram.push([c, vec![0.0]].concat());
+    rhs.push(equave.cents());
+    let mut solution = solve(gram, rhs)?;
+    solution.pop();
+    Some(solution)
+}
+
+fn minimax_generators(temperament: &Temperament, odd: u32, equave: Monzo) -> Option<Vec<f64>> {
+    let rank = temperament.rank();
+    let intervals: Vec<(Vec<f64>, f64)> = diamond(odd, temperament.dim())
+        .into_iter()
+        .map(|q| (mapped(temperament, q), q.cents()))
+        .collect();
+    if intervals.is_empty() {
+        return None;
+    }
+    let equave_row = mapped(temperament, equave);
+    let max_error = |g: &[f64]| {
+        intervals
+            .iter()
+            .map(|(row, cents)| (row.iter().zip(g).map(|(a, b)| a * b).sum::<f64>() - cents).abs())
+            .fold(0.0, f64::max)
+    };
+    if rank == 1 {
+        // Only the equave constraint: the generator is determined.
+        return solve(vec![equave_row], vec![equave.cents()]);
+    }
+    // The optimum is at a vertex where `rank` intervals besides the pure equave
+    // have errors of equal magnitude `e`. Solve for [g; e] at every such vertex.
+    let mut best: Option<(f64, Vec<f64>)> = None;
+    for active in (0..intervals.len()).combinations(rank) {
+        for signs in (0..rank).map(|_| [1.0, -1.0]).multi_cartesian_product() {
+            let mut a = vec![[equave_row.clone(), vec![0.0]].concat()];
+            let mut b = vec![equave.cents()];
+            for (&i, sign) in active.iter().zip(&signs) {
+                let (row, cents) = &intervals[i];
+                a.push([row.clone(), vec![-sign]].concat());
+                b.push(*cents);
+            }
+            if let Some(mut g) = solve(a, b) {
+                g.pop();
+                let error = max_error(&g);
+                if best.as_ref().is_none_or(|(e, _)| error < *e - 1e-12) {
+                    best = Some((error, g));
+                }
+            }
+        }
+    }
+    // If every vertex is degenerate, fall back to CTE.
+    best.map(|(_, g)| g)
+        .or_else(|| cte_generators(temperament, equave))
+}
+
+/// An optimal tuning of `temperament` under `method`.
+pub fn optimal_tuning(
+    temperament: &Temperament,
+    method: &TuningMethod,
+) -> Result<OptimalTuning, NoOptimalTuning> {
+    if temperament.rank() == 0 {
+        return Err(NoOptimalTuning::RankZero);
+    }
+    let generators = match method {
+        TuningMethod::Te => te_generators(temperament),
+        TuningMethod::Pote if temperament.map(Monzo::OCTAVE).iter().all(|&x| x == 0) => {
+            return Err(NoOptimalTuning::EquaveTemperedOut);
+        }
+        TuningMethod::Pote => te_generators(temperament).map(|g| {
+            let octave = OptimalTuning::new(temperament, g.clone()).cents(Monzo::OCTAVE);
+            g.into_iter().map(|x| x * 1200.0 / octave).collect()
+        }),
+        TuningMethod::Cte { equave } | TuningMethod::Minimax { equave, .. }
+            if temperament.map(*equave).iter().all(|&x| x == 0) =>
+        {
+            return Err(NoOptimalTuning::EquaveTemperedOut);
+        }
+        TuningMethod::Cte { equave } => cte_generators(temperament, *equave),
+        TuningMethod::Minimax { odd, equave } => {
+            if diamond(*odd, temperament.dim()).is_empty() {
+                return Err(NoOptimalTuning::EmptyDiamond);
+            }
+            minimax_generators(temperament, *odd, *equave)
+        }
+    };
+    // The canonical mapping has full rank, so the systems above are nonsingular.
+    let generators = generators.expect("the canonical mapping should have full rank");
+    Ok(OptimalTuning::new(temperament, generators))
+}
+
+/// The step sizes of a scale with JI steps `steps` under the `method`-optimal tuning of the temperament
+/// they represent once `commas` are tempered out (see [`Temperament::from_step_tuning`]).
+///
+/// # Examples
+///
+/// ```
+/// use ternary::monzo::Monzo;
+/// use ternary::optimal::{TuningMethod, tempered_step_tuning};
+/// use ternary::tuning::StepTuning;
+///
+/// // Diasem with L = 9/8, m = 28/27, s = 64/63 in POTE archytas
+/// let StepTuning::Ji(diasem) = StepTuning::from_strings(&["9/8", "28/27", "64/63"]).unwrap() else {
+///     unreachable!()
+/// };
+/// let archytas = Monzo::try_new(64, 63).unwrap();
+/// let steps = tempered_step_tuning(&diasem, &[archytas], &TuningMethod::Pote).unwrap();
+/// assert!(steps.step_cents()[2].abs() < 1e-9);
+/// ```
+pub fn tempered_step_tuning(
+    steps: &[Monzo],
+    commas: &[Monzo],
+    method: &TuningMethod,
+) -> Result<StepTuning, Error> {
+    let temperament = Temperament::from_step_tuning(steps, commas)?;
+    Ok(optimal_tuning(&temperament, method)?.step_tuning(steps))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meantone() -> Temperament {
+        Temperament::from_commas(&[Monzo::try_new(81, 80).unwrap()], 3).unwrap()
+    }
+
+    #[test]
+    fn test_te_and_pote() {
+        let te = optimal_tuning(&meantone(), &TuningMethod::Te).unwrap();
+        assert!((te.cents(Monzo::OCTAVE) - 1200.0).abs() > 0.1);
+        let pote = optimal_tuning(&meantone(), &TuningMethod::Pote).unwrap();
+        assert!((pote.cents(Monzo::OCTAVE) - 1200.0).abs() < 1e-9);
+        assert!((pote.cents(Monzo::PYTH_5TH) - 696.239).abs() < 0.001);
+        // The syntonic comma is tempered out.
+        assert!(pote.cents(monzo![-4, 4, -1]).abs() < 1e-9);
+        // POTE can't make a tempered-out octave pure.
+        let no_octave = Temperament::from_commas(&[Monzo::OCTAVE], 3).unwrap();
+        assert!(optimal_tuning(&no_octave, &TuningMethod::Te).is_ok());
+        assert_eq!(
+            optimal_tuning(&no_octave, &TuningMethod::Pote),
+            Err(NoOptimalTuning::EquaveTemperedOut)
+        );
+    }
+
+    #[test]
+    fn test_cte() {
+        let cte = optimal_tuning(
+            &meantone(),
+            &TuningMethod::Cte {
+                equave: Monzo::OCTAVE,
+            },
+        )
+        .unwrap();
+        assert!((cte.cents(Monzo::OCTAVE) - 1200.0).abs() < 1e-9);
+        assert!((cte.cents(Monzo::PYTH_5TH) - 697.214).abs() < 0.001);
+        // Tritave-equivalent CTE
+        let tritave = monzo![0, 1];
+        let cte3 = optimal_tuning(&meantone(), &TuningMethod::Cte { equave: tritave }).unwrap();
+        assert!((cte3.cents(tritave) - tritave.cents()).abs() < 1e-9);
+        assert_eq!(
+            optimal_tuning(
+                &Temperament::from_commas(&[Monzo::OCTAVE], 3).unwrap(),
+                &TuningMethod::Cte {
+                    equave: Monzo::OCTAVE
+                }
+            ),
+            Err(NoOptimalTuning::EquaveTemperedOut)
+        );
+    }
+
+    #[test]
+    fn test_minimax() {
+        let minimax = optimal_tuning(
+            &meantone(),
+            &TuningMethod::Minimax {
+                odd: 5,
+                equave: Monzo::OCTAVE,
+            },
+        )
+        .unwrap();
+        let quarter_comma = monzo![0, 0, 1].cents() / 4.0;
+        assert!((minimax.cents(Monzo::PYTH_5TH) - quarter_comma).abs() < 1e-6);
+        assert!(minimax.odd_limit_max_error(5) <= minimax.odd_limit_rms_error(5) * 2.0);
+        let pote = optimal_tuning(&meantone(), &TuningMethod::Pote).unwrap();
+        assert!(minimax.odd_limit_max_error(5) <= pote.odd_limit_max_error(5));
+    }
+
+    #[test]
+    fn test_step_tuning() {
+        // Zarlino in meantone: L and m become equal.
+        let zarlino = [monzo![-3, 2], monzo![1, -2, 1], monzo![4, -1, -1]];
+        let pote = optimal_tuning(&meantone(), &TuningMethod::Pote).unwrap();
+        let StepTuning::Cents(steps) = pote.step_tuning(&zarlino) else {
+            panic!("expected a cents tuning");
+        };
+        assert!((steps[0] - steps[1]).abs() < 1e-9);
+        let word = [0, 1, 2, 0, 1, 0, 2];
+        assert!((pote.step_tuning(&zarlino).equave_cents(&word) - 1200.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_tempered_step_tuning() {
+        // Word, then JI tuning, then temperament, then optimal step sizes
+        let word = crate::string_to_numbers("LmsLmLs");
+        let tuning = StepTuning::from_strings(&["9/8", "10/9", "16/15"]).unwrap();
+        tuning.check_word(&word).unwrap();
+        let StepTuning::Ji(steps) = tuning else {
+            panic!("expected a JI tuning");
+        };
+        let syntonic = Monzo::try_new(81, 80).unwrap();
+        let cte = TuningMethod::Cte {
+            equave: Monzo::OCTAVE,
+        };
+        let tempered = tempered_step_tuning(&steps, &[syntonic], &cte).unwrap();
+        let cents = tempered.step_cents();
+        // In CTE meantone, L = m is a whole tone of two fifths minus an octave.
+        assert!((cents[0] - cents[1]).abs() < 1e-9);
+        assert!((cents[0] - (2.0 * 697.214 - 1200.0)).abs() < 0.01);
+        assert!((tempered.equave_cents(&word) - 1200.0).abs() < 1e-9);
+        // Without commas, the tuning stays just.
+        let just = tempered_step_tuning(&steps, &[], &cte).unwrap();
+        for (tempered, ji) in just.step_cents().iter().zip(&steps) {
+            assert!((tempered - ji.cents()).abs() < 1e-9);
+        }
+        assert_eq!(
+            tempered_step_tuning(&steps, &[Monzo::OCTAVE], &cte),
+            Err(Error::NoOptimalTuning(NoOptimalTuning::EquaveTemperedOut))
+        );
+    }
+}
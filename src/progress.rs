@@ -0,0 +1,232 @@
+//! Progress reporting and cancellation for long searches.
+//!
+//! Enumerating the scales of a large step signature or running the slow JI solver can take
+//! many seconds. The searches that can take that long come in two forms:
+//!
+//! - a `run` method taking a [`Monitor`], which receives a [`Progress`] after every chunk of
+//!   work and stops the search with [`Cancelled`] once its [`CancelToken`] is cancelled;
+//! - a `step` method doing at most a given amount of work and returning, so that the caller
+//!   can resume the search later. This is how the WASM API runs searches without blocking
+//!   the page: JavaScript calls `step` in a loop, yielding to the event loop in between,
+//!   and aborts a search by no longer calling it.
+//!
+//! See [`SigSearch`](crate::SigSearch) and [`SlowSolver`](crate::ji::SlowSolver).
+//!
+//! # Examples
+//!
+//! ```
+//! use ternary::progress::{CancelToken, Cancelled, Monitor, Stage};
+//! use ternary::{SigFilter, SigSearch};
+//! use ternary::ji_ratio::RawJiRatio;
+//!
+//! let search = SigSearch::new(&[5, 2, 2], SigFilter::default(), RawJiRatio::OCTAVE, 53, 20.0, 250.0);
+//! let token = CancelToken::new();
+//! let mut stages = vec![];
+//! let mut monitor = Monitor::new()
+//!     .on_progress(|progress| {
+//!         if progress.stage == Stage::Filter && progress.done >= 10 {
+//!             token.cancel();
+//!         }
+//!         stages.push(progress.stage);
+//!     })
+//!     .cancel_token(token.clone());
+//! assert_eq!(search.run(&mut monitor), Err(Cancelled));
+//! drop(monitor);
+//! assert_eq!(stages.first(), Some(&Stage::Enumerate));
+//! assert_eq!(stages.last(), Some(&Stage::Filter));
+//! ```
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+/// The stage a search is at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    /// Enumerating the scales of a step signature.
+    Enumerate,
+    /// Filtering the scales and computing their profiles.
+    Filter,
+    /// Computing the JI and ED tunings of a step signature.
+    Tunings,
+    /// Running a JI tuning solver.
+    Solve,
+    /// The search has finished.
+    Done,
+}
+
+/// How far a search has got.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Progress {
+    /// the current stage
+    pub stage: Stage,
+    /// the units of work done in the current stage
+    pub done: usize,
+    /// the units of work in the current stage, if known in advance
+    pub total: Option<usize>,
+}
+
+impl Progress {
+    /// The progress of a finished search.
+    pub const DONE: Self = Self {
+        stage: Stage::Done,
+        done: 0,
+        total: None,
+    };
+
+    /// Whether the search has finished.
+    pub fn is_done(&self) -> bool {
+        self.stage == Stage::Done
+    }
+}
+
+/// Error type for searches stopped by a [`CancelToken`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("the search was cancelled")]
+pub struct Cancelled;
+
+/// A flag for cancelling a search, possibly from another thread.
+/// Clones share the same flag.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// A token that hasn't been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Cancel every search watching this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+    /// Whether [`cancel`](CancelToken::cancel) has been called on this token or a clone of it.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A progress callback and a cancellation token for a search; both are optional.
+#[derive(Default)]
+pub struct Monitor<'a> {
+    callback: Option<Box<dyn FnMut(Progress) + 'a>>,
+    token: Option<CancelToken>,
+}
+
+impl<'a> Monitor<'a> {
+    /// A monitor that ignores progress and never cancels.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Call `callback` with the progress of the search after every chunk of work.
+    pub fn on_progress(mut self, callback: impl FnMut(Progress) + 'a) -> Self {
+        self.callback = Some(Box::new(callback));
+        self
+    }
+    /// Stop the search once `token` is cancelled.
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+    /// Pass `progress` to the callback, then check the token.
+    pub fn report(&mut self, progress: Progress) -> Result<(), Cancelled> {
+        if let Some(callback) = &mut self.callback {
+            callback(progress);
+        }
+        self.check()
+    }
+    /// Return [`Cancelled`] if the token has been cancelled.
+    pub fn check(&self) -> Result<(), Cancelled> {
+        match &self.token {
+            Some(token) if token.is_cancelled() => Err(Cancelled),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl std::fmt::Debug for Monitor<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Monitor")
+            .field("callback", &self.callback.is_some())
+            .field("token", &self.token)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monitor() {
+        let progress = Progress {
+            stage: Stage::Solve,
+            done: 1,
+            total: Some(2),
+        };
+        assert_eq!(Monitor::new().report(progress), Ok(()));
+
+        let token = CancelToken::new();
+        let mut reports = 0;
+        let mut monitor = Monitor::new()
+            .on_progress(|_| reports += 1)
+            .cancel_token(token.clone());
+        assert_eq!(monitor.report(progress), Ok(()));
+        token.clone().cancel();
+        assert_eq!(monitor.check(), Err(Cancelled));
+        assert_eq!(monitor.report(progress), Err(Cancelled));
+        drop(monitor);
+        assert_eq!(reports, 2);
+    }
+
+    #[test]
+    fn test_serde() {
+        let progress = Progress {
+            stage: Stage::Filter,
+            done: 3,
+            total: Some(10),
+        };
+        assert_eq!(
+            serde_json::to_value(progress).unwrap(),
+            serde_json::json!({"stage": "filter", "done": 3, "total": 10})
+        );
+        assert!(Progress::DONE.is_done());
+    }
+
+    #[test]
+    fn test_cancel_enumeration() {
+        use crate::ji_ratio::RawJiRatio;
+        use crate::{SigFilter, SigSearch};
+
+        // 4620 scales, reported every 1024 found
+        let search = SigSearch::new(
+            &[6, 4, 3],
+            SigFilter::default(),
+            RawJiRatio::OCTAVE,
+            53,
+            20.0,
+            250.0,
+        );
+        let token = CancelToken::new();
+        let mut reports = vec![];
+        let mut monitor = Monitor::new()
+            .on_progress(|progress| {
+                if progress.done == 2048 {
+                    token.cancel();
+                }
+                reports.push((progress.stage, progress.done));
+            })
+            .cancel_token(token.clone());
+        assert_eq!(search.run(&mut monitor), Err(Cancelled));
+        drop(monitor);
+        assert_eq!(
+            reports,
+            vec![
+                (Stage::Enumerate, 0),
+                (Stage::Enumerate, 1024),
+                (Stage::Enumerate, 2048)
+            ]
+        );
+    }
+}
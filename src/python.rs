@@ -0,0 +1,697 @@
+//! Python bindings, enabled by the `python` feature.
+//!
+//! The extension module is built with [maturin](https://www.maturin.rs/) from the repository root:
+//!
+//! ```text
+//! maturin develop --release
+//! ```
+//!
+//! Scale words are passed as strings of step letters (`"LmLsLmLsL"`) and step signatures as lists
+//! of step counts (`[5, 2, 2]`). [`word_result`] and [`sig_result`] return plain dicts with the same
+//! keys as the JSON returned by `word_result` and `sig_result` in WASM, so notebooks can share code
+//! with the web page:
+//!
+//! ```text
+//! >>> import ternary
+//! >>> result = ternary.word_result("LmLsLmLsL")
+//! >>> result["profile"]["word"]
+//! 'LLmLsLmLs'
+//! >>> ternary.RawJiRatio(9, 8) * ternary.RawJiRatio(28, 27)
+//! RawJiRatio(7, 6)
+//! ```
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use pyo3::basic::CompareOp;
+use pyo3::exceptions::{PyOverflowError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::equal::{self, Val};
+use crate::guide::{self, GuideFrame};
+use crate::interval::{Dyad, JiRatio};
+use crate::ji;
+use crate::ji_ratio::RawJiRatio;
+use crate::monzo::Monzo;
+use crate::primes::SMALL_PRIMES_COUNT;
+use crate::words::{self, CountVector, countvector_to_slice};
+use crate::{
+    Error, SigFilter, numbers_to_string, parse_ternary_word, sig_to_result, try_string_to_numbers,
+    word_to_lattice_result, word_to_profile, word_to_result, word_to_sig,
+};
+
+/// Convert a serializable value to the Python object `json.loads` would give for its JSON.
+fn to_python<'py, T: Serialize>(py: Python<'py>, value: &T) -> PyResult<Bound<'py, PyAny>> {
+    let value = serde_json::to_value(value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    json_to_python(py, &value)
+}
+
+fn json_to_python<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    Ok(match value {
+        Value::Null => py.None().into_bound(py),
+        Value::Bool(b) => b.into_pyobject(py)?.to_owned().into_any(),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                i.into_pyobject(py)?.into_any()
+            } else if let Some(u) = n.as_u64() {
+                u.into_pyobject(py)?.into_any()
+            } else {
+                n.as_f64().unwrap_or(f64::NAN).into_pyobject(py)?.into_any()
+            }
+        }
+        Value::String(s) => s.into_pyobject(py)?.into_any(),
+        Value::Array(items) => {
+            let items = items
+                .iter()
+                .map(|item| json_to_python(py, item))
+                .collect::<PyResult<Vec<_>>>()?;
+            PyList::new(py, items)?.into_any()
+        }
+        Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, item) in map {
+                dict.set_item(key, json_to_python(py, item)?)?;
+            }
+            dict.into_any()
+        }
+    })
+}
+
+fn hash_of<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A crate error as a Python `ValueError`, prefixed with its [`code`](Error::code).
+fn value_error(e: Error) -> PyErr {
+    PyValueError::new_err(format!("{}: {e}", e.code()))
+}
+
+/// Read a scale word written in step letters, rejecting characters that aren't step letters.
+fn parse_word(word: &str) -> PyResult<Vec<usize>> {
+    try_string_to_numbers(word).map_err(value_error)
+}
+
+/// Check that a step signature is ternary, as the signature-based searches require.
+fn check_ternary_sig(step_sig: &[usize]) -> PyResult<()> {
+    crate::check_ternary_sig(step_sig).map_err(value_error)
+}
+
+fn equave_or_octave(equave: Option<PyRawJiRatio>) -> RawJiRatio {
+    equave.map_or(RawJiRatio::OCTAVE, |equave| equave.0)
+}
+
+fn equave_monzo(equave: RawJiRatio) -> PyResult<Monzo> {
+    Monzo::try_from_ratio(equave).map_err(|e| value_error(e.into()))
+}
+
+/// A JI interval as a vector of prime exponents.
+#[pyclass(name = "Monzo", module = "ternary", frozen)]
+#[derive(Clone, Copy)]
+pub struct PyMonzo(pub Monzo);
+
+#[pymethods]
+impl PyMonzo {
+    /// Create a monzo from prime exponents, starting with the exponent of 2.
+    #[new]
+    fn new(exponents: Vec<i32>) -> PyResult<Self> {
+        if exponents.len() > SMALL_PRIMES_COUNT {
+            Err(PyValueError::new_err(format!(
+                "monzos have at most {SMALL_PRIMES_COUNT} entries"
+            )))
+        } else {
+            Ok(Self(Monzo::from_slice(&exponents)))
+        }
+    }
+    /// The monzo of the ratio `numer/denom`.
+    #[staticmethod]
+    fn from_ratio(numer: u32, denom: u32) -> PyResult<Self> {
+        Monzo::try_new(numer, denom)
+            .map(Self)
+            .map_err(|e| value_error(e.into()))
+    }
+    /// The prime exponents of the monzo.
+    #[getter]
+    fn exponents(&self) -> Vec<i32> {
+        self.0.into_inner().as_array().to_vec()
+    }
+    /// The size of the interval in cents.
+    fn cents(&self) -> f64 {
+        self.0.cents()
+    }
+    /// The interval as a ratio, or `None` if it is too large for one.
+    fn ratio(&self) -> Option<PyRawJiRatio> {
+        self.0.try_to_ratio().map(PyRawJiRatio)
+    }
+    fn __add__(&self, other: &Self) -> Self {
+        Self(self.0 + other.0)
+    }
+    fn __sub__(&self, other: &Self) -> Self {
+        Self(self.0 - other.0)
+    }
+    fn __neg__(&self) -> Self {
+        Self(-self.0)
+    }
+    fn __mul__(&self, n: i32) -> Self {
+        Self(self.0 * n)
+    }
+    fn __rmul__(&self, n: i32) -> Self {
+        Self(self.0 * n)
+    }
+    fn __richcmp__(&self, other: &Self, op: CompareOp) -> bool {
+        op.matches(self.0.cmp(&other.0))
+    }
+    fn __hash__(&self) -> u64 {
+        hash_of(&self.0.into_inner())
+    }
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+    fn __repr__(&self) -> String {
+        let exponents = self.exponents();
+        let len = exponents
+            .iter()
+            .rposition(|&exponent| exponent != 0)
+            .map_or(0, |i| i + 1);
+        format!("Monzo({:?})", &exponents[..len])
+    }
+}
+
+/// A JI ratio in lowest terms.
+#[pyclass(name = "RawJiRatio", module = "ternary", frozen)]
+#[derive(Clone, Copy)]
+pub struct PyRawJiRatio(pub RawJiRatio);
+
+#[pymethods]
+impl PyRawJiRatio {
+    /// Create the ratio `numer/denom`, reduced to lowest terms.
+    #[new]
+    fn new(numer: u32, denom: u32) -> PyResult<Self> {
+        RawJiRatio::try_new(numer, denom)
+            .map(Self)
+            .map_err(|e| value_error(e.into()))
+    }
+    #[getter]
+    fn numer(&self) -> u32 {
+        self.0.numer()
+    }
+    #[getter]
+    fn denom(&self) -> u32 {
+        self.0.denom()
+    }
+    /// The size of the interval in cents.
+    fn cents(&self) -> f64 {
+        self.0.cents()
+    }
+    /// The ratio as a monzo. Raises `ValueError` for ratios with primes beyond 23.
+    fn monzo(&self) -> PyResult<PyMonzo> {
+        Monzo::try_from_ratio(self.0)
+            .map(PyMonzo)
+            .map_err(|e| value_error(e.into()))
+    }
+    fn __mul__(&self, other: &Self) -> PyResult<Self> {
+        num_traits::CheckedMul::checked_mul(&self.0, &other.0)
+            .map(Self)
+            .ok_or_else(|| PyOverflowError::new_err("ratio too large"))
+    }
+    fn __truediv__(&self, other: &Self) -> PyResult<Self> {
+        num_traits::CheckedDiv::checked_div(&self.0, &other.0)
+            .map(Self)
+            .ok_or_else(|| PyOverflowError::new_err("ratio too large"))
+    }
+    fn __richcmp__(&self, other: &Self, op: CompareOp) -> bool {
+        op.matches(self.0.cmp(&other.0))
+    }
+    fn __hash__(&self) -> u64 {
+        hash_of(&(self.0.numer(), self.0.denom()))
+    }
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+    fn __repr__(&self) -> String {
+        format!("RawJiRatio({}, {})", self.0.numer(), self.0.denom())
+    }
+}
+
+/// A val, i.e. a mapping of each prime to a number of steps of an equal temperament.
+#[pyclass(name = "Val", module = "ternary", frozen)]
+#[derive(Clone, Copy)]
+pub struct PyVal(pub Val);
+
+#[pymethods]
+impl PyVal {
+    /// Create a val from the number of steps of each prime, starting with 2.
+    #[new]
+    fn new(components: Vec<i32>) -> PyResult<Self> {
+        if components.len() > SMALL_PRIMES_COUNT {
+            Err(PyValueError::new_err(format!(
+                "vals have at most {SMALL_PRIMES_COUNT} entries"
+            )))
+        } else {
+            Ok(Self(Val::from_slice(&components)))
+        }
+    }
+    /// Read a val in wart notation such as `"17c"` over the first `dim` primes.
+    #[staticmethod]
+    fn from_wart(wart: &str, dim: usize) -> PyResult<Self> {
+        Val::from_wart(wart, dim)
+            .map(Self)
+            .map_err(|e| value_error(e.into()))
+    }
+    /// The number of steps of each prime.
+    #[getter]
+    fn components(&self) -> Vec<i32> {
+        self.0.into_inner().as_array().to_vec()
+    }
+    /// The number of steps `monzo` is mapped to.
+    fn evaluate(&self, monzo: &PyMonzo) -> i32 {
+        self.0.evaluate(monzo.0)
+    }
+    /// The val in wart notation.
+    fn wart_name(&self) -> String {
+        self.0.wart_name()
+    }
+    /// Whether the val is consistent in the `odd`-odd-limit.
+    fn is_consistent(&self, odd: u32) -> bool {
+        self.0.is_consistent(odd)
+    }
+    /// The L2 error of the val's mapping of the `odd`-odd-limit.
+    fn odd_limit_l2_error(&self, odd: u32) -> f64 {
+        self.0.odd_limit_l2_error(odd)
+    }
+    fn __eq__(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+    fn __hash__(&self) -> u64 {
+        hash_of(&self.0)
+    }
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+    fn __repr__(&self) -> String {
+        format!("Val({:?})", self.components())
+    }
+}
+
+/// A guide frame of a scale word; see [`GuideFrame`].
+#[pyclass(name = "GuideFrame", module = "ternary", frozen)]
+#[derive(Clone)]
+pub struct PyGuideFrame {
+    frame: GuideFrame,
+    /// The number of step sizes of the scale, used as the length of step count lists.
+    arity: usize,
+}
+
+impl PyGuideFrame {
+    fn step_counts(&self, vectors: &[CountVector<usize>]) -> Vec<Vec<i32>> {
+        vectors
+            .iter()
+            .map(|v| {
+                let mut counts = countvector_to_slice(v.clone());
+                counts.resize(self.arity.max(counts.len()), 0);
+                counts
+            })
+            .collect()
+    }
+}
+
+#[pymethods]
+impl PyGuideFrame {
+    /// The generator sequence, each generator as a list of step counts.
+    #[getter]
+    fn gs(&self) -> Vec<Vec<i32>> {
+        self.step_counts(&self.frame.gs)
+    }
+    /// The offsets of the interleaved generator sequences, as lists of step counts.
+    #[getter]
+    fn offset_chord(&self) -> Vec<Vec<i32>> {
+        self.step_counts(&self.frame.offset_chord)
+    }
+    #[getter]
+    fn complexity(&self) -> usize {
+        self.frame.complexity()
+    }
+    #[getter]
+    fn multiplicity(&self) -> usize {
+        self.frame.multiplicity()
+    }
+    fn __eq__(&self, other: &Self) -> bool {
+        self.frame == other.frame
+    }
+    fn __hash__(&self) -> u64 {
+        hash_of(&self.frame)
+    }
+    fn __repr__(&self) -> String {
+        format!(
+            "GuideFrame(gs={:?}, offset_chord={:?})",
+            self.gs(),
+            self.offset_chord()
+        )
+    }
+}
+
+/// The brightest mode of a scale word.
+#[pyfunction]
+fn brightest_mode(word: &str) -> PyResult<String> {
+    Ok(numbers_to_string(&words::least_mode(&parse_word(word)?)))
+}
+
+/// The maximum variety of a scale word.
+#[pyfunction]
+fn maximum_variety(word: &str) -> PyResult<usize> {
+    Ok(words::maximum_variety(&parse_word(word)?))
+}
+
+/// The chirality of a scale word: `"Left"`, `"Achiral"` or `"Right"`.
+#[pyfunction]
+fn chirality<'py>(py: Python<'py>, word: &str) -> PyResult<Bound<'py, PyAny>> {
+    to_python(py, &words::chirality(&parse_word(word)?))
+}
+
+/// The step signature of a ternary scale word, as counts of L, m and s.
+#[pyfunction]
+fn step_signature(word: &str) -> PyResult<Vec<usize>> {
+    Ok(word_to_sig(&parse_word(word)?))
+}
+
+/// The MOS substitution scales of a ternary step signature.
+#[pyfunction]
+fn mos_substitution_scales(py: Python<'_>, step_sig: Vec<usize>) -> PyResult<Vec<String>> {
+    check_ternary_sig(&step_sig)?;
+    Ok(py.detach(|| {
+        words::mos_substitution_scales(&step_sig)
+            .iter()
+            .map(|scale| numbers_to_string(scale))
+            .collect()
+    }))
+}
+
+/// The profile of a scale word as a dict.
+#[pyfunction]
+fn scale_profile<'py>(py: Python<'py>, word: &str) -> PyResult<Bound<'py, PyAny>> {
+    to_python(py, &word_to_profile(&parse_word(word)?))
+}
+
+/// The guide frames of a scale word, simplest first.
+#[pyfunction]
+fn guide_frames(word: &str) -> PyResult<Vec<PyGuideFrame>> {
+    let word = parse_word(word)?;
+    let arity = word.iter().max().map_or(0, |&letter| letter + 1);
+    Ok(guide::guide_frames(&word)
+        .into_iter()
+        .map(|frame| PyGuideFrame { frame, arity })
+        .collect())
+}
+
+/// The pitch class lattice of a scale word as a dict with `coordinates` and `basis`,
+/// or `None` if the word has no unimodular basis.
+#[pyfunction]
+fn lattice<'py>(py: Python<'py>, word: &str) -> PyResult<Bound<'py, PyAny>> {
+    to_python(py, &word_to_lattice_result(&parse_word(word)?))
+}
+
+/// JI tunings of a ternary step signature with steps between `s_lower` and `s_upper` cents.
+/// `slow` also searches tunings the fast solver misses.
+#[pyfunction]
+#[pyo3(signature = (step_sig, equave=None, s_lower=20.0, s_upper=250.0, slow=false))]
+fn ji_tunings(
+    py: Python<'_>,
+    step_sig: Vec<usize>,
+    equave: Option<PyRawJiRatio>,
+    s_lower: f64,
+    s_upper: f64,
+    slow: bool,
+) -> PyResult<Vec<Vec<PyMonzo>>> {
+    check_ternary_sig(&step_sig)?;
+    let equave = equave_monzo(equave_or_octave(equave))?;
+    let solutions = py.detach(|| {
+        if slow {
+            ji::solve_step_sig_slow(&step_sig, equave, s_lower, s_upper)
+        } else {
+            ji::solve_step_sig_fast(&step_sig, equave, s_lower, s_upper)
+        }
+    });
+    Ok(solutions
+        .into_iter()
+        .map(|steps| steps.into_iter().map(PyMonzo).collect())
+        .collect())
+}
+
+/// The ratios of the `limit`-odd-limit.
+#[pyfunction]
+fn odd_limit(limit: u32) -> Vec<PyRawJiRatio> {
+    ji::odd_limit(limit).into_iter().map(PyRawJiRatio).collect()
+}
+
+/// ED tunings of a ternary step signature as step counts of L, m and s, up to `ed_bound` steps
+/// per equave and with the small step between `s_lower` and `s_upper` cents.
+#[pyfunction]
+#[pyo3(signature = (step_sig, equave=None, ed_bound=111, s_lower=20.0, s_upper=250.0))]
+fn ed_tunings(
+    py: Python<'_>,
+    step_sig: Vec<usize>,
+    equave: Option<PyRawJiRatio>,
+    ed_bound: i32,
+    s_lower: f64,
+    s_upper: f64,
+) -> PyResult<Vec<Vec<i32>>> {
+    check_ternary_sig(&step_sig)?;
+    let equave = equave_or_octave(equave);
+    Ok(py.detach(|| equal::ed_tunings_for_ternary(&step_sig, equave, ed_bound, s_lower, s_upper)))
+}
+
+/// The val of `edo` with the least error in the `odd`-odd-limit.
+#[pyfunction]
+fn best_val(edo: i32, odd: u32) -> PyVal {
+    PyVal(equal::best_val(edo, odd))
+}
+
+/// The generalized patent val of an ED with `edo` steps to the octave.
+#[pyfunction]
+fn gpval(edo: f64) -> PyVal {
+    PyVal(equal::gpval(edo))
+}
+
+/// The profile and tunings of a ternary scale word as a dict, as `word_result` returns in WASM.
+#[pyfunction]
+#[pyo3(signature = (word, equave=None, ed_bound=111, s_lower=20.0, s_upper=250.0))]
+fn word_result<'py>(
+    py: Python<'py>,
+    word: &str,
+    equave: Option<PyRawJiRatio>,
+    ed_bound: i32,
+    s_lower: f64,
+    s_upper: f64,
+) -> PyResult<Bound<'py, PyAny>> {
+    let word = parse_ternary_word(word).map_err(value_error)?;
+    let equave = equave_or_octave(equave);
+    let result = py.detach(|| word_to_result(&word, equave, ed_bound, s_lower, s_upper));
+    to_python(py, &result)
+}
+
+/// The profiles of the scales of a ternary step signature that pass the given filters, and the
+/// tunings of the step signature, as a dict, as `sig_result` returns in WASM.
+#[pyfunction]
+#[pyo3(signature = (
+    step_sig, equave=None, ed_bound=111, s_lower=20.0, s_upper=250.0, *,
+    lm=false, ms=false, s0=false, ggs_len=0, ggs_len_exact=false, mv=0, mv_exact=false,
+    mos_subst=false,
+))]
+#[allow(clippy::too_many_arguments)]
+fn sig_result<'py>(
+    py: Python<'py>,
+    step_sig: Vec<usize>,
+    equave: Option<PyRawJiRatio>,
+    ed_bound: i32,
+    s_lower: f64,
+    s_upper: f64,
+    lm: bool,
+    ms: bool,
+    s0: bool,
+    ggs_len: u8,
+    ggs_len_exact: bool,
+    mv: u8,
+    mv_exact: bool,
+    mos_subst: bool,
+) -> PyResult<Bound<'py, PyAny>> {
+    check_ternary_sig(&step_sig)?;
+    let filter = SigFilter {
+        lm,
+        ms,
+        s0,
+        ggs_len,
+        ggs_len_exact,
+        mv,
+        mv_exact,
+        mos_subst,
+    };
+    let equave = equave_or_octave(equave);
+    let result =
+        py.detach(|| sig_to_result(&step_sig, &filter, equave, ed_bound, s_lower, s_upper));
+    to_python(py, &result)
+}
+
+/// The `ternary` Python module.
+#[pymodule]
+#[pyo3(name = "ternary")]
+fn ternary_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyMonzo>()?;
+    m.add_class::<PyRawJiRatio>()?;
+    m.add_class::<PyVal>()?;
+    m.add_class::<PyGuideFrame>()?;
+    m.add_function(wrap_pyfunction!(brightest_mode, m)?)?;
+    m.add_function(wrap_pyfunction!(maximum_variety, m)?)?;
+    m.add_function(wrap_pyfunction!(chirality, m)?)?;
+    m.add_function(wrap_pyfunction!(step_signature, m)?)?;
+    m.add_function(wrap_pyfunction!(mos_substitution_scales, m)?)?;
+    m.add_function(wrap_pyfunction!(scale_profile, m)?)?;
+    m.add_function(wrap_pyfunction!(guide_frames, m)?)?;
+    m.add_function(wrap_pyfunction!(lattice, m)?)?;
+    m.add_function(wrap_pyfunction!(ji_tunings, m)?)?;
+    m.add_function(wrap_pyfunction!(odd_limit, m)?)?;
+    m.add_function(wrap_pyfunction!(ed_tunings, m)?)?;
+    m.add_function(wrap_pyfunction!(best_val, m)?)?;
+    m.add_function(wrap_pyfunction!(gpval, m)?)?;
+    m.add_function(wrap_pyfunction!(word_result, m)?)?;
+    m.add_function(wrap_pyfunction!(sig_result, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_module<F: FnOnce(Python<'_>, &Bound<'_, PyModule>)>(f: F) {
+        Python::initialize();
+        Python::attach(|py| {
+            let module = PyModule::new(py, "ternary").unwrap();
+            ternary_module(&module).unwrap();
+            f(py, &module);
+        });
+    }
+
+    #[test]
+    fn test_word_result_shape() {
+        with_module(|py, module| {
+            let result = module
+                .getattr("word_result")
+                .unwrap()
+                .call1(("LmLsLmLsL",))
+                .unwrap();
+            let profile = result.get_item("profile").unwrap();
+            assert_eq!(
+                profile
+                    .get_item("word")
+                    .unwrap()
+                    .extract::<String>()
+                    .unwrap(),
+                "LLmLsLmLs"
+            );
+            assert_eq!(
+                profile
+                    .get_item("chirality")
+                    .unwrap()
+                    .extract::<String>()
+                    .unwrap(),
+                "Right"
+            );
+            assert_eq!(
+                profile
+                    .get_item("ed_join")
+                    .unwrap()
+                    .extract::<Vec<i32>>()
+                    .unwrap()
+                    .len(),
+                3
+            );
+            for (word, message) in [
+                ("LxL", "invalid_word: `LxL` is not a valid scale word"),
+                ("LsLLs", "invalid_word: `LsLLs` is not a valid scale word"),
+            ] {
+                let error = module
+                    .getattr("word_result")
+                    .unwrap()
+                    .call1((word,))
+                    .unwrap_err();
+                assert_eq!(error.value(py).to_string(), message);
+            }
+        });
+    }
+
+    #[test]
+    fn test_sig_result_filters() {
+        with_module(|py, module| {
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("mv", 3).unwrap();
+            let result = module
+                .getattr("sig_result")
+                .unwrap()
+                .call((vec![3, 2, 2],), Some(&kwargs))
+                .unwrap();
+            assert_eq!(result.get_item("profiles").unwrap().len().unwrap(), 2);
+            let error = module
+                .getattr("sig_result")
+                .unwrap()
+                .call1((vec![5, 2, 0],))
+                .unwrap_err();
+            assert_eq!(
+                error.value(py).to_string(),
+                "invalid_sig: [5, 2, 0] is not a valid step signature"
+            );
+        });
+    }
+
+    #[test]
+    fn test_tuning_bounds() {
+        with_module(|py, module| {
+            // Every function bounding the small step spells the bounds `s_lower` and `s_upper`.
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("s_lower", 30.0).unwrap();
+            kwargs.set_item("s_upper", 60.0).unwrap();
+            for function in ["ji_tunings", "ed_tunings", "sig_result"] {
+                assert!(
+                    module
+                        .getattr(function)
+                        .unwrap()
+                        .call((vec![5, 2, 2],), Some(&kwargs))
+                        .is_ok(),
+                    "{function}"
+                );
+            }
+            let ed_tunings: Vec<Vec<i32>> = module
+                .getattr("ed_tunings")
+                .unwrap()
+                .call((vec![5, 2, 2],), Some(&kwargs))
+                .unwrap()
+                .extract()
+                .unwrap();
+            assert!(ed_tunings.contains(&vec![4, 2, 1]));
+            assert!(!ed_tunings.contains(&vec![7, 2, 1]));
+        });
+    }
+
+    #[test]
+    fn test_classes() {
+        with_module(|py, _| {
+            let step = Bound::new(py, PyRawJiRatio::new(9, 8).unwrap()).unwrap();
+            let other = Bound::new(py, PyRawJiRatio::new(28, 27).unwrap()).unwrap();
+            let product = step.mul(&other).unwrap();
+            assert_eq!(product.repr().unwrap().to_string(), "RawJiRatio(7, 6)");
+            assert_eq!(
+                PyMonzo::new(vec![-3, 2]).unwrap().__repr__(),
+                "Monzo([-3, 2])"
+            );
+            let val = best_val(12, 5);
+            assert_eq!(val.evaluate(&PyMonzo::new(vec![-1, 1]).unwrap()), 7);
+            assert_eq!(val.wart_name(), "12p");
+            let frames = guide_frames("LmLsLmLsL").unwrap();
+            assert_eq!(frames[0].complexity(), 2);
+            assert_eq!(frames[0].gs(), vec![vec![1, 1, 0], vec![1, 0, 1]]);
+            assert_eq!(frames[0].offset_chord(), vec![vec![0, 0, 0]]);
+        });
+    }
+}
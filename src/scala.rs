@@ -0,0 +1,455 @@
+//! Scala `.scl` files: export of scale words under a tuning, and import of `.scl` files
+//! back into scale words.
+//!
+//! The [Scala scale file format](https://www.huygens-fokker.org/scala/scl_format.html) is a
+//! plain text format with the following rules:
+//!
+//! - Lines starting with `!` are comments.
+//! - The first non-comment line is a one-line description of the scale (possibly empty).
+//! - The second non-comment line is the number of notes.
+//! - Each following non-comment line is a pitch above the tonic. The tonic 1/1 is implicit
+//!   and the last pitch is the interval of equivalence.
+//! - A pitch containing a `.` is in cents; otherwise it is a ratio `n/d` or a whole number `n`.
+//!
+//! # Examples
+//!
+//! ```
+//! use ternary::scala::scl_string;
+//! use ternary::tuning::StepTuning;
+//! use ternary::monzo;
+//!
+//! // Zarlino (LmsLmLs) with L = 9/8, m = 10/9, s = 16/15
+//! let zarlino = [0, 1, 2, 0, 1, 0, 2];
+//! let tuning = StepTuning::Ji(vec![monzo![-3, 2], monzo![1, -2, 1], monzo![4, -1, -1]]);
+//! let scl = scl_string(&zarlino, &tuning, "zarlino", "Zarlino's intense diatonic");
+//! let pitches: Vec<&str> = scl.lines().skip(4).map(str::trim).collect();
+//! assert_eq!(pitches, ["9/8", "5/4", "4/3", "3/2", "5/3", "15/8", "2/1"]);
+//!
+//! // Reading the file back recovers the word and its step sizes.
+//! let (word, steps) = ternary::scala::scl_to_word(&scl, 0.0).unwrap();
+//! assert_eq!(word, zarlino);
+//! assert_eq!(steps.len(), 3);
+//! ```
+
+use crate::interval::{Dyad, JiRatio};
+use crate::ji::step_form;
+use crate::ji_ratio::RawJiRatio;
+use crate::monzo::Monzo;
+use crate::tuning::StepTuning;
+use crate::words::Letter;
+
+/// The numerator and denominator of a monzo, or `None` if either doesn't fit in a `u32`.
+pub(crate) fn checked_ratio(monzo: Monzo) -> Option<(u32, u32)> {
+    monzo
+        .try_to_ratio()
+        .map(|ratio| (ratio.numer(), ratio.denom()))
+}
+
+/// Write a pitch in cents the way Scala reads it, i.e. always with a decimal point.
+fn cents_line(cents: f64) -> String {
+    format!("{cents:.6}")
+}
+
+/// The pitch lines of `word` under `tuning`, excluding the tonic and ending with the equave.
+///
+/// JI pitches are written as ratios, falling back to cents if a ratio is too large for Scala.
+/// ED pitches are written as cents, except that a pitch of a whole number of equaves is written
+/// as the equave ratio raised to that power.
+pub fn scl_pitches(word: &[Letter], tuning: &StepTuning) -> Vec<String> {
+    let cents = tuning.pitches_cents(word);
+    match tuning {
+        StepTuning::Ji(_) => tuning
+            .pitches_monzo(word)
+            .expect("a JI tuning gives every pitch a monzo")
+            .into_iter()
+            .zip(cents)
+            .map(|(monzo, cents)| match checked_ratio(monzo) {
+                Some((numer, denom)) => format!("{numer}/{denom}"),
+                None => cents_line(cents),
+            })
+            .collect(),
+        StepTuning::Ed { steps, ed, equave } => word
+            .iter()
+            .scan(0, |acc, &letter| {
+                *acc += steps[letter];
+                Some(*acc)
+            })
+            .zip(cents)
+            .map(|(count, cents)| {
+                let equave_monzo = Monzo::try_from_ratio(*equave).ok();
+                match equave_monzo {
+                    Some(monzo) if *ed != 0 && count > 0 && count % ed == 0 => {
+                        checked_ratio(monzo * (count / ed))
+                            .map(|(numer, denom)| format!("{numer}/{denom}"))
+                            .unwrap_or_else(|| cents_line(cents))
+                    }
+                    _ => cents_line(cents),
+                }
+            })
+            .collect(),
+        StepTuning::Cents(_) => cents.into_iter().map(cents_line).collect(),
+    }
+}
+
+/// The contents of a Scala `.scl` file for `word` under `tuning`.
+///
+/// `name` is used for the file name comment and `description` for the description line.
+/// Line breaks in `description` are replaced by spaces, and a description that would be
+/// read as a comment is prefixed with a space.
+pub fn scl_string(word: &[Letter], tuning: &StepTuning, name: &str, description: &str) -> String {
+    let mut description = description.replace(['\r', '\n'], " ");
+    if description.starts_with('!') {
+        description.insert(0, ' ');
+    }
+    let pitches = scl_pitches(word, tuning);
+    let mut result = format!("! {name}.scl\n!\n{description}\n {}\n", pitches.len());
+    for pitch in pitches {
+        result.push(' ');
+        result.push_str(&pitch);
+        result.push('\n');
+    }
+    result
+}
+
+/// Error type for `.scl` files that can't be read, or can't be read as a scale word.
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum BadScl {
+    /// The file has no description line.
+    #[error("missing description line")]
+    MissingDescription,
+    /// The file has no note count line.
+    #[error("missing note count line")]
+    MissingNoteCount,
+    /// The note count is not a nonnegative integer.
+    #[error("invalid note count `{0}`")]
+    BadNoteCount(String),
+    /// A pitch is neither a ratio nor a number of cents.
+    #[error("invalid pitch `{0}`")]
+    BadPitch(String),
+    /// The number of pitch lines differs from the note count.
+    #[error("expected {expected} pitches but found {found}")]
+    WrongPitchCount { expected: usize, found: usize },
+    /// The file has no pitches.
+    #[error("the scale has no pitches")]
+    Empty,
+    /// A step of the scale is not ascending.
+    #[error("step {} is not ascending", .0 + 1)]
+    NonAscendingStep(usize),
+    /// The steps fall into this many size classes instead of 2 or 3.
+    #[error("the scale has {0} step size(s); only 2- or 3-step-size scales are supported")]
+    StepSizeCount(usize),
+}
+
+/// A pitch line of a `.scl` file.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SclPitch {
+    /// A pitch written as a ratio or a whole number.
+    Ratio(RawJiRatio),
+    /// A pitch written in cents.
+    Cents(f64),
+}
+
+impl SclPitch {
+    /// The size of the pitch in cents.
+    pub fn cents(&self) -> f64 {
+        match self {
+            Self::Ratio(ratio) => ratio.cents(),
+            Self::Cents(cents) => *cents,
+        }
+    }
+}
+
+/// The contents of a `.scl` file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SclFile {
+    /// The description line.
+    pub description: String,
+    /// The pitches above the tonic, ending with the equave.
+    pub pitches: Vec<SclPitch>,
+}
+
+/// Read a pitch line. Anything after the first whitespace-separated token is a comment.
+fn parse_pitch(line: &str) -> Result<SclPitch, BadScl> {
+    let token = line.split_whitespace().next().unwrap_or("");
+    let bad = || BadScl::BadPitch(token.to_string());
+    if token.contains('.') {
+        token
+            .parse::<f64>()
+            .ok()
+            .filter(|cents| cents.is_finite())
+            .map(SclPitch::Cents)
+            .ok_or_else(bad)
+    } else {
+        let (numer, denom) = token.split_once('/').unwrap_or((token, "1"));
+        match (numer.parse::<u32>(), denom.parse::<u32>()) {
+            (Ok(numer), Ok(denom)) => RawJiRatio::try_new(numer, denom)
+                .map(SclPitch::Ratio)
+                .map_err(|_| bad()),
+            _ => Err(bad()),
+        }
+    }
+}
+
+/// Parse the contents of a `.scl` file.
+pub fn parse_scl(contents: &str) -> Result<SclFile, BadScl> {
+    let mut lines = contents
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.starts_with('!'));
+    let description = lines
+        .next()
+        .ok_or(BadScl::MissingDescription)?
+        .trim()
+        .to_string();
+    let note_count = lines.next().ok_or(BadScl::MissingNoteCount)?;
+    let note_count = note_count
+        .split_whitespace()
+        .next()
+        .and_then(|count| count.parse::<usize>().ok())
+        .ok_or_else(|| BadScl::BadNoteCount(note_count.trim().to_string()))?;
+    let pitches = lines
+        .filter(|line| !line.trim().is_empty())
+        .map(parse_pitch)
+        .collect::<Result<Vec<_>, _>>()?;
+    if pitches.len() != note_count {
+        return Err(BadScl::WrongPitchCount {
+            expected: note_count,
+            found: pitches.len(),
+        });
+    }
+    Ok(SclFile {
+        description,
+        pitches,
+    })
+}
+
+/// The steps of a scale given in cumulative form, in cents.
+/// Steps between two ratios are computed exactly with [`step_form`] when the quotient fits in a
+/// `RawJiRatio`; other steps are differences of cents.
+pub fn scl_step_cents(pitches: &[SclPitch]) -> Vec<f64> {
+    let pitches = [&[SclPitch::Ratio(RawJiRatio::UNISON)], pitches].concat();
+    pitches
+        .windows(2)
+        .map(|pair| match (pair[0], pair[1]) {
+            // `step_form` divides the pitches, so check that the cross products fit in a `u32`.
+            (SclPitch::Ratio(lo), SclPitch::Ratio(hi))
+                if hi.numer().checked_mul(lo.denom()).is_some()
+                    && lo.numer().checked_mul(hi.denom()).is_some() =>
+            {
+                step_form(&[lo, hi])[1].cents()
+            }
+            (lo, hi) => hi.cents() - lo.cents(),
+        })
+        .collect()
+}
+
+/// Group step sizes (in cents) into letters. Sizes within `tolerance` cents of their
+/// neighbors in sorted order get the same letter, and letter 0 is the largest size.
+/// Returns the word and the mean size of each letter.
+pub fn cluster_steps(step_cents: &[f64], tolerance: f64) -> (Vec<Letter>, Vec<f64>) {
+    let mut order: Vec<usize> = (0..step_cents.len()).collect();
+    order.sort_by(|&a, &b| step_cents[b].total_cmp(&step_cents[a]));
+    let mut word = vec![0; step_cents.len()];
+    let mut clusters: Vec<Vec<f64>> = vec![];
+    let mut prev: Option<f64> = None;
+    for i in order {
+        let size = step_cents[i];
+        if prev.is_none_or(|prev| prev - size > tolerance) {
+            clusters.push(vec![]);
+        }
+        prev = Some(size);
+        word[i] = clusters.len() - 1;
+        clusters
+            .last_mut()
+            .expect("a cluster was just pushed")
+            .push(size);
+    }
+    let sizes = clusters
+        .iter()
+        .map(|cluster| cluster.iter().sum::<f64>() / cluster.len() as f64)
+        .collect();
+    (word, sizes)
+}
+
+/// Read a `.scl` file as a 2- or 3-step-size scale word, grouping steps within `tolerance` cents
+/// of each other into the same step size.
+/// Returns the word (starting on the tonic of the file) and the size in cents of each letter.
+pub fn scl_to_word(contents: &str, tolerance: f64) -> Result<(Vec<Letter>, Vec<f64>), BadScl> {
+    let file = parse_scl(contents)?;
+    if file.pitches.is_empty() {
+        return Err(BadScl::Empty);
+    }
+    let step_cents = scl_step_cents(&file.pitches);
+    if let Some(i) = step_cents.iter().position(|&step| step <= 0.0) {
+        return Err(BadScl::NonAscendingStep(i));
+    }
+    let (word, sizes) = cluster_steps(&step_cents, tolerance);
+    if sizes.len() == 2 || sizes.len() == 3 {
+        Ok((word, sizes))
+    } else {
+        Err(BadScl::StepSizeCount(sizes.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tuning::parse_ratio;
+
+    /// The non-comment lines of a `.scl` file.
+    fn data_lines(scl: &str) -> Vec<&str> {
+        scl.lines().filter(|line| !line.starts_with('!')).collect()
+    }
+
+    #[test]
+    fn test_scl_format() {
+        let diasem = [0, 1, 0, 2, 0, 1, 0, 2, 0];
+        let tunings = [
+            StepTuning::Ji(vec![
+                monzo![-3, 2],
+                monzo![2, -3, 0, 1],
+                monzo![6, -2, 0, -1],
+            ]),
+            StepTuning::Ed {
+                steps: vec![4, 2, 1],
+                ed: 26,
+                equave: RawJiRatio::OCTAVE,
+            },
+            StepTuning::Cents(vec![200.0, 80.0, 40.0]),
+        ];
+        for tuning in tunings {
+            let scl = scl_string(&diasem, &tuning, "diasem", "diasem");
+            assert!(scl.starts_with("! diasem.scl\n"));
+            let lines = data_lines(&scl);
+            assert_eq!(lines[0], "diasem");
+            assert_eq!(lines[1].trim().parse::<usize>(), Ok(9));
+            assert_eq!(lines.len(), 11);
+            // Every pitch is either cents with a decimal point or a ratio.
+            for (pitch, cents) in lines[2..].iter().zip(tuning.pitches_cents(&diasem)) {
+                let pitch = pitch.trim();
+                if pitch.contains('.') {
+                    assert!((pitch.parse::<f64>().unwrap() - cents).abs() < 1e-5);
+                } else {
+                    let ratio = parse_ratio(pitch).unwrap();
+                    assert!((ratio.cents() - cents).abs() < 1e-5);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_scl_pitches() {
+        let diasem = [0, 1, 0, 2, 0, 1, 0, 2, 0];
+        let ji = StepTuning::Ji(vec![
+            monzo![-3, 2],
+            monzo![2, -3, 0, 1],
+            monzo![6, -2, 0, -1],
+        ]);
+        assert_eq!(
+            scl_pitches(&diasem, &ji),
+            vec![
+                "9/8", "7/6", "21/16", "4/3", "3/2", "14/9", "7/4", "16/9", "2/1"
+            ]
+        );
+        // Tempered pitches are cents; the equave stays a ratio.
+        let ed = StepTuning::Ed {
+            steps: vec![4, 2, 1],
+            ed: 26,
+            equave: RawJiRatio::OCTAVE,
+        };
+        let pitches = scl_pitches(&diasem, &ed);
+        assert_eq!(pitches[0], "184.615385");
+        assert_eq!(pitches[8], "2/1");
+        // Ratios too large for Scala are written in cents.
+        let huge = StepTuning::Ji(vec![monzo![-50, 32]]);
+        assert!(scl_pitches(&[0], &huge)[0].contains('.'));
+    }
+
+    #[test]
+    fn test_scl_description() {
+        let scl = scl_string(
+            &[0, 1],
+            &StepTuning::Cents(vec![700.0, 500.0]),
+            "x",
+            "!a\nb",
+        );
+        assert_eq!(data_lines(&scl)[0], " !a b");
+    }
+
+    #[test]
+    fn test_parse_scl() {
+        let contents =
+            "! meanmt.scl\n!\nQuarter-comma meantone\n 3\n!\n 193.157 ! a comment\n 5/4\n2\n";
+        let file = parse_scl(contents).unwrap();
+        assert_eq!(file.description, "Quarter-comma meantone");
+        assert_eq!(
+            file.pitches,
+            vec![
+                SclPitch::Cents(193.157),
+                SclPitch::Ratio(RawJiRatio::PENTAL_MAJ3),
+                SclPitch::Ratio(RawJiRatio::OCTAVE),
+            ]
+        );
+        assert_eq!(parse_scl("!\n"), Err(BadScl::MissingDescription));
+        assert_eq!(
+            parse_scl("x\n 2\n 9/8\n"),
+            Err(BadScl::WrongPitchCount {
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            parse_scl("x\n 1\n 9/x\n"),
+            Err(BadScl::BadPitch("9/x".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_scl_round_trip() {
+        let diasem = [0, 1, 0, 2, 0, 1, 0, 2, 0];
+        let ji = StepTuning::Ji(vec![
+            monzo![-3, 2],
+            monzo![2, -3, 0, 1],
+            monzo![6, -2, 0, -1],
+        ]);
+        let (word, sizes) = scl_to_word(&scl_string(&diasem, &ji, "d", "d"), 0.0).unwrap();
+        assert_eq!(word, diasem);
+        assert!((sizes[0] - RawJiRatio::try_new(9, 8).unwrap().cents()).abs() < 1e-9);
+
+        // A tempered tuning needs a tolerance for the rounding of the cents values.
+        let ed = StepTuning::Ed {
+            steps: vec![4, 2, 1],
+            ed: 26,
+            equave: RawJiRatio::OCTAVE,
+        };
+        let scl = scl_string(&diasem, &ed, "d", "d");
+        assert_eq!(scl_to_word(&scl, 0.01).unwrap().0, diasem);
+    }
+
+    #[test]
+    fn test_scl_not_ternary() {
+        // 12edo has a single step size.
+        let chromatic = StepTuning::Cents(vec![100.0]);
+        let scl = scl_string(&[0; 12], &chromatic, "12edo", "12edo");
+        assert_eq!(scl_to_word(&scl, 0.01), Err(BadScl::StepSizeCount(1)));
+        // The 5-limit JI major scale has 3 step sizes; detuning 15/8 splits two of them.
+        let contents = "x\n 7\n 9/8\n 5/4\n 4/3\n 3/2\n 5/3\n 15/8\n 2/1\n";
+        assert_eq!(scl_to_word(contents, 0.0).unwrap().1.len(), 3);
+        let contents = "x\n 7\n 9/8\n 5/4\n 4/3\n 3/2\n 5/3\n 1085.0\n 2/1\n";
+        assert_eq!(scl_to_word(contents, 0.0), Err(BadScl::StepSizeCount(5)));
+        assert_eq!(
+            scl_to_word("x\n 2\n 3/2\n 4/3\n", 0.0),
+            Err(BadScl::NonAscendingStep(1))
+        );
+    }
+
+    #[test]
+    fn test_scl_binary() {
+        // The Pythagorean diatonic is a 2-step-size scale.
+        let contents = "x\n 7\n 9/8\n 81/64\n 4/3\n 3/2\n 27/16\n 243/128\n 2/1\n";
+        let (word, sizes, profile) = crate::scl_to_profile(contents, 0.0).unwrap();
+        assert_eq!(word, vec![0, 0, 1, 0, 0, 0, 1]);
+        assert_eq!(sizes.len(), 2);
+        assert!(profile.description().starts_with("5L2s scale"));
+    }
+}
@@ -0,0 +1,199 @@
+//! SonicWeave source code for tuned scale words.
+//!
+//! [SonicWeave](https://github.com/xenharmonic-devs/sonic-weave) is the scale language of
+//! Scale Workshop 3. A tuned scale word is written as one variable per step size followed by
+//! the word as a sequence of those variables, which `stack()` turns into a scale:
+//!
+//! ```text
+//! let L = 9/8
+//! let m = 28/27
+//! let s = 64/63
+//! L;m;L;s;L;m;L;s;L;
+//! stack()
+//! ```
+//!
+//! JI steps are written as ratios, ED steps as `n\ed` (or `n\ed<equave>` for other equaves than
+//! the octave) and other steps in cents. The equave is the last pitch of the stacked scale, so
+//! non-octave scales need no special handling.
+//!
+//! # Examples
+//!
+//! ```
+//! use ternary::sonicweave::{SonicWeaveOptions, sonicweave_code};
+//! use ternary::tuning::StepTuning;
+//!
+//! // Mode 1 of 5L2m2s in 21ed3 with L = 3\21<3>, m = 2\21<3> and s = 1\21<3>
+//! let word = [0, 1, 0, 2, 0, 1, 0, 2, 0];
+//! let tuning = StepTuning::from_strings(&["3\\21<3>", "2\\21<3>", "1\\21<3>"]).unwrap();
+//! let options = SonicWeaveOptions { mode: 1, ..Default::default() };
+//! let code = sonicweave_code(&word, &tuning, &options);
+//! assert!(code.contains("let L = 3\\21<3>\n"));
+//! assert!(code.contains("\nm;L;s;L;m;L;s;L;L;\nstack()"));
+//! ```
+
+use std::cmp::min;
+
+use itertools::Itertools;
+
+use crate::interval::JiRatio;
+use crate::monzo::Monzo;
+use crate::scala::{checked_ratio, scl_pitches};
+use crate::tuning::StepTuning;
+use crate::words::{Letter, rotate};
+
+/// Options for [`sonicweave_code`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SonicWeaveOptions {
+    /// The degree of the word the exported mode starts on, as in [`rotate`].
+    pub mode: usize,
+    /// The variable name of each step letter. Defaults to `L`, `m`, `s` for ternary scales
+    /// (`L`, `s` for binary and `X` for unary scales).
+    pub step_labels: Option<Vec<String>>,
+}
+
+/// The default variable names for the steps of a scale with `arity` step sizes.
+pub fn default_step_labels(arity: usize) -> Vec<String> {
+    crate::STEP_LETTERS[min(arity, 11)]
+        .chars()
+        .take(arity)
+        .map(String::from)
+        .collect()
+}
+
+/// A monzo as a SonicWeave monzo literal such as `[-3 2>`.
+fn monzo_literal(monzo: Monzo) -> String {
+    let exponents = monzo.into_inner();
+    let exponents = exponents.as_array();
+    let len = exponents
+        .iter()
+        .rposition(|&exponent| exponent != 0)
+        .map_or(1, |i| i + 1);
+    format!("[{}>", exponents[..len].iter().join(" "))
+}
+
+/// The SonicWeave literal for step `letter` of `tuning`.
+pub fn sonicweave_step(tuning: &StepTuning, letter: Letter) -> String {
+    match tuning {
+        StepTuning::Ji(steps) => {
+            let step = steps.get(letter).copied().unwrap_or(Monzo::UNISON);
+            checked_ratio(step)
+                .map(|(numer, denom)| format!("{numer}/{denom}"))
+                .unwrap_or_else(|| monzo_literal(step))
+        }
+        StepTuning::Ed { steps, ed, equave } => {
+            let count = steps.get(letter).copied().unwrap_or(0);
+            if equave.numer() == 2 && equave.denom() == 1 {
+                format!("{count}\\{ed}")
+            } else if equave.denom() == 1 {
+                format!("{count}\\{ed}<{}>", equave.numer())
+            } else {
+                format!("{count}\\{ed}<{equave}>")
+            }
+        }
+        StepTuning::Cents(steps) => format!("{:.6}", steps.get(letter).copied().unwrap_or(0.0)),
+    }
+}
+
+/// SonicWeave source code for a mode of `word` under `tuning`.
+/// Missing step labels fall back to the defaults of [`default_step_labels`].
+pub fn sonicweave_code(
+    word: &[Letter],
+    tuning: &StepTuning,
+    options: &SonicWeaveOptions,
+) -> String {
+    if word.is_empty() {
+        return "(* empty scale *)\n".to_string();
+    }
+    let mode = rotate(word, options.mode);
+    let arity = tuning
+        .arity()
+        .max(mode.iter().max().map_or(0, |&letter| letter + 1));
+    let defaults = default_step_labels(arity);
+    let labels: Vec<String> = (0..arity)
+        .map(|letter| {
+            options
+                .step_labels
+                .as_ref()
+                .and_then(|labels| labels.get(letter))
+                .or(defaults.get(letter))
+                .cloned()
+                .unwrap_or_else(|| format!("step{letter}"))
+        })
+        .collect();
+    let word_string: String = mode.iter().map(|&letter| labels[letter].as_str()).collect();
+    let equave = scl_pitches(&mode, tuning)
+        .pop()
+        .expect("a nonempty word has an equave");
+    let mut result = format!("(* {word_string} with equave {equave} *)\n");
+    for (letter, label) in labels.iter().enumerate() {
+        result.push_str(&format!(
+            "let {label} = {}\n",
+            sonicweave_step(tuning, letter)
+        ));
+    }
+    for &letter in &mode {
+        result.push_str(&labels[letter]);
+        result.push(';');
+    }
+    result.push_str("\nstack()\n");
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ji_ratio::RawJiRatio;
+
+    #[test]
+    fn test_sonicweave_ji() {
+        let diasem = [0, 1, 0, 2, 0, 1, 0, 2, 0];
+        let tuning = StepTuning::Ji(vec![
+            monzo![-3, 2],
+            monzo![2, -3, 0, 1],
+            monzo![6, -2, 0, -1],
+        ]);
+        assert_eq!(
+            sonicweave_code(&diasem, &tuning, &SonicWeaveOptions::default()),
+            "(* LmLsLmLsL with equave 2/1 *)\n\
+             let L = 9/8\n\
+             let m = 28/27\n\
+             let s = 64/63\n\
+             L;m;L;s;L;m;L;s;L;\n\
+             stack()\n"
+        );
+        // Steps too large for a ratio are written as monzos.
+        let huge = StepTuning::Ji(vec![monzo![-50, 32], monzo![-3, 2]]);
+        assert_eq!(sonicweave_step(&huge, 0), "[-50 32>");
+        assert_eq!(monzo_literal(Monzo::UNISON), "[0>");
+    }
+
+    #[test]
+    fn test_sonicweave_ed_and_cents() {
+        let diatonic = [0, 0, 1, 0, 0, 0, 1];
+        let ed = StepTuning::Ed {
+            steps: vec![3, 1],
+            ed: 17,
+            equave: RawJiRatio::TRITAVE,
+        };
+        let options = SonicWeaveOptions {
+            mode: 2,
+            step_labels: Some(vec!["T".to_string()]),
+        };
+        let code = sonicweave_code(&diatonic, &ed, &options);
+        assert!(code.starts_with("(* sTTTsTT with equave 3/1 *)\n"));
+        assert!(code.contains("let T = 3\\17<3>\nlet s = 1\\17<3>\n"));
+        assert!(code.contains("\ns;T;T;T;s;T;T;\n"));
+
+        let cents = StepTuning::Cents(vec![200.0, 100.0]);
+        let code = sonicweave_code(&diatonic, &cents, &SonicWeaveOptions::default());
+        assert!(code.contains("let L = 200.000000\nlet s = 100.000000\n"));
+        assert!(code.starts_with("(* LLsLLLs with equave 1200.000000 *)"));
+
+        let half_octave = StepTuning::Ed {
+            steps: vec![1],
+            ed: 5,
+            equave: RawJiRatio::try_new(3, 2).unwrap(),
+        };
+        assert_eq!(sonicweave_step(&half_octave, 0), "1\\5<3/2>");
+    }
+}
@@ -0,0 +1,388 @@
+//! Standalone SVG drawings of pitch class lattices.
+//!
+//! [`lattice_svg`] draws the lattice that [`word_to_lattice_result`] computes for the web app,
+//! or the lattice in another basis, without a browser. Each pitch class is a node labeled by
+//! its degree or by its pitch under a tuning. Neighboring nodes are joined by an edge along
+//! each basis vector, and the outline of the parallelogram the pitch classes fill is drawn if
+//! they fill one. A mode or a chord can be highlighted.
+//!
+//! Scales without a 2D lattice are drawn from their [`fallback_lattice`], with the layers of
+//! pitch classes shifted along the diagonal and colored by layer, as in the web app.
+//!
+//! # Examples
+//!
+//! ```
+//! use ternary::svg::{Highlight, SvgOptions, lattice_svg};
+//! use ternary::tuning::StepTuning;
+//!
+//! // Zarlino with L = 9/8, m = 10/9, s = 16/15, highlighting the major triad on the tonic
+//! let zarlino = [0, 1, 2, 0, 1, 0, 2];
+//! let options = SvgOptions {
+//!     tuning: Some(StepTuning::from_strings(&["9/8", "10/9", "16/15"]).unwrap()),
+//!     highlight: Highlight::Chord(vec![0, 2, 4]),
+//!     ..Default::default()
+//! };
+//! let svg = lattice_svg(&zarlino, &options).unwrap();
+//! assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+//! assert!(svg.contains(">5/4</text>"));
+//! assert!(svg.contains("class=\"chord\""));
+//! ```
+//!
+//! [`fallback_lattice`]: crate::lattice::fallback_lattice
+
+use std::fmt::Write;
+
+use crate::Error;
+use crate::helpers::slicify_each;
+use crate::lattice::{
+    PitchClassLatticeBasis, Projection, lattice_coordinates, parallelogram_witness,
+};
+use crate::scala::checked_ratio;
+use crate::tuning::StepTuning;
+use crate::word_to_lattice_result;
+use crate::words::Letter;
+
+/// Fill colors of the layers of scales without a 2D lattice, starting with the equave's.
+const LAYER_COLORS: [&str; 5] = ["white", "#fc6", "#6cf", "#f9c", "#9f9"];
+
+/// The style sheet of the drawings, with the colors of the web app.
+const STYLE: &str = "\
+.vx { stroke: #76f; stroke-width: 2; }
+.vy { stroke: gray; stroke-width: 2; }
+.outline { fill: none; stroke: black; stroke-width: 1.5; stroke-dasharray: 6 4; }
+.mode { fill: none; stroke: #e44; stroke-width: 2.5; stroke-opacity: 0.7; }
+.chord { fill: #e44; fill-opacity: 0.2; stroke: #e44; stroke-width: 2.5; }
+.node circle { stroke: black; stroke-width: 1; }
+.node.highlighted circle { stroke: #e44; stroke-width: 3; }
+.node text { font-family: sans-serif; text-anchor: middle; dominant-baseline: central; }";
+
+/// What to highlight in a lattice drawing.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Highlight {
+    /// nothing
+    #[default]
+    None,
+    /// the mode starting on this degree: its tonic is ringed and its steps are drawn as a path
+    Mode(usize),
+    /// these degrees, ringed and joined in order into a polygon
+    Chord(Vec<usize>),
+}
+
+/// Options for [`lattice_svg`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgOptions {
+    /// Label each node by its pitch under this tuning instead of by its degree.
+    /// JI pitches are written as ratios, ED pitches as `n\ed` and other pitches in cents.
+    pub tuning: Option<StepTuning>,
+    /// The basis to draw the lattice in, such as one from
+    /// [`ranked_lattice_bases`](crate::lattice::ranked_lattice_bases).
+    /// Defaults to the basis of [`word_to_lattice_result`].
+    pub basis: Option<PitchClassLatticeBasis>,
+    /// A mode or chord to highlight.
+    pub highlight: Highlight,
+    /// Whether to outline the parallelogram the pitch classes fill, if they fill one.
+    pub outline: bool,
+    /// The distance between neighboring lattice points, in pixels.
+    pub spacing: f64,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            tuning: None,
+            basis: None,
+            highlight: Highlight::None,
+            outline: true,
+            spacing: 48.0,
+        }
+    }
+}
+
+/// The label of each degree of `word` under `tuning`, starting with the tonic.
+fn pitch_labels(word: &[Letter], tuning: &StepTuning) -> Vec<String> {
+    let cents = tuning.pitches_cents(word);
+    let mut labels = vec![match tuning {
+        StepTuning::Ji(_) => "1/1".to_string(),
+        StepTuning::Ed { ed, .. } => format!("0\\{ed}"),
+        StepTuning::Cents(_) => "0¢".to_string(),
+    }];
+    match tuning {
+        StepTuning::Ji(_) => labels.extend(
+            tuning
+                .pitches_monzo(word)
+                .expect("a JI tuning gives every pitch a monzo")
+                .into_iter()
+                .zip(&cents)
+                .map(|(monzo, cents)| match checked_ratio(monzo) {
+                    Some((numer, denom)) => format!("{numer}/{denom}"),
+                    None => format!("{cents:.0}¢"),
+                }),
+        ),
+        StepTuning::Ed { steps, ed, .. } => labels.extend(word.iter().scan(0, |count, &letter| {
+            *count += steps[letter];
+            Some(format!("{count}\\{ed}"))
+        })),
+        StepTuning::Cents(_) => labels.extend(cents.iter().map(|cents| format!("{cents:.0}¢"))),
+    }
+    labels.truncate(word.len());
+    labels
+}
+
+/// A standalone SVG drawing of the pitch class lattice of `word`.
+///
+/// Node `d` is degree `d` of the word, so node 0 is the tonic, at the origin.
+/// Fails for the empty word, for a tuning without a step size for each letter of `word`,
+/// and for degrees in [`Highlight`] that aren't degrees of `word`.
+pub fn lattice_svg(word: &[Letter], options: &SvgOptions) -> Result<String, Error> {
+    let lattice = word_to_lattice_result(word).ok_or_else(|| Error::InvalidWord(String::new()))?;
+    let n = word.len();
+    if let Some(tuning) = &options.tuning {
+        tuning.check_word(word)?;
+    }
+    let highlighted_degrees = match &options.highlight {
+        Highlight::None => &[][..],
+        Highlight::Mode(tonic) => std::slice::from_ref(tonic),
+        Highlight::Chord(degrees) => degrees,
+    };
+    if let Some(degree) = highlighted_degrees.iter().find(|&&degree| degree >= n) {
+        return Err(Error::OutOfRange {
+            value: format!("degree {degree}"),
+            expected: "a degree of the scale",
+        });
+    }
+    let basis = options.basis.clone().unwrap_or_else(|| {
+        let [vx, vy] = [&lattice.basis[0], &lattice.basis[1]]
+            .map(|v| v.iter().map(|&x| x as i32).collect::<Vec<_>>());
+        PitchClassLatticeBasis::from_slices(&vx, &vy)
+    });
+    // coordinates[i] is the pitch after i + 1 steps, so degree d is at coordinates[d - 1]
+    let coordinates = match (&options.basis, &lattice.fallback) {
+        (Some(basis), None) => lattice_coordinates(word, basis),
+        _ => lattice.coordinates.clone(),
+    };
+    let position = |degree: usize| &coordinates[(degree + n - 1) % n];
+    let (layers, layer_count) = match &lattice.fallback {
+        Some(fallback) => (
+            fallback.coordinates.iter().map(|c| c[0]).collect(),
+            match fallback.projection {
+                Projection::Layers { count } => count,
+                Projection::Plane => 1,
+            },
+        ),
+        None => (vec![0; n], 1),
+    };
+    let layer = |degree: usize| layers[(degree + n - 1) % n];
+
+    // Screen coordinates, with y pointing up and layers shifted along the diagonal
+    let spacing = options.spacing;
+    let point = |degree: usize| {
+        let [x, y] = [position(degree)[0], position(degree)[1]];
+        let shift = layer(degree) as f64 / layer_count as f64;
+        (
+            (x as f64 + shift) * spacing,
+            0.0 - (y as f64 + shift) * spacing,
+        )
+    };
+    let points: Vec<(f64, f64)> = (0..n).map(point).collect();
+    let min_x = points.iter().map(|p| p.0).fold(f64::INFINITY, f64::min) - spacing;
+    let max_x = points.iter().map(|p| p.0).fold(f64::NEG_INFINITY, f64::max) + spacing;
+    let min_y = points.iter().map(|p| p.1).fold(f64::INFINITY, f64::min) - spacing;
+    let max_y = points.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max) + spacing;
+    let (width, height) = (max_x - min_x, max_y - min_y);
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width:.0}\" height=\"{height:.0}\" \
+         viewBox=\"{min_x:.1} {min_y:.1} {width:.1} {height:.1}\">"
+    );
+    let word_string = crate::numbers_to_string(word);
+    let _ = writeln!(svg, "<title>{word_string}</title>");
+    let _ = writeln!(svg, "<style>\n{STYLE}\n</style>");
+
+    if options.outline && lattice.fallback.is_none() {
+        let pitch_classes = slicify_each(&coordinates);
+        if let Some((shape, row_basis)) =
+            parallelogram_witness(&pitch_classes, &[1, 0], &[0, 1], &basis)
+        {
+            // rows go along the row generator, which is vx or vy
+            let (row_len, row_count) = (shape.full_row_len(), shape.row_count());
+            let (x_len, y_len) = if row_basis.vx() == basis.vx() {
+                (row_len, row_count)
+            } else {
+                (row_count, row_len)
+            };
+            let x0 = coordinates.iter().map(|c| c[0]).min().unwrap_or(0);
+            let y0 = coordinates.iter().map(|c| c[1]).min().unwrap_or(0);
+            let corner = |x: f64, y: f64| format!("{:.1},{:.1}", x * spacing, 0.0 - y * spacing);
+            let (left, bottom) = (x0 as f64 - 0.5, y0 as f64 - 0.5);
+            let (right, top) = (left + x_len as f64, bottom + y_len as f64);
+            let _ = writeln!(
+                svg,
+                "<polygon class=\"outline\" points=\"{} {} {} {}\"/>",
+                corner(left, bottom),
+                corner(right, bottom),
+                corner(right, top),
+                corner(left, top),
+            );
+        }
+    }
+
+    // An edge along each basis vector between neighboring nodes on the same layer
+    for from in 0..n {
+        for to in 0..n {
+            let (a, b) = (position(from), position(to));
+            if layer(from) != layer(to) {
+                continue;
+            }
+            let class = match (b[0] - a[0], b[1] - a[1]) {
+                (1, 0) => "vx",
+                (0, 1) => "vy",
+                _ => continue,
+            };
+            let _ = writeln!(
+                svg,
+                "<line class=\"{class}\" x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\"/>",
+                points[from].0, points[from].1, points[to].0, points[to].1
+            );
+        }
+    }
+
+    let joined = |degrees: &mut dyn Iterator<Item = usize>| {
+        degrees
+            .map(|degree| format!("{:.1},{:.1}", points[degree].0, points[degree].1))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let highlighted: Vec<usize> = match &options.highlight {
+        Highlight::None => vec![],
+        Highlight::Mode(tonic) => {
+            let _ = writeln!(
+                svg,
+                "<polyline class=\"mode\" points=\"{}\"/>",
+                joined(&mut (0..=n).map(|step| (tonic + step) % n))
+            );
+            vec![*tonic]
+        }
+        Highlight::Chord(degrees) => {
+            let _ = writeln!(
+                svg,
+                "<polygon class=\"chord\" points=\"{}\"/>",
+                joined(&mut degrees.iter().copied())
+            );
+            degrees.clone()
+        }
+    };
+
+    let labels = match &options.tuning {
+        Some(tuning) => pitch_labels(word, tuning),
+        None => (0..n).map(|degree| degree.to_string()).collect(),
+    };
+    let radius = spacing * 0.32;
+    let font_size = spacing * if options.tuning.is_some() { 0.2 } else { 0.3 };
+    for (degree, (x, y)) in points.iter().enumerate() {
+        let class = if highlighted.contains(&degree) {
+            "node highlighted"
+        } else {
+            "node"
+        };
+        let fill = LAYER_COLORS[layer(degree) as usize % LAYER_COLORS.len()];
+        let _ = writeln!(
+            svg,
+            "<g class=\"{class}\"><circle cx=\"{x:.1}\" cy=\"{y:.1}\" r=\"{radius:.1}\" \
+             fill=\"{fill}\"/><text x=\"{x:.1}\" y=\"{y:.1}\" font-size=\"{font_size:.1}\">{}</text></g>",
+            labels.get(degree).map_or("", String::as_str)
+        );
+    }
+    svg.push_str("</svg>\n");
+    Ok(svg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lattice::{BasisScore, ranked_lattice_bases};
+    use crate::string_to_numbers;
+    use crate::tuning::BadTuning;
+
+    #[test]
+    fn test_lattice_svg() {
+        let diasem = string_to_numbers("LmLsLmLsL");
+        let svg = lattice_svg(&diasem, &SvgOptions::default()).unwrap();
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<g class=\"node\">").count(), 9);
+        assert_eq!(svg.matches("class=\"outline\"").count(), 1);
+        // the 9 pitch classes of a parallelogram substring have at least 8 edges between them
+        assert!(svg.matches("<line ").count() >= 8);
+        assert!(svg.contains(">8</text>"));
+
+        // LLmLLms with the basis L, LLm: degree 3 (LLm) is one step along vy from the tonic
+        let word = string_to_numbers("LLmLLms");
+        let options = SvgOptions {
+            basis: Some(PitchClassLatticeBasis::from_slices(&[1, 0, 0], &[2, 1, 0])),
+            highlight: Highlight::Mode(3),
+            spacing: 10.0,
+            ..Default::default()
+        };
+        let svg = lattice_svg(&word, &options).unwrap();
+        assert!(svg.contains("<line class=\"vy\" x1=\"0.0\" y1=\"0.0\" x2=\"0.0\" y2=\"-10.0\"/>"));
+        assert!(svg.contains("<circle cx=\"0.0\" cy=\"-10.0\""));
+        assert_eq!(svg.matches("node highlighted").count(), 1);
+        assert!(svg.contains("<polyline class=\"mode\" points=\"0.0,-10.0 "));
+        // the outline is a 3 by 3 parallelogram with one partial row
+        assert!(svg.contains("points=\"-5.0,5.0 25.0,5.0 25.0,-25.0 -5.0,-25.0\""));
+
+        // any ranked basis can be drawn
+        for scored in ranked_lattice_bases(&diasem, &BasisScore::Shortest).unwrap() {
+            let options = SvgOptions {
+                basis: Some(scored.basis),
+                ..Default::default()
+            };
+            assert!(
+                lattice_svg(&diasem, &options)
+                    .unwrap()
+                    .contains("class=\"outline\"")
+            );
+        }
+        assert!(lattice_svg(&[], &SvgOptions::default()).is_err());
+
+        // m and s aren't drawn as unisons, and degrees aren't wrapped
+        let options = SvgOptions {
+            tuning: Some(StepTuning::from_strings(&["9/8"]).unwrap()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            lattice_svg(&diasem, &options),
+            Err(Error::Tuning(BadTuning::TooFewSteps { .. }))
+        ));
+        for highlight in [Highlight::Mode(9), Highlight::Chord(vec![0, 2, 99])] {
+            let options = SvgOptions {
+                highlight,
+                ..Default::default()
+            };
+            assert!(matches!(
+                lattice_svg(&diasem, &options),
+                Err(Error::OutOfRange { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_labels_and_fallback() {
+        let word = string_to_numbers("LLmLLms");
+        let ed = StepTuning::from_strings(&["3\\17", "2\\17", "1\\17"]).unwrap();
+        assert_eq!(
+            pitch_labels(&word, &ed),
+            [
+                "0\\17", "3\\17", "6\\17", "8\\17", "11\\17", "14\\17", "16\\17"
+            ]
+        );
+        let cents = StepTuning::Cents(vec![200.0, 150.0, 100.0]);
+        assert_eq!(pitch_labels(&word, &cents)[3], "550¢");
+
+        // LmsLms repeats twice per octave, so it is drawn in two layers
+        let svg = lattice_svg(&string_to_numbers("LmsLms"), &SvgOptions::default()).unwrap();
+        assert_eq!(svg.matches("fill=\"#fc6\"").count(), 3);
+        assert!(!svg.contains("class=\"outline\""));
+    }
+}
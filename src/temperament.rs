@@ -0,0 +1,626 @@
+//! Regular temperaments as mapping matrices and comma bases.
+//!
+//! A temperament on the first `dim` primes is determined either by its *mapping*, a list of
+//! vals (one per generator), or by its *comma basis*, a list of monzos that the mapping sends
+//! to zero. The two are related by taking integer nullspaces. We store temperaments by their
+//! canonical mapping: the Hermite normal form (HNF) of the defactored mapping, so two
+//! temperaments are equal if and only if their canonical mappings are.
+//!
+//! # Examples
+//!
+//! ```
+//! use ternary::equal::gpval;
+//! use ternary::monzo::Monzo;
+//! use ternary::temperament::Temperament;
+//!
+//! // 12 & 19 is meantone, which tempers out 81/80.
+//! let meantone = Temperament::from_vals(&[gpval(12.0), gpval(19.0)], 3).unwrap();
+//! let syntonic = Monzo::try_new(81, 80).unwrap();
+//! assert_eq!(meantone.rank(), 2);
+//! assert_eq!(meantone.comma_basis(), vec![syntonic]);
+//! assert_eq!(meantone, Temperament::from_commas(&[syntonic], 3).unwrap());
+//! assert_eq!(meantone.name(), Some("meantone"));
+//! ```
+
+use std::fmt;
+
+use itertools::Itertools;
+
+use crate::equal::{Val, gpval};
+use crate::interval::{Dyad, JiRatio};
+use crate::monzo::Monzo;
+use crate::primes::{SMALL_PRIMES, SMALL_PRIMES_COUNT};
+
+/// Error type for invalid temperament constructions.
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum TemperamentError {
+    /// The number of primes was 0 or more than `SMALL_PRIMES_COUNT`.
+    #[error("a temperament must be on 1 to {SMALL_PRIMES_COUNT} primes, got {0}")]
+    BadDimension(usize),
+    /// A comma used a prime beyond the temperament's prime limit.
+    #[error("a comma uses a prime beyond the prime limit")]
+    OutsidePrimeLimit,
+    /// Two temperaments with different prime limits were combined.
+    #[error("can't combine temperaments on {0} and {1} primes")]
+    DimensionMismatch(usize, usize),
+}
+
+/// A regular temperament on the first `dim` primes, stored by its canonical mapping.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Temperament {
+    dim: usize,
+    /// Rows of the defactored Hermite normal form of the mapping.
+    mapping: Vec<Vec<i64>>,
+}
+
+/// A well-known temperament: its name, its prime limit as a number of primes, and a comma basis as ratios.
+type NamedTemperament = (&'static str, usize, &'static [(u32, u32)]);
+
+/// Well-known temperaments, looked up by [`Temperament::name`].
+const NAMED_TEMPERAMENTS: [NamedTemperament; 27] = [
+    // 5-limit rank-2
+    ("meantone", 3, &[(81, 80)]),
+    ("schismatic", 3, &[(32805, 32768)]),
+    ("augmented", 3, &[(128, 125)]),
+    ("diminished", 3, &[(648, 625)]),
+    ("porcupine", 3, &[(250, 243)]),
+    ("magic", 3, &[(3125, 3072)]),
+    ("kleismic", 3, &[(15625, 15552)]),
+    ("srutal", 3, &[(2048, 2025)]),
+    ("tetracot", 3, &[(20000, 19683)]),
+    ("mavila", 3, &[(135, 128)]),
+    ("dicot", 3, &[(25, 24)]),
+    ("blackwood", 3, &[(256, 243)]),
+    // 7-limit rank-2
+    ("septimal meantone", 4, &[(81, 80), (126, 125)]),
+    ("superpyth", 4, &[(64, 63), (245, 243)]),
+    ("septimal magic", 4, &[(225, 224), (245, 243)]),
+    ("miracle", 4, &[(225, 224), (1029, 1024)]),
+    ("pajara", 4, &[(50, 49), (64, 63)]),
+    // 7-limit rank-3
+    ("marvel", 4, &[(225, 224)]),
+    ("starling", 4, &[(126, 125)]),
+    ("gamelismic", 4, &[(1029, 1024)]),
+    ("hemifamity", 4, &[(5120, 5103)]),
+    ("ragismic", 4, &[(4375, 4374)]),
+    ("sensamagic", 4, &[(245, 243)]),
+    ("archytas", 4, &[(64, 63)]),
+    ("jubilismic", 4, &[(50, 49)]),
+    ("keemic", 4, &[(875, 864)]),
+    ("mint", 4, &[(36, 35)]),
+];
+
+impl Temperament {
+    /// The temperament generated by a list of vals restricted to the first `dim` primes (the join of the vals).
+    pub fn from_vals(vals: &[Val], dim: usize) -> Result<Self, TemperamentError> {
+        check_dim(dim)?;
+        let rows = vals
+            .iter()
+            .map(|val| {
+                val.into_inner()
+                    .iter()
+                    .take(dim)
+                    .map(|&x| x as i64)
+                    .collect()
+            })
+            .collect();
+        Ok(Self::from_mapping_rows(rows, dim))
+    }
+
+    /// The temperament on the first `dim` primes tempering out exactly the given commas
+    /// (and everything they generate, up to saturation).
+    pub fn from_commas(commas: &[Monzo], dim: usize) -> Result<Self, TemperamentError> {
+        check_dim(dim)?;
+        let rows = commas
+            .iter()
+            .map(|comma| truncate(comma.into_inner().as_array(), dim))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_mapping_rows(nullspace(&rows, dim), dim))
+    }
+
+    /// Just intonation on the first `dim` primes, i.e. the temperament tempering out nothing.
+    pub fn just(dim: usize) -> Result<Self, TemperamentError> {
+        Self::from_commas(&[], dim)
+    }
+
+    /// The temperament a JI tuning of a scale's steps represents once `commas` are tempered out:
+    /// the temperament on the prime limit of the steps and the commas that tempers out exactly the commas.
+    /// Without commas, this is JI on the prime limit of the steps.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ternary::monzo;
+    /// use ternary::monzo::Monzo;
+    /// use ternary::temperament::Temperament;
+    ///
+    /// // Diasem with L = 9/8, m = 28/27, s = 64/63 is an archytas scale.
+    /// let diasem = [monzo![-3, 2], monzo![2, -3, 0, 1], monzo![6, -2, 0, -1]];
+    /// let archytas = Monzo::try_new(64, 63).unwrap();
+    /// let temperament = Temperament::from_step_tuning(&diasem, &[archytas]).unwrap();
+    /// assert_eq!(temperament.name(), Some("archytas"));
+    /// ```
+    pub fn from_step_tuning(steps: &[Monzo], commas: &[Monzo]) -> Result<Self, TemperamentError> {
+        Self::from_commas(commas, prime_limit_dim(&[steps, commas].concat()))
+    }
+
+    fn from_mapping_rows(rows: Vec<Vec<i64>>, dim: usize) -> Self {
+        // Taking the nullspace twice saturates (defactors) the mapping.
+        let mapping = hnf(nullspace(&nullspace(&rows, dim), dim));
+        Self { dim, mapping }
+    }
+
+    /// The number of primes the temperament is on.
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// The number of generators of the temperament.
+    pub fn rank(&self) -> usize {
+        self.mapping.len()
+    }
+
+    /// The number of independent commas tempered out.
+    pub fn nullity(&self) -> usize {
+        self.dim - self.rank()
+    }
+
+    /// The canonical mapping as a list of vals, one per generator.
+    pub fn mapping(&self) -> Vec<Val> {
+        self.mapping
+            .iter()
+            .map(|row| Val::from_slice(&row.iter().map(|&x| x as i32).collect::<Vec<_>>()))
+            .collect()
+    }
+
+    /// A canonical comma basis: the Hermite normal form of the nullspace of the mapping,
+    /// with each comma written as a ratio greater than 1.
+    pub fn comma_basis(&self) -> Vec<Monzo> {
+        hnf(nullspace(&self.mapping, self.dim))
+            .into_iter()
+            .map(|row| {
+                let comma = Monzo::from_slice(&row.iter().map(|&x| x as i32).collect::<Vec<_>>());
+                if comma.cents() < 0.0 { -comma } else { comma }
+            })
+            .collect()
+    }
+
+    /// The generator counts the temperament maps `monzo` to.
+    /// Primes beyond the temperament's prime limit are ignored.
+    pub fn map(&self, monzo: Monzo) -> Vec<i32> {
+        self.mapping
+            .iter()
+            .map(|row| (0..self.dim).map(|i| row[i] * monzo[i] as i64).sum::<i64>() as i32)
+            .collect()
+    }
+
+    /// Whether the temperament tempers out `monzo`.
+    pub fn tempers_out(&self, monzo: Monzo) -> bool {
+        (self.dim..SMALL_PRIMES_COUNT).all(|i| monzo[i] == 0)
+            && self.map(monzo).iter().all(|&x| x == 0)
+    }
+
+    /// The wedgie of the temperament: the `rank`-minors of the canonical mapping
+    /// over all sets of `rank` primes in lexicographic order, normalized so that their gcd is 1
+    /// and the first nonzero entry is positive.
+    pub fn wedgie(&self) -> Vec<i64> {
+        let rank = self.rank();
+        let mut minors: Vec<i64> = (0..self.dim)
+            .combinations(rank)
+            .map(|cols| {
+                let minor = self
+                    .mapping
+                    .iter()
+                    .map(|row| cols.iter().map(|&c| row[c]).collect())
+                    .collect::<Vec<Vec<i64>>>();
+                determinant(&minor)
+            })
+            .collect();
+        let g = minors.iter().fold(0, |acc, &x| gcd_i64(acc, x));
+        let sign = minors.iter().find(|&&x| x != 0).map_or(1, |&x| x.signum());
+        if g != 0 {
+            for x in minors.iter_mut() {
+                *x = *x * sign / g;
+            }
+        }
+        minors
+    }
+
+    /// Merge the mappings of two temperaments (`self & other`), giving the temperament
+    /// tempering out only the commas tempered out by both.
+    pub fn join(&self, other: &Self) -> Result<Self, TemperamentError> {
+        self.check_same_dim(other)?;
+        let rows = [self.mapping.clone(), other.mapping.clone()].concat();
+        Ok(Self::from_mapping_rows(rows, self.dim))
+    }
+
+    /// Merge the comma bases of two temperaments, giving the temperament
+    /// tempering out every comma tempered out by either.
+    pub fn meet(&self, other: &Self) -> Result<Self, TemperamentError> {
+        self.check_same_dim(other)?;
+        let commas = [self.comma_basis(), other.comma_basis()].concat();
+        Self::from_commas(&commas, self.dim)
+    }
+
+    /// Split the temperament into `rank` patent vals of EDOs up to `max_edo` whose join is the temperament,
+    /// choosing the smallest EDOs greedily. Returns `None` if no such vals are found.
+    pub fn split(&self, max_edo: u32) -> Option<Vec<Val>> {
+        let commas = self.comma_basis();
+        let mut chosen: Vec<Val> = vec![];
+        let mut current = Self::from_vals(&[], self.dim).ok()?;
+        for edo in 1..=max_edo {
+            if chosen.len() == self.rank() {
+                break;
+            }
+            let val = Val::from_slice(&gpval(edo as f64).into_inner().as_array()[..self.dim]);
+            if commas.iter().any(|&comma| val.evaluate(comma) != 0) {
+                continue;
+            }
+            let extended = current
+                .join(&Self::from_vals(&[val], self.dim).ok()?)
+                .ok()?;
+            if extended.rank() > current.rank() {
+                chosen.push(val);
+                current = extended;
+            }
+        }
+        (current == *self).then_some(chosen)
+    }
+
+    /// The name of the temperament if it is a well-known one.
+    pub fn name(&self) -> Option<&'static str> {
+        NAMED_TEMPERAMENTS
+            .iter()
+            .find(|(_, dim, commas)| {
+                *dim == self.dim
+                    && commas.len() == self.nullity()
+                    && commas.iter().all(|&(n, d)| {
+                        Monzo::try_new(n, d).is_ok_and(|comma| self.tempers_out(comma))
+                    })
+            })
+            .map(|(name, _, _)| *name)
+    }
+
+    fn check_same_dim(&self, other: &Self) -> Result<(), TemperamentError> {
+        if self.dim == other.dim {
+            Ok(())
+        } else {
+            Err(TemperamentError::DimensionMismatch(self.dim, other.dim))
+        }
+    }
+}
+
+impl fmt::Display for Temperament {
+    /// Writes the canonical mapping in bra notation, e.g. `[<1 0 -4], <0 1 4]}`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{}}}",
+            self.mapping
+                .iter()
+                .map(|row| format!("<{}]", row.iter().join(" ")))
+                .join(", ")
+        )
+    }
+}
+
+/// A basis of the JI subgroup spanned by `steps`, in Hermite normal form,
+/// with each basis element written as a ratio greater than 1.
+///
+/// The JI tuning of a ternary scale represents rank-3 JI on this subgroup.
+pub fn subgroup_basis(steps: &[Monzo]) -> Vec<Monzo> {
+    let rows = steps
+        .iter()
+        .map(|m| m.into_inner().iter().map(|&x| x as i64).collect())
+        .collect();
+    hnf(rows)
+        .into_iter()
+        .map(|row| {
+            let m = Monzo::from_slice(&row.iter().map(|&x| x as i32).collect::<Vec<_>>());
+            if m.cents() < 0.0 { -m } else { m }
+        })
+        .collect()
+}
+
+/// The name of a JI subgroup in dot notation, e.g. "2.3.7" or "2.9.5/3".
+pub fn subgroup_name(basis: &[Monzo]) -> String {
+    basis
+        .iter()
+        .map(|m| {
+            m.try_to_ratio().map_or_else(
+                || m.to_string(),
+                |r| {
+                    if r.denom() == 1 {
+                        r.numer().to_string()
+                    } else {
+                        r.to_string()
+                    }
+                },
+            )
+        })
+        .join(".")
+}
+
+/// The name of the temperament a JI tuning of a scale's steps represents once `commas` are tempered out
+/// (see [`Temperament::from_step_tuning`]): the name of a well-known temperament if it is one,
+/// JI on the subgroup spanned by the steps if nothing is tempered out, e.g. "2.3.7 JI" for diasem
+/// tuned with 9/8, 28/27 and 64/63, and otherwise the prime limit and the commas.
+pub fn step_tuning_name(steps: &[Monzo], commas: &[Monzo]) -> Result<String, TemperamentError> {
+    let temperament = Temperament::from_step_tuning(steps, commas)?;
+    if let Some(name) = temperament.name() {
+        return Ok(name.to_string());
+    }
+    if temperament.nullity() == 0 {
+        return Ok(format!("{} JI", subgroup_name(&subgroup_basis(steps))));
+    }
+    let prime = prime_of_dim(temperament.dim()).expect("a temperament's dimension is checked");
+    Ok(format!(
+        "{prime}-limit tempering out {}",
+        commas
+            .iter()
+            .map(|m| {
+                m.try_to_ratio()
+                    .map_or_else(|| m.to_string(), |r| r.to_string())
+            })
+            .join(", ")
+    ))
+}
+
+fn check_dim(dim: usize) -> Result<(), TemperamentError> {
+    if dim == 0 || dim > SMALL_PRIMES_COUNT {
+        Err(TemperamentError::BadDimension(dim))
+    } else {
+        Ok(())
+    }
+}
+
+/// The first `dim` entries of a monzo widened to `i64`, failing if any later entry is nonzero.
+fn truncate(arr: &[i32; SMALL_PRIMES_COUNT], dim: usize) -> Result<Vec<i64>, TemperamentError> {
+    if arr[dim..].iter().any(|&x| x != 0) {
+        return Err(TemperamentError::OutsidePrimeLimit);
+    }
+    Ok(arr[..dim].iter().map(|&x| x as i64).collect())
+}
+
+fn gcd_i64(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Bring the first `ncols` columns of `m` to row echelon form using unimodular row operations.
+/// Returns the number of pivots; rows from that index on are zero in the first `ncols` columns.
+fn echelon(m: &mut [Vec<i64>], ncols: usize) -> usize {
+    let mut pivot_row = 0;
+    for col in 0..ncols {
+        if pivot_row == m.len() {
+            break;
+        }
+        // Euclid's algorithm on the column, below the pivot row
+        while let Some(best) = (pivot_row..m.len())
+            .filter(|&r| m[r][col] != 0)
+            .min_by_key(|&r| m[r][col].abs())
+        {
+            m.swap(pivot_row, best);
+            let mut done = true;
+            for r in pivot_row + 1..m.len() {
+                let q = m[r][col] / m[pivot_row][col];
+                if q != 0 {
+                    for c in 0..m[r].len() {
+                        m[r][c] -= q * m[pivot_row][c];
+                    }
+                }
+                done &= m[r][col] == 0;
+            }
+            if done {
+                break;
+            }
+        }
+        if m[pivot_row][col] == 0 {
+            continue;
+        }
+        if m[pivot_row][col] < 0 {
+            for x in m[pivot_row].iter_mut() {
+                *x = -*x;
+            }
+        }
+        // Reduce the entries above the pivot
+        for r in 0..pivot_row {
+            let q = m[r][col].div_euclid(m[pivot_row][col]);
+            if q != 0 {
+                for c in 0..m[r].len() {
+                    m[r][c] -= q * m[pivot_row][c];
+                }
+            }
+        }
+        pivot_row += 1;
+    }
+    pivot_row
+}
+
+/// The Hermite normal form of the row lattice spanned by `rows`, without zero rows.
+pub fn hnf(mut rows: Vec<Vec<i64>>) -> Vec<Vec<i64>> {
+    let ncols = rows.first().map_or(0, Vec::len);
+    let rank = echelon(&mut rows, ncols);
+    rows.truncate(rank);
+    rows
+}
+
+/// A basis of the integer nullspace of `rows` (vectors of length `dim`).
+/// The basis spans a saturated lattice: if `k * v` is in it for some integer `k != 0`, so is `v`.
+pub fn nullspace(rows: &[Vec<i64>], dim: usize) -> Vec<Vec<i64>> {
+    // Row-reduce [rows^T | I]; the identity part of the rows that become zero on the left is the nullspace.
+    let r = rows.len();
+    let mut augmented: Vec<Vec<i64>> = (0..dim)
+        .map(|i| {
+            let mut row: Vec<i64> = rows.iter().map(|v| v[i]).collect();
+            row.extend((0..dim).map(|j| i64::from(i == j)));
+            row
+        })
+        .collect();
+    let rank = echelon(&mut augmented, r);
+    augmented[rank..]
+        .iter()
+        .map(|row| row[r..].to_vec())
+        .collect()
+}
+
+/// The determinant of a square integer matrix using fraction-free Bareiss elimination.
+pub fn determinant(m: &[Vec<i64>]) -> i64 {
+    let n = m.len();
+    if n == 0 {
+        return 1;
+    }
+    let mut a: Vec<Vec<i128>> = m
+        .iter()
+        .map(|row| row.iter().map(|&x| x as i128).collect())
+        .collect();
+    let mut sign = 1;
+    let mut prev = 1i128;
+    for k in 0..n - 1 {
+        if a[k][k] == 0 {
+            let Some(swap) = (k + 1..n).find(|&r| a[r][k] != 0) else {
+                return 0;
+            };
+            a.swap(k, swap);
+            sign = -sign;
+        }
+        for i in k + 1..n {
+            for j in k + 1..n {
+                a[i][j] = (a[i][j] * a[k][k] - a[i][k] * a[k][j]) / prev;
+            }
+        }
+        prev = a[k][k];
+    }
+    (sign * a[n - 1][n - 1]) as i64
+}
+
+/// The `dim`-prime limit of a list of monzos: the smallest number of primes containing all of them.
+pub fn prime_limit_dim(monzos: &[Monzo]) -> usize {
+    (0..SMALL_PRIMES_COUNT)
+        .rev()
+        .find(|&i| monzos.iter().any(|m| m[i] != 0))
+        .map_or(1, |i| i + 1)
+}
+
+/// The largest prime in the `dim`-prime limit, or `None` if `dim` is 0 or more than `SMALL_PRIMES_COUNT`.
+pub fn prime_of_dim(dim: usize) -> Option<u32> {
+    dim.checked_sub(1)
+        .and_then(|i| SMALL_PRIMES.get(i))
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hnf_and_nullspace() {
+        assert_eq!(
+            hnf(vec![vec![12, 19, 28], vec![19, 30, 44]]),
+            vec![vec![1, 0, -4], vec![0, 1, 4]]
+        );
+        assert_eq!(
+            nullspace(&[vec![1, 0, -4], vec![0, 1, 4]], 3),
+            vec![vec![4, -4, 1]]
+        );
+        // The nullspace of nothing is everything.
+        assert_eq!(nullspace(&[], 2), vec![vec![1, 0], vec![0, 1]]);
+        assert_eq!(determinant(&[vec![2, 1], vec![7, 4]]), 1);
+        assert_eq!(
+            determinant(&[vec![0, 1, 0], vec![1, 0, 0], vec![0, 0, 1]]),
+            -1
+        );
+    }
+
+    #[test]
+    fn test_mapping_and_commas() {
+        let meantone = Temperament::from_commas(&[Monzo::try_new(81, 80).unwrap()], 3).unwrap();
+        assert_eq!(meantone.mapping(), vec![val![1, 0, -4], val![0, 1, 4]]);
+        assert_eq!(meantone.to_string(), "[<1 0 -4], <0 1 4]}");
+        assert_eq!(meantone.wedgie(), vec![1, 4, 4]);
+        assert!(meantone.tempers_out(monzo![-4, 4, -1]));
+        assert_eq!(meantone.map(Monzo::PYTH_5TH), vec![-1, 1]);
+        assert_eq!(
+            meantone.split(20),
+            Some(vec![val![5, 8, 12], val![7, 11, 16]])
+        );
+
+        // 24 & 19 is meantone too, once the mapping is defactored.
+        assert_eq!(
+            Temperament::from_vals(&[val![24, 38, 56], val![19, 30, 44]], 3).unwrap(),
+            meantone
+        );
+        assert!(matches!(
+            Temperament::from_commas(&[Monzo::try_new(64, 63).unwrap()], 3),
+            Err(TemperamentError::OutsidePrimeLimit)
+        ));
+        assert_eq!(
+            Temperament::from_commas(&[], 0),
+            Err(TemperamentError::BadDimension(0))
+        );
+    }
+
+    #[test]
+    fn test_join_meet_and_names() {
+        let marvel = Temperament::from_commas(&[Monzo::try_new(225, 224).unwrap()], 4).unwrap();
+        let sensamagic = Temperament::from_commas(&[Monzo::try_new(245, 243).unwrap()], 4).unwrap();
+        assert_eq!(marvel.name(), Some("marvel"));
+        assert_eq!(marvel.rank(), 3);
+        let magic = marvel.meet(&sensamagic).unwrap();
+        assert_eq!(magic.rank(), 2);
+        assert_eq!(magic.name(), Some("septimal magic"));
+        assert_eq!(
+            marvel.join(&sensamagic).unwrap(),
+            Temperament::just(4).unwrap()
+        );
+        assert!(matches!(
+            marvel.join(&Temperament::just(3).unwrap()),
+            Err(TemperamentError::DimensionMismatch(4, 3))
+        ));
+    }
+
+    #[test]
+    fn test_subgroup() {
+        // Diasem's JI tuning spans the 2.3.7 subgroup.
+        let diasem = [monzo![-3, 2], monzo![2, -3, 0, 1], monzo![6, -2, 0, -1]];
+        assert_eq!(subgroup_name(&subgroup_basis(&diasem)), "2.3.7");
+        // Zarlino spans the whole 5-limit.
+        let zarlino = [monzo![-3, 2], monzo![1, -2, 1], monzo![4, -1, -1]];
+        assert_eq!(subgroup_name(&subgroup_basis(&zarlino)), "2.3.5");
+        assert_eq!(step_tuning_name(&zarlino, &[]).unwrap(), "2.3.5 JI");
+        // A JI tuning where L = m + s only spans a rank-2 subgroup.
+        let dependent = [monzo![-3, 2], monzo![4, -1, -1], monzo![-7, 3, 1]];
+        assert_eq!(subgroup_basis(&dependent).len(), 2);
+        assert_eq!(prime_limit_dim(&diasem), 4);
+        assert_eq!(prime_of_dim(4), Some(7));
+        assert_eq!(prime_of_dim(0), None);
+        assert_eq!(prime_of_dim(SMALL_PRIMES_COUNT + 1), None);
+    }
+
+    #[test]
+    fn test_step_tuning_names() {
+        let diasem = [monzo![-3, 2], monzo![2, -3, 0, 1], monzo![6, -2, 0, -1]];
+        assert_eq!(step_tuning_name(&diasem, &[]).unwrap(), "2.3.7 JI");
+        let archytas = Monzo::try_new(64, 63).unwrap();
+        assert_eq!(step_tuning_name(&diasem, &[archytas]).unwrap(), "archytas");
+        // Zarlino in meantone, on the 5-limit of its steps
+        let zarlino = [monzo![-3, 2], monzo![1, -2, 1], monzo![4, -1, -1]];
+        let syntonic = Monzo::try_new(81, 80).unwrap();
+        assert_eq!(
+            Temperament::from_step_tuning(&zarlino, &[syntonic]).unwrap(),
+            Temperament::from_commas(&[syntonic], 3).unwrap()
+        );
+        assert_eq!(step_tuning_name(&zarlino, &[syntonic]).unwrap(), "meantone");
+        // A comma beyond the steps' prime limit widens it.
+        let marvel = Monzo::try_new(225, 224).unwrap();
+        assert_eq!(step_tuning_name(&zarlino, &[marvel]).unwrap(), "marvel");
+        let hemimean = Monzo::try_new(3136, 3125).unwrap();
+        assert_eq!(
+            step_tuning_name(&diasem, &[hemimean]).unwrap(),
+            "7-limit tempering out 3136/3125"
+        );
+    }
+}
@@ -0,0 +1,399 @@
+//! Concrete tunings of scale words.
+//!
+//! A scale word only records which step size occurs where. To get actual pitches
+//! we also need a size for each letter. This module provides [`StepTuning`], which
+//! assigns a size to each step letter in one of three ways:
+//!
+//! - JI step sizes given as monzos (e.g. L = 9/8, m = 28/27, s = 64/63)
+//! - Step counts in an equal division of an equave (e.g. 4\26, 2\26, 1\26)
+//! - Arbitrary step sizes in cents
+//!
+//! A tuning must have a step size for every letter of the words it is used with, which
+//! [`StepTuning::check_word`] checks.
+//!
+//! # Examples
+//!
+//! ```
+//! use ternary::tuning::StepTuning;
+//! use ternary::ji_ratio::RawJiRatio;
+//! use ternary::monzo;
+//!
+//! // Diasem (LmLsLmLsL) in JI
+//! let diasem = [0, 1, 0, 2, 0, 1, 0, 2, 0];
+//! let ji = StepTuning::Ji(vec![monzo![-3, 2], monzo![2, -3, 0, 1], monzo![6, -2, 0, -1]]);
+//! assert!((ji.equave_cents(&diasem) - 1200.0).abs() < 1e-9);
+//!
+//! // The same scale in 26edo
+//! let ed = StepTuning::Ed { steps: vec![4, 2, 1], ed: 26, equave: RawJiRatio::OCTAVE };
+//! assert_eq!(ed.pitches_cents(&diasem).len(), 9);
+//! ```
+
+use crate::equal::steps_as_cents;
+use crate::interval::Dyad;
+use crate::ji_ratio::RawJiRatio;
+use crate::monzo::Monzo;
+use crate::words::{CountVector, Letter};
+
+/// A tuning of the step sizes of a scale word, with one entry per step letter.
+///
+/// Entry `i` is the size of letter `i`, so for ternary scales the entries are
+/// the sizes of L, m and s in that order.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StepTuning {
+    /// JI step sizes as monzos.
+    Ji(Vec<Monzo>),
+    /// Step sizes as step counts of `ed`-ed<`equave`>.
+    Ed {
+        steps: Vec<i32>,
+        ed: i32,
+        equave: RawJiRatio,
+    },
+    /// Step sizes in cents.
+    Cents(Vec<f64>),
+}
+
+/// Error type for step size strings that can't be read as a tuning.
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum BadTuning {
+    /// No step sizes were given.
+    #[error("no step sizes were given")]
+    Empty,
+    /// A step size could not be parsed.
+    #[error("could not read step size \"{0}\"")]
+    Unparseable(String),
+    /// A JI step size was outside the supported prime limit.
+    #[error("step size {0} exceeds the supported prime limit")]
+    ExceededPrimeLimit(String),
+    /// ED step sizes did not all refer to the same ED.
+    #[error("ED step sizes must all use the same ED")]
+    MixedEds,
+    /// A tuning was needed but none was given; names what needed it.
+    #[error("{0} needs a tuning")]
+    Missing(&'static str),
+    /// The tuning has no step size for some letter of a scale word.
+    #[error("expected a step size for each of {letters} step letters, got {steps}")]
+    TooFewSteps { steps: usize, letters: usize },
+}
+
+impl StepTuning {
+    /// The number of step sizes in the tuning.
+    pub fn arity(&self) -> usize {
+        match self {
+            Self::Ji(steps) => steps.len(),
+            Self::Ed { steps, .. } => steps.len(),
+            Self::Cents(steps) => steps.len(),
+        }
+    }
+
+    /// Fail with [`BadTuning::TooFewSteps`] unless the tuning has a step size for each letter of `word`.
+    /// The functions taking a word or an interval panic on letters without a step size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ternary::tuning::{BadTuning, StepTuning};
+    ///
+    /// let tuning = StepTuning::from_strings(&["9/8", "28/27"]).unwrap();
+    /// assert_eq!(tuning.check_word(&[0, 1, 0, 1]), Ok(()));
+    /// assert_eq!(
+    ///     tuning.check_word(&[0, 1, 0, 2]),
+    ///     Err(BadTuning::TooFewSteps { steps: 2, letters: 3 })
+    /// );
+    /// ```
+    pub fn check_word(&self, word: &[Letter]) -> Result<(), BadTuning> {
+        let letters = word.iter().max().map_or(0, |&letter| letter + 1);
+        if letters <= self.arity() {
+            Ok(())
+        } else {
+            Err(BadTuning::TooFewSteps {
+                steps: self.arity(),
+                letters,
+            })
+        }
+    }
+
+    /// The size of each step letter in cents.
+    pub fn step_cents(&self) -> Vec<f64> {
+        match self {
+            Self::Ji(steps) => steps.iter().map(|m| m.cents()).collect(),
+            Self::Ed { steps, ed, equave } => steps
+                .iter()
+                .map(|s| steps_as_cents(*s, *ed as f64, *equave))
+                .collect(),
+            Self::Cents(steps) => steps.clone(),
+        }
+    }
+
+    /// The size in cents of an interval given by its step content.
+    pub fn interval_cents(&self, interval: &CountVector<Letter>) -> f64 {
+        let step_cents = self.step_cents();
+        interval
+            .into_inner()
+            .into_iter()
+            .map(|(letter, count)| step_cents[letter] * count as f64)
+            .sum()
+    }
+
+    /// The JI interval with the given step content, if the tuning is a JI tuning.
+    pub fn interval_monzo(&self, interval: &CountVector<Letter>) -> Option<Monzo> {
+        if let Self::Ji(steps) = self {
+            Some(
+                interval
+                    .into_inner()
+                    .into_iter()
+                    .map(|(letter, count)| steps[letter] * count)
+                    .sum(),
+            )
+        } else {
+            None
+        }
+    }
+
+    /// The step count of an interval in the ED, if the tuning is an ED tuning.
+    pub fn interval_ed_steps(&self, interval: &CountVector<Letter>) -> Option<i32> {
+        if let Self::Ed { steps, .. } = self {
+            Some(
+                interval
+                    .into_inner()
+                    .into_iter()
+                    .map(|(letter, count)| steps[letter] * count)
+                    .sum(),
+            )
+        } else {
+            None
+        }
+    }
+
+    /// The size in cents of the equave of `word` under this tuning.
+    pub fn equave_cents(&self, word: &[Letter]) -> f64 {
+        self.interval_cents(&CountVector::from_slice(word))
+    }
+
+    /// The pitches of `word` in cents from the tonic, in cumulative form
+    /// (the last entry is the equave).
+    pub fn pitches_cents(&self, word: &[Letter]) -> Vec<f64> {
+        let step_cents = self.step_cents();
+        word.iter()
+            .scan(0.0, |acc, letter| {
+                *acc += step_cents[*letter];
+                Some(*acc)
+            })
+            .collect()
+    }
+
+    /// The pitch in cents of scale degree `degree` of `word` above the tonic,
+    /// where degrees outside `0..word.len()` are in other equaves.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ternary::tuning::StepTuning;
+    ///
+    /// let diatonic = [0, 0, 1, 0, 0, 0, 1];
+    /// let tuning = StepTuning::Cents(vec![200.0, 100.0]);
+    /// assert_eq!(tuning.degree_cents(&diatonic, 2), 400.0);
+    /// assert_eq!(tuning.degree_cents(&diatonic, 9), 1600.0);
+    /// assert_eq!(tuning.degree_cents(&diatonic, -1), -100.0);
+    /// ```
+    pub fn degree_cents(&self, word: &[Letter], degree: i32) -> f64 {
+        if word.is_empty() {
+            return 0.0;
+        }
+        let len = word.len() as i32;
+        let (equaves, degree) = (degree.div_euclid(len), degree.rem_euclid(len) as usize);
+        let pitch = if degree == 0 {
+            0.0
+        } else {
+            self.pitches_cents(word)[degree - 1]
+        };
+        equaves as f64 * self.equave_cents(word) + pitch
+    }
+
+    /// The pitches of `word` as monzos from the tonic, in cumulative form,
+    /// if the tuning is a JI tuning.
+    pub fn pitches_monzo(&self, word: &[Letter]) -> Option<Vec<Monzo>> {
+        if let Self::Ji(steps) = self {
+            Some(
+                word.iter()
+                    .scan(Monzo::UNISON, |acc, letter| {
+                        *acc += steps[*letter];
+                        Some(*acc)
+                    })
+                    .collect(),
+            )
+        } else {
+            None
+        }
+    }
+
+    /// Read a tuning from the step size strings used in the result tables,
+    /// i.e. the output of [`crate::sig_to_ji_tunings`] (`"9/8"`) or
+    /// [`crate::sig_to_ed_tunings`] (`"4\22"`, `"3\13<3/1>"`).
+    /// Plain numbers are read as cents, with or without a trailing `c` or `¢`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use ternary::tuning::StepTuning;
+    /// use ternary::ji_ratio::RawJiRatio;
+    ///
+    /// let ed = StepTuning::from_strings(&["4\\22", "3\\22", "1\\22"]).unwrap();
+    /// assert_eq!(ed, StepTuning::Ed { steps: vec![4, 3, 1], ed: 22, equave: RawJiRatio::OCTAVE });
+    ///
+    /// let ji = StepTuning::from_strings(&["9/8", "28/27", "64/63"]).unwrap();
+    /// assert_eq!(ji.arity(), 3);
+    ///
+    /// let cents = StepTuning::from_strings(&["203.9c", "150¢", "90"]).unwrap();
+    /// assert_eq!(cents, StepTuning::Cents(vec![203.9, 150.0, 90.0]));
+    /// ```
+    pub fn from_strings<S: AsRef<str>>(steps: &[S]) -> Result<Self, BadTuning> {
+        if steps.is_empty() {
+            return Err(BadTuning::Empty);
+        }
+        let steps: Vec<&str> = steps.iter().map(|s| s.as_ref().trim()).collect();
+        if steps.iter().all(|s| s.contains('\\')) {
+            let mut result: Vec<i32> = vec![];
+            let mut ed_and_equave: Option<(i32, RawJiRatio)> = None;
+            for s in steps {
+                let (count, ed, equave) = parse_ed_step(s)?;
+                match ed_and_equave {
+                    Some(prev) if prev != (ed, equave) => return Err(BadTuning::MixedEds),
+                    _ => ed_and_equave = Some((ed, equave)),
+                }
+                result.push(count);
+            }
+            let (ed, equave) = ed_and_equave.ok_or(BadTuning::Empty)?;
+            Ok(Self::Ed {
+                steps: result,
+                ed,
+                equave,
+            })
+        } else if steps.iter().all(|s| s.contains('/')) {
+            steps
+                .into_iter()
+                .map(|s| {
+                    let ratio = parse_ratio(s)?;
+                    Monzo::try_from_ratio(ratio)
+                        .map_err(|_| BadTuning::ExceededPrimeLimit(s.to_string()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(Self::Ji)
+        } else {
+            steps
+                .into_iter()
+                .map(|s| {
+                    s.strip_suffix(['c', '¢'])
+                        .unwrap_or(s)
+                        .trim_end()
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|c| c.is_finite())
+                        .ok_or_else(|| BadTuning::Unparseable(s.to_string()))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(Self::Cents)
+        }
+    }
+}
+
+/// Parse a JI ratio written as `"n/d"`.
+pub(crate) fn parse_ratio(s: &str) -> Result<RawJiRatio, BadTuning> {
+    if s.contains('/') {
+        s.parse().map_err(|_| BadTuning::Unparseable(s.to_string()))
+    } else {
+        Err(BadTuning::Unparseable(s.to_string()))
+    }
+}
+
+/// Parse an ED step written as `"k\n"` or `"k\n<p/q>"`.
+fn parse_ed_step(s: &str) -> Result<(i32, i32, RawJiRatio), BadTuning> {
+    let bad = || BadTuning::Unparseable(s.to_string());
+    let (count, rest) = s.split_once('\\').ok_or_else(bad)?;
+    let (ed, equave) = if let Some((ed, equave)) = rest.split_once('<') {
+        let equave = equave.strip_suffix('>').ok_or_else(bad)?;
+        // Whole-number equaves may be written without a denominator, as in SonicWeave (`<3>`).
+        let equave = if equave.contains('/') {
+            parse_ratio(equave)?
+        } else {
+            parse_ratio(&format!("{equave}/1"))?
+        };
+        (ed, equave)
+    } else {
+        (rest, RawJiRatio::OCTAVE)
+    };
+    let count = count.trim().parse::<i32>().map_err(|_| bad())?;
+    let ed = ed.trim().parse::<i32>().map_err(|_| bad())?;
+    if ed <= 0 || equave == RawJiRatio::UNISON {
+        Err(bad())
+    } else {
+        Ok((count, ed, equave))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_strings() {
+        assert_eq!(
+            StepTuning::from_strings(&["9/8", "28/27", "64/63"]),
+            Ok(StepTuning::Ji(vec![
+                monzo![-3, 2],
+                monzo![2, -3, 0, 1],
+                monzo![6, -2, 0, -1]
+            ]))
+        );
+        assert_eq!(
+            StepTuning::from_strings(&["3\\13<3/1>", "2\\13<3/1>", "1\\13<3/1>"]),
+            Ok(StepTuning::Ed {
+                steps: vec![3, 2, 1],
+                ed: 13,
+                equave: RawJiRatio::TRITAVE
+            })
+        );
+        assert_eq!(
+            StepTuning::from_strings(&["3\\13<3>", "2\\13<3/1>"]),
+            Ok(StepTuning::Ed {
+                steps: vec![3, 2],
+                ed: 13,
+                equave: RawJiRatio::TRITAVE
+            })
+        );
+        assert_eq!(
+            StepTuning::from_strings(&["200", "100.5", "50"]),
+            Ok(StepTuning::Cents(vec![200.0, 100.5, 50.0]))
+        );
+        assert_eq!(
+            StepTuning::from_strings(&["4\\22", "3\\21"]),
+            Err(BadTuning::MixedEds)
+        );
+        assert!(StepTuning::from_strings(&["9/0", "1/1"]).is_err());
+        assert!(StepTuning::from_strings::<&str>(&[]).is_err());
+    }
+
+    #[test]
+    fn test_pitches() {
+        let diasem = [0, 1, 0, 2, 0, 1, 0, 2, 0];
+        let ji = StepTuning::Ji(vec![
+            monzo![-3, 2],
+            monzo![2, -3, 0, 1],
+            monzo![6, -2, 0, -1],
+        ]);
+        let pitches = ji.pitches_monzo(&diasem).unwrap();
+        assert_eq!(pitches[1], monzo![-1, -1, 0, 1]); // 7/6
+        assert_eq!(pitches[8], Monzo::OCTAVE);
+
+        let ed = StepTuning::Ed {
+            steps: vec![4, 2, 1],
+            ed: 26,
+            equave: RawJiRatio::OCTAVE,
+        };
+        assert_eq!(
+            ed.interval_ed_steps(&CountVector::from_slice(&diasem)),
+            Some(26)
+        );
+        let cents = ed.pitches_cents(&diasem);
+        assert!((cents[8] - 1200.0).abs() < 1e-9);
+    }
+}
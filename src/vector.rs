@@ -2,6 +2,7 @@
 use std::iter::Sum;
 use std::ops::{Add, AddAssign, Div, Index, Mul, Neg, Sub, SubAssign};
 
+use crate::ji_ratio::BadJiArith;
 use crate::primes::SMALL_PRIMES_COUNT;
 
 /// A fixed-size vector of `SMALL_PRIMES_COUNT` i32 elements, replacing nalgebra::SVector
@@ -70,6 +71,15 @@ impl Vector {
     pub fn is_zero(&self) -> bool {
         self.0.iter().all(|&x| x == 0)
     }
+
+    /// Divide every element by `scalar`, returning an error instead of panicking if `scalar == 0`.
+    pub fn try_div(self, scalar: i32) -> Result<Vector, BadJiArith> {
+        if scalar == 0 {
+            Err(BadJiArith::DivByZero)
+        } else {
+            Ok(self / scalar)
+        }
+    }
 }
 
 impl Index<usize> for Vector {
@@ -43,11 +43,12 @@
 //! ```
 
 use itertools::Itertools;
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::cmp::{Ordering, max};
 use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::hash::Hash;
 
+use crate::error::Error;
 use crate::helpers::{ScaleError, gcd, modinv};
 
 /// A step letter representing a step size class.
@@ -85,6 +86,7 @@ impl Subtendable for CountVector<Letter> {
 /// The [chirality](https://en.xen.wiki/w/Chirality) (handedness) of a scale.
 ///
 /// Compares a scale to its reversal to determine symmetry.
+/// Serialized as `"Left"`, `"Achiral"` or `"Right"`; the lowercase names are also accepted.
 ///
 /// # Examples
 ///
@@ -99,13 +101,16 @@ impl Subtendable for CountVector<Letter> {
 /// let right_handed = [0, 1, 0, 2, 0, 1, 0, 2, 0];  // diasem
 /// assert_eq!(chirality(&right_handed), Chirality::Right);
 /// ```
-#[derive(Copy, Clone, Debug, Hash, PartialEq, Serialize)]
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Serialize, Deserialize)]
 pub enum Chirality {
     /// Scale word > reversed word (lexicographically, in canonical form).
+    #[serde(alias = "left")]
     Left,
     /// Scale equals its reversal as a circular word.
+    #[serde(alias = "achiral")]
     Achiral,
     /// Scale word < reversed word (lexicographically, in canonical form).
+    #[serde(alias = "right")]
     Right,
 }
 
@@ -302,26 +307,38 @@ pub fn countvector_to_slice(v: CountVector<usize>) -> Vec<i32> {
     }
 }
 
+/// Serialized as the array of counts of each letter, so `5L2m2s` is `[5, 2, 2]`.
+impl Serialize for CountVector<Letter> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        countvector_to_slice(self.clone()).serialize(serializer)
+    }
+}
+
+/// Deserialized from the array of counts of each letter.
+impl<'de> Deserialize<'de> for CountVector<Letter> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let counts = Vec::<i32>::deserialize(deserializer)?;
+        Ok(CountVector::from_tuples(
+            counts
+                .into_iter()
+                .enumerate()
+                .filter(|&(_, count)| count != 0),
+        ))
+    }
+}
+
 /// Treating `scale` as a circular string,
-/// take a slice of length `subword_length` from `degree`; assumes `subword_length` <= `scale`.len().
-/// Reduce `degree` first.
+/// take a slice of length `subword_length` from `degree`, wrapping around as many times as needed.
+/// Reduce `degree` first. An empty `scale` gives an empty slice.
 pub fn word_on_degree<T>(scale: &[T], degree: usize, subword_length: usize) -> Vec<T>
 where
     T: Clone,
 {
-    let rotated = rotate(scale, degree);
-    if subword_length < scale.len() {
-        Vec::<_>::from(&rotated[..subword_length])
-    } else {
-        let prefix_for_div = rotated
-            .iter()
-            .cloned()
-            .cycle()
-            .take(subword_length / scale.len())
-            .collect::<Vec<_>>();
-        let suffix_for_rem = &rotated[0..subword_length];
-        [&prefix_for_div, suffix_for_rem].concat()
-    }
+    rotate(scale, degree)
+        .into_iter()
+        .cycle()
+        .take(subword_length)
+        .collect()
 }
 
 /// The dyad on the specified degree of the scale as a `CountVector`.
@@ -477,9 +494,11 @@ where
                         .filter_map(|dyad| dyad.get(letter))
                         .copied()
                         .collect::<BTreeSet<_>>();
-                    // the differences to collect
-                    *counts.last().expect("`counts` should be nonempty")
-                        - *counts.first().expect("`counts` should be nonempty") // this will always be >= 0 for a nonempty `BTreeSet`
+                    // the differences to collect; this will always be >= 0 for a nonempty `BTreeSet`
+                    match (counts.first(), counts.last()) {
+                        (Some(min), Some(max)) => max - min,
+                        _ => 0,
+                    }
                 })
             })
             .max();
@@ -495,15 +514,29 @@ where
 /// We chose the darkest mode rather than the brightest because this is the mode with brightness == 0.
 /// Bjorklund's algorithm is asymptotically faster, but this Bresenham implementation is faster for
 /// practical MOS sizes.
+///
+/// # Panics
+///
+/// Panics if `a + b == 0`; see [`try_brightest_mos_mode_and_gener_bresenham`].
 pub fn brightest_mos_mode_and_gener_bresenham(
     a: usize,
     b: usize,
 ) -> (Vec<Letter>, CountVector<Letter>) {
+    try_brightest_mos_mode_and_gener_bresenham(a, b).expect("a MOS needs at least one step")
+}
+
+/// [`brightest_mos_mode_and_gener_bresenham`], returning an error for the empty MOS `a == b == 0`.
+pub fn try_brightest_mos_mode_and_gener_bresenham(
+    a: usize,
+    b: usize,
+) -> Result<(Vec<Letter>, CountVector<Letter>), ScaleError> {
+    if a + b == 0 {
+        return Err(ScaleError::CannotMakeScale);
+    }
     let d = gcd(a as u32, b as u32) as usize;
     if d == 1 {
-        let count_gen_steps = modinv(b as i32, (a + b) as i32)
-                .expect("The dark generator is a (|s|⁻¹ mod |scale|)-step, since stacking it |s| times results in the L step (mod period).")
-               as usize;
+        // The dark generator is a (|s|⁻¹ mod |scale|)-step, since stacking it |s| times results in the L step (mod period).
+        let count_gen_steps = modinv(b as i32, (a + b) as i32)? as usize;
         let mut result_scale: Vec<usize> = vec![];
         let (mut current_x, mut current_y) = (0usize, 0usize); // Start from the (0, 0) and walk until the dark generator is reached; we now know how many steps to walk.
         while current_x < a || current_y < b {
@@ -518,25 +551,40 @@ pub fn brightest_mos_mode_and_gener_bresenham(
             }
         }
         let result_gen = CountVector::from_slice(&result_scale[0..count_gen_steps]);
-        (result_scale, result_gen)
+        Ok((result_scale, result_gen))
     } else {
-        let (prim_mos, gener) = brightest_mos_mode_and_gener_bresenham(a / d, b / d);
-        (prim_mos.repeat(d), gener)
+        let (prim_mos, gener) = try_brightest_mos_mode_and_gener_bresenham(a / d, b / d)?;
+        Ok((prim_mos.repeat(d), gener))
     }
 }
 
 /// Return the brightest mode of the MOS aLbs and the bright generator, using Bjorklund's algorithm.
 /// The brightest mode is the lexicographically first rotation.
+///
+/// # Panics
+///
+/// Panics if `b == 0`; see [`try_brightest_mos_mode_and_gener_bjorklund`].
 pub fn brightest_mos_mode_and_gener_bjorklund(
     a: usize,
     b: usize,
 ) -> (Vec<Letter>, CountVector<Letter>) {
+    try_brightest_mos_mode_and_gener_bjorklund(a, b).expect("Bjorklund's algorithm needs an s step")
+}
+
+/// [`brightest_mos_mode_and_gener_bjorklund`], returning an error if there are no s steps.
+pub fn try_brightest_mos_mode_and_gener_bjorklund(
+    a: usize,
+    b: usize,
+) -> Result<(Vec<Letter>, CountVector<Letter>), ScaleError> {
+    // With no `second` subwords the loop below would never terminate.
+    if b == 0 {
+        return Err(ScaleError::CannotMakeScale);
+    }
     let d = gcd(a as u32, b as u32) as usize;
     if d == 1 {
         // The bright generator is a (b⁻¹ mod |scale|)-step, since stacking it `b` times results in the L step (mod period).
-        let count_gener_steps = modinv(b as i32, a as i32 + b as i32)
-            .expect("Should be ok because gcd(a + b, b) == gcd(a, b) == 1")
-            as usize;
+        // This is ok because gcd(a + b, b) == gcd(a, b) == 1.
+        let count_gener_steps = modinv(b as i32, a as i32 + b as i32)? as usize;
         // These are the seed strings we build the brightest MOS word from.
         // The algorithm uses two subwords at each step, iteratively appending the
         // lexicographically second subword to the lexicographically first subword to ensure
@@ -575,26 +623,38 @@ pub fn brightest_mos_mode_and_gener_bjorklund(
         scale.extend_from_slice(&second);
         // The bright generator is the first `count_gener_steps` of the scale.
         let gener = CountVector::from_slice(&scale[0..count_gener_steps]);
-        (scale, gener)
+        Ok((scale, gener))
     } else {
-        let (primitive_mos, gener) = brightest_mos_mode_and_gener_bjorklund(a / d, b / d);
-        (primitive_mos.repeat(d), gener)
+        let (primitive_mos, gener) = try_brightest_mos_mode_and_gener_bjorklund(a / d, b / d)?;
+        Ok((primitive_mos.repeat(d), gener))
     }
 }
 
 /// The mode of the MOS aLbs with a given brightness (count of bright generators up from root).
 /// Brightness is taken modulo (a + b), so any non-negative value is valid.
 /// Brightness 0 returns the darkest mode, brightness (a + b - 1) returns the brightest mode.
+///
+/// # Panics
+///
+/// Panics if `a + b == 0`; see [`try_mos_mode`].
 pub fn mos_mode(a: usize, b: usize, brightness: usize) -> Vec<Letter> {
+    try_mos_mode(a, b, brightness).expect("a MOS needs at least one step")
+}
+
+/// [`mos_mode`], returning an error for the empty MOS `a == b == 0`.
+pub fn try_mos_mode(a: usize, b: usize, brightness: usize) -> Result<Vec<Letter>, ScaleError> {
     let scale_len = a + b;
+    if scale_len == 0 {
+        return Err(ScaleError::CannotMakeScale);
+    }
     let brightness = brightness % scale_len;
     // Bresenham is faster for practical sizes
-    let (mos, bright_gener) = brightest_mos_mode_and_gener_bresenham(a, b);
+    let (mos, bright_gener) = try_brightest_mos_mode_and_gener_bresenham(a, b)?;
     let bright_gener_step_count: usize = bright_gener.len();
     // Rotate backwards from brightest mode by `(scale_len - 1 - brightness)` bright generators
     // which is equivalent to rotating forward by `brightness` dark generators from darkest mode
     let steps_from_brightest = (scale_len - 1 - brightness) * bright_gener_step_count;
-    rotate(&mos, steps_from_brightest)
+    Ok(rotate(&mos, steps_from_brightest))
 }
 
 /// Rotate a scale word left by `degree` positions (change mode).
@@ -609,6 +669,9 @@ pub fn mos_mode(a: usize, b: usize, brightness: usize) -> Vec<Letter> {
 /// assert_eq!(mixolydian, vec![0, 0, 1, 0, 0, 1, 0]);
 /// ```
 pub fn rotate<T: std::clone::Clone>(slice: &[T], degree: usize) -> Vec<T> {
+    if slice.is_empty() {
+        return vec![];
+    }
     let degree = degree % slice.len();
     if degree == 0 {
         slice.to_vec()
@@ -738,9 +801,23 @@ pub fn subst(template: &[Letter], x: Letter, filler: &[Letter]) -> Vec<Letter> {
 /// let only_contains_one_scale = mos_substitution_scales_one_perm(6, 5, 5);
 /// assert_eq!(only_contains_one_scale.len(), 1);
 /// ```
+///
+/// # Panics
+///
+/// Panics if `n1 + n2 == 0`; see [`try_mos_substitution_scales_one_perm`].
 pub fn mos_substitution_scales_one_perm(n0: usize, n1: usize, n2: usize) -> Vec<Vec<Letter>> {
-    let (template, _) = brightest_mos_mode_and_gener_bresenham(n0, n1 + n2);
-    let (filler, gener) = brightest_mos_mode_and_gener_bresenham(n1, n2);
+    try_mos_substitution_scales_one_perm(n0, n1, n2)
+        .expect("the filling MOS needs at least one step")
+}
+
+/// [`mos_substitution_scales_one_perm`], returning an error if the filling MOS is empty.
+pub fn try_mos_substitution_scales_one_perm(
+    n0: usize,
+    n1: usize,
+    n2: usize,
+) -> Result<Vec<Vec<Letter>>, ScaleError> {
+    let (template, _) = try_brightest_mos_mode_and_gener_bresenham(n0, n1 + n2)?;
+    let (filler, gener) = try_brightest_mos_mode_and_gener_bresenham(n1, n2)?;
     let filler = filler.into_iter().map(|x| x + 1).collect::<Vec<_>>();
     let gener_size = gener.len();
     let redundant_list: Vec<_> = (0..(n1 + n2))
@@ -753,30 +830,44 @@ pub fn mos_substitution_scales_one_perm(n0: usize, n1: usize, n2: usize) -> Vec<
         })
         .collect();
     // Canonicalize every scale and remove duplicates
-    redundant_list
+    Ok(redundant_list
         .into_iter()
         .map(|scale| least_mode(&scale))
         .sorted()
         .dedup()
-        .collect()
+        .collect())
 }
 
 /// The set of all [MOS substitution](https://en.xen.wiki/w/User:Inthar/MOS_substitution) ternary scales
 /// with the given step signature `sig`.
+///
+/// # Panics
+///
+/// Panics unless `sig` has three step counts, at most one of them zero; see [`try_mos_substitution_scales`].
 pub fn mos_substitution_scales(sig: &[usize]) -> Vec<Vec<Letter>> {
-    let (n0, n1, n2) = (sig[0], sig[1], sig[2]);
+    try_mos_substitution_scales(sig).expect("`sig` should be a ternary step signature")
+}
+
+/// [`mos_substitution_scales`], returning an error unless `sig` has three step counts, at most one of them zero.
+pub fn try_mos_substitution_scales(sig: &[usize]) -> Result<Vec<Vec<Letter>>, Error> {
+    let &[n0, n1, n2] = sig else {
+        return Err(Error::InvalidStepSig(sig.to_vec()));
+    };
+    if sig.iter().filter(|&&n| n == 0).count() > 1 {
+        return Err(Error::InvalidStepSig(sig.to_vec()));
+    }
 
     // Only need 3 permutations of (0, 1, 2) for the MOS substitution patterns n0*_ (n1*_ n2*_)
     let redundant_list = [
         // n0L (n1m n2s)
-        mos_substitution_scales_one_perm(n0, n1, n2),
+        try_mos_substitution_scales_one_perm(n0, n1, n2)?,
         // n1m (n0L n2s)
-        mos_substitution_scales_one_perm(n1, n2, n0)
+        try_mos_substitution_scales_one_perm(n1, n2, n0)?
             .into_iter()
             .map(|scale| scale.into_iter().map(|x| (x + 1) % 3).collect())
             .collect(),
         // n2s (n0L n1m)
-        mos_substitution_scales_one_perm(n2, n0, n1)
+        try_mos_substitution_scales_one_perm(n2, n0, n1)?
             .into_iter()
             .map(|scale| {
                 scale
@@ -788,12 +879,12 @@ pub fn mos_substitution_scales(sig: &[usize]) -> Vec<Vec<Letter>> {
     ]
     .concat();
     // Canonicalize every scale and remove duplicates
-    redundant_list
+    Ok(redundant_list
         .into_iter()
         .map(|scale| least_mode(&scale))
         .sorted()
         .dedup()
-        .collect()
+        .collect())
 }
 
 /// Whether `scale` is a MOS substitution scale with any choice of letter as template letter.
@@ -1046,6 +1137,31 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_serde() {
+        let sig = CountVector::from_slice(&[0, 1, 0, 2, 0, 1, 0, 2, 0]);
+        assert_eq!(serde_json::to_string(&sig).unwrap(), "[5,2,2]");
+        assert_eq!(
+            serde_json::from_str::<CountVector<Letter>>("[5,2,2]").unwrap(),
+            sig
+        );
+        // Zero counts are dropped rather than stored.
+        let interval = CountVector::from_slice(&[0, 2]);
+        assert_eq!(serde_json::to_string(&interval).unwrap(), "[1,0,1]");
+        assert_eq!(
+            serde_json::from_str::<CountVector<Letter>>("[1,0,1]").unwrap(),
+            interval
+        );
+        assert_eq!(
+            serde_json::to_string(&Chirality::Right).unwrap(),
+            r#""Right""#
+        );
+        assert_eq!(
+            serde_json::from_str::<Chirality>(r#""left""#).unwrap(),
+            Chirality::Left
+        );
+    }
+
     #[test]
     fn test_booth() {
         let blackdye = [2, 0, 1, 0, 2, 0, 1, 0, 2, 0];
@@ -1068,6 +1184,8 @@ mod tests {
             odd_two_steps,
             vec![vec![0, 1], vec![0, 2], vec![0, 1], vec![0, 2], vec![0, 2],]
         );
+        assert_eq!(word_on_degree(&[0, 1], 1, 5), vec![1, 0, 1, 0, 1]);
+        assert!(word_on_degree::<usize>(&[], 3, 2).is_empty());
     }
     #[test]
     fn test_spectrum() {
@@ -1166,4 +1284,40 @@ mod tests {
         assert_eq!(oneirotonic.0, vec![0, 0, 1, 0, 0, 1, 0, 1]);
         assert_eq!(oneirotonic.1.into_inner(), BTreeMap::from([(0, 2), (1, 1)]));
     }
+
+    #[test]
+    fn test_try_variants() {
+        assert_eq!(
+            try_brightest_mos_mode_and_gener_bresenham(0, 0),
+            Err(ScaleError::CannotMakeScale)
+        );
+        assert_eq!(
+            try_brightest_mos_mode_and_gener_bjorklund(2, 0),
+            Err(ScaleError::CannotMakeScale)
+        );
+        assert_eq!(
+            try_brightest_mos_mode_and_gener_bjorklund(5, 2).map(|(mos, _)| mos),
+            Ok(vec![0, 0, 0, 1, 0, 0, 1])
+        );
+        assert_eq!(try_mos_mode(0, 0, 3), Err(ScaleError::CannotMakeScale));
+        assert_eq!(try_mos_mode(5, 2, 0), Ok(mos_mode(5, 2, 0)));
+        assert_eq!(
+            try_mos_substitution_scales_one_perm(3, 0, 0),
+            Err(ScaleError::CannotMakeScale)
+        );
+        assert_eq!(
+            try_mos_substitution_scales(&[5, 2]),
+            Err(Error::InvalidStepSig(vec![5, 2]))
+        );
+        assert_eq!(
+            try_mos_substitution_scales(&[5, 0, 0]),
+            Err(Error::InvalidStepSig(vec![5, 0, 0]))
+        );
+        assert_eq!(
+            try_mos_substitution_scales(&[5, 2, 2]),
+            Ok(mos_substitution_scales(&[5, 2, 2]))
+        );
+        assert!(least_mode(&[]).is_empty());
+        assert_eq!(block_balance::<usize>(&[]), 0);
+    }
 }
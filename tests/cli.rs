@@ -0,0 +1,272 @@
+//! Tests for the `ternary` command-line interface.
+
+#![cfg(feature = "cli")]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn ternary(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_ternary"))
+        .args(args)
+        .output()
+        .expect("failed to run the ternary binary")
+}
+
+#[test]
+fn word_json() {
+    let output = ternary(&["word", "LmLsLmLsL", "--ed-bound", "30", "--json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["profile"]["word"], "LLmLsLmLs");
+    assert_eq!(json["profile"]["mv"], 3);
+}
+
+#[test]
+fn word_table() {
+    let output = ternary(&["word", "LmLsLmLsL", "--ed-bound", "30"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Step signature  5L2m2s"));
+    assert!(stdout.contains("4\\26"));
+}
+
+#[test]
+fn sig_filters() {
+    let output = ternary(&["sig", "3L2m2s", "--mv", "3", "--json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["profiles"].as_array().unwrap().len(), 2);
+}
+
+#[test]
+fn sig_sort() {
+    let output = ternary(&["sig", "4L2m1s", "--sort", "mv", "--descending", "--json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let mvs: Vec<u64> = json["profiles"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|profile| profile["mv"].as_u64().unwrap())
+        .collect();
+    assert!(mvs.is_sorted_by(|a, b| a >= b));
+
+    let output = ternary(&["sig", "4L2m1s", "--sort", "size"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn names() {
+    let output = ternary(&["word", "Zarlino", "--ed-bound", "20", "--json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["profile"]["word"], "LmLsLms");
+    assert_eq!(json["profile"]["name"], "zarlino");
+
+    let output = ternary(&["names", "diasem", "--json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["word"], "LLmLsLmLs");
+    assert_eq!(json.as_array().unwrap().len(), 2);
+
+    let output = ternary(&["names", "--sig", "5L2m3s", "--json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["name"], "blackdye");
+    assert_eq!(json.as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn lattice() {
+    let output = ternary(&["lattice", "LLmLLms"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("Basis: "));
+
+    let output = ternary(&["lattice", "LmsLms"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("No 2D lattice: the scale repeats 2 times per equave"));
+    assert!(stdout.contains("layer"));
+
+    let output = ternary(&["lattice", "diasem", "--bases", "guide_frames", "--json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let bases = json.as_array().unwrap();
+    assert!(bases.len() > 1);
+    assert_eq!(bases[0]["score"], 0.0);
+    assert_eq!(bases[0]["coordinates"].as_array().unwrap().len(), 9);
+
+    let near_seventh = [
+        "lattice",
+        "diasem",
+        "--bases",
+        "near_interval",
+        "--interval",
+        "7/4",
+        "--tuning",
+        "9/8,28/27,64/63",
+        "--json",
+    ];
+    let output = ternary(&near_seventh);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json[0]["score"].as_f64().unwrap() < 1e-6);
+    let output = ternary(&near_seventh[..6]);
+    assert!(!output.status.success());
+    let mut two_steps = near_seventh;
+    two_steps[7] = "9/8,28/27";
+    assert!(!ternary(&two_steps).status.success());
+
+    let output = ternary(&[
+        "lattice",
+        "zarlino",
+        "--svg",
+        "-",
+        "--tuning",
+        "9/8, 10/9, 16/15",
+        "--chord",
+        "0,2,4",
+    ]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("<svg "));
+    assert!(stdout.contains(">1/1</text>"));
+    assert!(stdout.contains("class=\"chord\""));
+
+    let output = ternary(&["lattice", "diasem", "--svg", "-", "--chord", "0,x"]);
+    assert!(!output.status.success());
+    let output = ternary(&["lattice", "diasem", "--svg", "-", "--chord", "0,2,99"]);
+    assert!(!output.status.success());
+    let output = ternary(&["lattice", "LmLsLmLsL", "--svg", "-", "--tuning", "9/8"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn temper() {
+    let output = ternary(&[
+        "temper",
+        "diasem",
+        "--tuning",
+        "9/8,28/27,64/63",
+        "--comma",
+        "64/63",
+    ]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Temperament     archytas"));
+
+    let output = ternary(&["temper", "zarlino", "--tuning", "9/8,10/9,16/15", "--json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["temperament"], "2.3.5 JI");
+    assert_eq!(json["rank"], 3);
+
+    let output = ternary(&[
+        "temper",
+        "zarlino",
+        "--tuning",
+        "9/8,10/9,16/15",
+        "--comma",
+        "81/80",
+        "--method",
+        "cte",
+        "--json",
+    ]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["temperament"], "meantone");
+    let cents: Vec<f64> = json["step_cents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| c.as_f64().unwrap())
+        .collect();
+    assert!((cents[0] - cents[1]).abs() < 1e-9);
+    assert!((5.0 * cents[0] + 2.0 * cents[2] - 1200.0).abs() < 1e-9);
+
+    let output = ternary(&["temper", "diasem", "--tuning", "9/8,28/27"]);
+    assert!(!output.status.success());
+    let output = ternary(&["temper", "diasem", "--tuning", "4\\26,2\\26,1\\26"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn invalid_input() {
+    let output = ternary(&["word", "xyz"]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("`xyz` is not a valid scale word")
+    );
+
+    let output = ternary(&["word", "LLsLLLs"]);
+    assert!(!output.status.success());
+
+    let output = ternary(&["sig", "5L2m"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn batch() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ternary"))
+        .arg("batch")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run the ternary binary");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(
+            b"{\"id\": 1, \"type\": \"lattice\", \"word\": \"LLmLLms\"}\n\
+              {\"id\": 2, \"type\": \"profile\", \"word\": \"LL\"}\n",
+        )
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let responses: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["id"], 1);
+    assert!(responses[0]["result"]["coordinates"].is_array());
+    assert_eq!(responses[1]["error"]["code"], "invalid_word");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1 of 2 requests failed"));
+}
+
+#[test]
+fn catalog() {
+    let dir = std::env::temp_dir().join(format!("ternary-cli-catalog-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let catalog = dir.join("catalog.json");
+    let requests = dir.join("requests.jsonl");
+    let catalog_path = catalog.to_str().unwrap();
+
+    let output = ternary(&["catalog", "--max-notes", "6", catalog_path]);
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&catalog).unwrap()).unwrap();
+    assert_eq!(json["catalog_version"], ternary::cache::CATALOG_VERSION);
+    assert!(!json["profiles"].as_array().unwrap().is_empty());
+
+    std::fs::write(&requests, "{\"type\": \"profile\", \"word\": \"LLmLms\"}\n").unwrap();
+    let output = ternary(&[
+        "batch",
+        "--catalog",
+        catalog_path,
+        requests.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    let response: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(response["result"]["word"], "LLmLms");
+
+    let output = ternary(&["batch", "--catalog", requests.to_str().unwrap()]);
+    assert!(!output.status.success());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
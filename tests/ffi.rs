@@ -0,0 +1,67 @@
+//! Builds the C harness in `tests/ffi/harness.c` against the shared library and runs it.
+
+#![cfg(all(feature = "ffi", target_os = "linux"))]
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The header generated by the build script, in `OUT_DIR`.
+const GENERATED_HEADER: &str = include_str!(concat!(env!("OUT_DIR"), "/ternary.h"));
+
+#[test]
+fn header_is_current() {
+    let header = Path::new(env!("CARGO_MANIFEST_DIR")).join("include/ternary.h");
+    if std::env::var_os("TERNARY_UPDATE_HEADER").is_some() {
+        std::fs::write(&header, GENERATED_HEADER).unwrap();
+    }
+    assert!(
+        std::fs::read_to_string(&header).unwrap() == GENERATED_HEADER,
+        "include/ternary.h is out of date; update it with \
+         `TERNARY_UPDATE_HEADER=1 cargo test --features ffi --test ffi`"
+    );
+}
+
+#[test]
+fn c_harness() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    // The shared library is built next to the test binary, in `target/<profile>/deps`.
+    let lib_dir = std::env::current_exe()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .to_path_buf();
+    assert!(
+        lib_dir.join("libternary.so").exists(),
+        "libternary.so not found in {}",
+        lib_dir.display()
+    );
+    let harness = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("ffi_harness");
+    let status = Command::new(std::env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .args(["-std=c99", "-Wall", "-Wextra", "-Werror"])
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg(manifest_dir.join("tests/ffi/harness.c"))
+        .arg("-o")
+        .arg(&harness)
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-lternary")
+        .status()
+        .expect("failed to run the C compiler");
+    assert!(status.success(), "the C harness failed to compile");
+
+    // Cargo's `LD_LIBRARY_PATH` may list a stale copy of the library built without `ffi` first.
+    let output = Command::new(&harness)
+        .env("LD_LIBRARY_PATH", &lib_dir)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "the C harness failed:\n{stdout}\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.ends_with("ok\n"));
+    assert!(stdout.contains("L = 4\\26"));
+}
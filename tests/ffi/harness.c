@@ -0,0 +1,110 @@
+/* Exercises the C API the way an embedding application would. Built and run by tests/ffi.rs. */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "ternary.h"
+
+#define CHECK(call)                                                                   \
+    do {                                                                              \
+        TernaryStatus status_ = (call);                                               \
+        if (status_ != TERNARY_STATUS_OK) {                                           \
+            fprintf(stderr, "%s:%d: %s: %s\n", __FILE__, __LINE__, #call,             \
+                    ternary_status_message(status_));                                 \
+            return 1;                                                                 \
+        }                                                                             \
+    } while (0)
+
+#define EXPECT(condition)                                                             \
+    do {                                                                              \
+        if (!(condition)) {                                                           \
+            fprintf(stderr, "%s:%d: expected %s\n", __FILE__, __LINE__, #condition);  \
+            return 1;                                                                 \
+        }                                                                             \
+    } while (0)
+
+int main(void) {
+    TernaryWord *word = NULL;
+    CHECK(ternary_word_new("LmLsLmLsL", &word));
+    EXPECT(ternary_word_len(word) == 9);
+
+    TernaryProfile profile;
+    CHECK(ternary_word_profile(word, &profile));
+    EXPECT(profile.step_sig[0] == 5 && profile.step_sig[1] == 2 && profile.step_sig[2] == 2);
+    EXPECT(profile.max_variety == 3);
+    EXPECT(profile.chirality == TERNARY_CHIRALITY_RIGHT);
+    printf("mv %u, guide frame complexity %u\n", profile.max_variety, profile.guide_complexity);
+
+    char *mode = NULL;
+    CHECK(ternary_word_brightest_mode(word, &mode));
+    EXPECT(strcmp(mode, "LLmLsLmLs") == 0);
+    ternary_string_free(mode);
+
+    char *json = NULL;
+    CHECK(ternary_word_profile_json(word, &json));
+    EXPECT(strstr(json, "\"word\":\"LLmLsLmLs\"") != NULL);
+    ternary_string_free(json);
+
+    /* Errors are reported as status codes and leave out-parameters untouched. */
+    TernaryWord *bad = NULL;
+    EXPECT(ternary_word_new("LxL", &bad) == TERNARY_STATUS_INVALID_WORD);
+    EXPECT(bad == NULL);
+    EXPECT(ternary_word_profile(NULL, &profile) == TERNARY_STATUS_NULL_POINTER);
+
+    TernaryTuningList *ed_tunings = NULL;
+    CHECK(ternary_ed_tunings(word, 2, 1, 30, 20.0, 250.0, &ed_tunings));
+    EXPECT(ternary_tuning_list_len(ed_tunings) == 4);
+    TernaryTuning *ed_tuning = NULL;
+    CHECK(ternary_tuning_list_get(ed_tunings, 1, &ed_tuning));
+    char *step = NULL;
+    CHECK(ternary_tuning_step_string(ed_tuning, 0, &step));
+    printf("L = %s\n", step);
+    EXPECT(strcmp(step, "4\\26") == 0);
+    ternary_string_free(step);
+
+    TernaryTuningList *ji_tunings = NULL;
+    CHECK(ternary_ji_tunings(word, 2, 1, 20.0, 250.0, &ji_tunings));
+    EXPECT(ternary_tuning_list_len(ji_tunings) > 0);
+    ternary_tuning_list_free(ji_tunings);
+
+    const char *steps[] = {"9/8", "28/27", "64/63"};
+    TernaryTuning *ji_tuning = NULL;
+    CHECK(ternary_tuning_new(steps, 3, &ji_tuning));
+    double cents = 0.0;
+    CHECK(ternary_tuning_step_cents(ji_tuning, 0, &cents));
+    EXPECT(cents > 203.9 && cents < 204.0);
+    EXPECT(ternary_tuning_step_cents(ji_tuning, 3, &cents) == TERNARY_STATUS_OUT_OF_RANGE);
+
+    const char *cents_steps[] = {"203.9c", "150c", "90c"};
+    TernaryTuning *cents_tuning = NULL;
+    CHECK(ternary_tuning_new(cents_steps, 3, &cents_tuning));
+    CHECK(ternary_tuning_step_cents(cents_tuning, 1, &cents));
+    EXPECT(cents == 150.0);
+    ternary_tuning_free(cents_tuning);
+
+    char *scl = NULL;
+    CHECK(ternary_scl(word, ji_tuning, &scl));
+    EXPECT(strstr(scl, " 9\n 9/8\n") != NULL);
+    fputs(scl, stdout);
+    ternary_string_free(scl);
+
+    TernaryKeyboardMapping mapping = ternary_keyboard_mapping_default();
+    mapping.reference_frequency = 256.0;
+    uint8_t *sysex = NULL;
+    size_t sysex_len = 0;
+    CHECK(ternary_mts_bulk_dump(word, ed_tuning, &mapping, 0x7F, 0, "diasem", &sysex, &sysex_len));
+    EXPECT(sysex_len == 408 && sysex[0] == 0xF0 && sysex[sysex_len - 1] == 0xF7);
+    ternary_bytes_free(sysex, sysex_len);
+
+    mapping.root_note = 200;
+    EXPECT(ternary_mts_bulk_dump(word, ed_tuning, &mapping, 0x7F, 0, NULL, &sysex, &sysex_len)
+           == TERNARY_STATUS_INVALID_ARGUMENT);
+
+    ternary_tuning_free(ji_tuning);
+
+    ternary_tuning_free(ed_tuning);
+    ternary_tuning_list_free(ed_tunings);
+    ternary_word_free(word);
+    puts("ok");
+    return 0;
+}
@@ -0,0 +1,86 @@
+//! Round trips of the result types through JSON.
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use ternary::ji_ratio::RawJiRatio;
+use ternary::{
+    PageQuery, ProfileSort, SCHEMA_VERSION, SigFilter, SigResult, Versioned, WordResult,
+    sig_to_result, string_to_numbers, word_to_lattice_result, word_to_result,
+};
+
+fn round_trip<T: Serialize + DeserializeOwned>(value: &T) -> T {
+    serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+}
+
+#[test]
+fn word_result() {
+    let result = word_to_result(
+        &string_to_numbers("LmLsLmLsL"),
+        RawJiRatio::OCTAVE,
+        53,
+        20.0,
+        250.0,
+    );
+    assert_eq!(round_trip(&result), result);
+}
+
+#[test]
+fn sig_result() {
+    let filter = SigFilter {
+        mv: 3,
+        ..Default::default()
+    };
+    let result = sig_to_result(&[3, 2, 2], &filter, RawJiRatio::TRITAVE, 40, 20.0, 250.0);
+    assert!(!result.profiles.is_empty());
+    assert_eq!(round_trip(&result), result);
+}
+
+#[test]
+fn profile_page() {
+    let result = sig_to_result(
+        &[4, 2, 1],
+        &SigFilter::default(),
+        RawJiRatio::OCTAVE,
+        40,
+        20.0,
+        250.0,
+    );
+    let query: PageQuery = serde_json::from_str(r#"{"limit": 5, "sort": "guide_frames"}"#).unwrap();
+    assert_eq!((query.offset, query.sort), (0, ProfileSort::GuideFrames));
+    let page = result.page(&query);
+    assert_eq!(page.profiles.len(), 5);
+    assert_eq!(round_trip(&page), page);
+}
+
+#[test]
+fn lattice_result() {
+    let result = word_to_lattice_result(&string_to_numbers("LLmLLms")).unwrap();
+    assert_eq!(round_trip(&result), result);
+    let result = word_to_lattice_result(&string_to_numbers("LmsLms")).unwrap();
+    assert!(result.fallback.is_some());
+    assert_eq!(round_trip(&result), result);
+}
+
+#[test]
+fn versioned() {
+    let result = word_to_result(
+        &string_to_numbers("LLmLLms"),
+        RawJiRatio::OCTAVE,
+        30,
+        20.0,
+        250.0,
+    );
+    let json = serde_json::to_value(Versioned::new(result.clone())).unwrap();
+    assert_eq!(json["schema_version"], SCHEMA_VERSION);
+    assert_eq!(json["profile"]["word"], "LLmLLms");
+
+    let mut stored: Versioned<WordResult> = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(stored.clone().into_current(), Ok(result));
+    stored.schema_version = SCHEMA_VERSION + 1;
+    let mismatch = stored.into_current().unwrap_err();
+    assert_eq!(mismatch.found, SCHEMA_VERSION + 1);
+
+    // Results of another kind don't load.
+    assert!(serde_json::from_value::<Versioned<SigResult>>(json).is_err());
+}